@@ -0,0 +1,161 @@
+use solana_client::client_error::ClientError;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use spl_token_2022::extension::BaseStateWithExtensions;
+use spl_token_2022::extension::ExtensionType;
+use spl_token_2022::extension::StateWithExtensions;
+use spl_token_2022::state::Mint;
+use spl_token_metadata_interface::state::TokenMetadata as TokenMetadataExtension;
+
+pub static PUMPFUN_DECIMALS: u8 = 6;
+pub static PUMPFUN_SUPPLY: u64 = 1_000_000_000_000_000;
+static TOKEN_METADATA_PROGRAM_ID: Pubkey = pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
+pub struct TokenMetadata {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String
+}
+
+pub enum TokenWarning {
+    MintAuthority(Pubkey),
+    FreezeAuthority(Pubkey),
+    Decimals(u8),
+    Supply(u64),
+    Extension(ExtensionType),
+    MissingMetadata
+}
+
+impl ToString for TokenWarning {
+    fn to_string(&self) -> String {
+        match self {
+            Self::MintAuthority(authority) => format!("mint authority is active ({}), more supply can be minted", authority),
+            Self::FreezeAuthority(authority) => format!("freeze authority is active ({}), token accounts can be frozen", authority),
+            Self::Decimals(decimals) => format!("non-standard decimals {} (pump.fun uses {})", decimals, PUMPFUN_DECIMALS),
+            Self::Supply(supply) => format!("non-standard supply {} (pump.fun uses {})", supply, PUMPFUN_SUPPLY),
+            Self::Extension(extension) => format!("unusual token-2022 extension {:?}", extension),
+            Self::MissingMetadata => "no token metadata found".to_string()
+        }
+    }
+}
+
+pub struct TokenReport {
+    pub mint_id: Pubkey,
+    pub token_program: Pubkey,
+    pub decimals: u8,
+    pub supply: u64,
+    pub metadata: Option<TokenMetadata>,
+    pub warnings: Vec<TokenWarning>
+}
+
+impl TokenReport {
+    pub fn ui_supply(&self) -> f64 {
+        self.supply as f64 / 10f64.powi(self.decimals as i32)
+    }
+}
+
+/// Read-only inspection of a mint and its metadata, flagging anything that differs from a
+/// standard pump.fun token.
+pub async fn analyze_token(client: &RpcClient, mint_id: &Pubkey) -> Result<TokenReport, TokenAnalysisError> {
+    let metadata_address = Pubkey::find_program_address(
+        &[b"metadata", TOKEN_METADATA_PROGRAM_ID.as_ref(), mint_id.as_ref()],
+        &TOKEN_METADATA_PROGRAM_ID
+    ).0;
+
+    let mut accounts = client.get_multiple_accounts(&[*mint_id, metadata_address]).await?.into_iter();
+    let mint_account = accounts.next().flatten().ok_or(TokenAnalysisError::AccountNotFound(*mint_id))?;
+    let metadata_account = accounts.next().flatten();
+
+    if mint_account.owner != spl_token::id() && mint_account.owner != spl_token_2022::id() {
+        return Err(TokenAnalysisError::NotAMint(*mint_id));
+    }
+
+    let mint = StateWithExtensions::<Mint>::unpack(&mint_account.data)
+        .map_err(|_| TokenAnalysisError::NotAMint(*mint_id))?;
+
+    let mut warnings = vec![];
+
+    if let Some(authority) = Option::<Pubkey>::from(mint.base.mint_authority) {
+        warnings.push(TokenWarning::MintAuthority(authority));
+    }
+    if let Some(authority) = Option::<Pubkey>::from(mint.base.freeze_authority) {
+        warnings.push(TokenWarning::FreezeAuthority(authority));
+    }
+    if mint.base.decimals != PUMPFUN_DECIMALS {
+        warnings.push(TokenWarning::Decimals(mint.base.decimals));
+    }
+    if mint.base.supply != PUMPFUN_SUPPLY {
+        warnings.push(TokenWarning::Supply(mint.base.supply));
+    }
+
+    for extension in mint.get_extension_types().unwrap_or_default() {
+        if is_unusual_extension(extension) {
+            warnings.push(TokenWarning::Extension(extension));
+        }
+    }
+
+    let metadata = metadata_account
+        .and_then(|account| decode_metadata(&account.data))
+        .or_else(|| mint.get_variable_len_extension::<TokenMetadataExtension>().ok().map(|metadata| TokenMetadata {
+            name: metadata.name,
+            symbol: metadata.symbol,
+            uri: metadata.uri
+        }));
+
+    if metadata.is_none() {
+        warnings.push(TokenWarning::MissingMetadata);
+    }
+
+    Ok(TokenReport {
+        mint_id: *mint_id,
+        token_program: mint_account.owner,
+        decimals: mint.base.decimals,
+        supply: mint.base.supply,
+        metadata,
+        warnings
+    })
+}
+
+/// Extensions a pump.fun token can carry without changing how it trades.
+fn is_unusual_extension(extension: ExtensionType) -> bool {
+    !matches!(
+        extension,
+        ExtensionType::Uninitialized
+            | ExtensionType::MetadataPointer
+            | ExtensionType::TokenMetadata
+            | ExtensionType::GroupPointer
+            | ExtensionType::TokenGroup
+            | ExtensionType::GroupMemberPointer
+            | ExtensionType::TokenGroupMember
+    )
+}
+
+/// Decodes name, symbol and uri from a Metaplex metadata account. They follow the key (1 byte),
+/// update authority and mint, as borsh strings padded with null bytes.
+fn decode_metadata(data: &[u8]) -> Option<TokenMetadata> {
+    let mut offset = 1 + 32 + 32;
+    let mut read_string = || -> Option<String> {
+        let length = u32::from_le_bytes(data.get(offset..offset + 4)?.try_into().ok()?) as usize;
+        let value = data.get(offset + 4..offset + 4 + length)?;
+        offset += 4 + length;
+
+        Some(String::from_utf8_lossy(value).trim_end_matches('\0').trim().to_string())
+    };
+
+    Some(TokenMetadata {
+        name: read_string()?,
+        symbol: read_string()?,
+        uri: read_string()?
+    })
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum TokenAnalysisError {
+    #[error("Solana client error: {0}")]
+    ClientError(#[from] ClientError),
+    #[error("Account {0} was not found")]
+    AccountNotFound(Pubkey),
+    #[error("{0} is not a token mint")]
+    NotAMint(Pubkey)
+}
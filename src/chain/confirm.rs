@@ -0,0 +1,176 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+use serde::Deserialize;
+use serde::Serialize;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::message::VersionedMessage;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_transaction_status::option_serializer::OptionSerializer;
+use solana_transaction_status::TransactionConfirmationStatus;
+use solana_transaction_status::UiTransactionEncoding;
+use solana_transaction_status::UiTransactionStatusMeta;
+use tokio::time::sleep_until;
+use tokio::time::Instant;
+
+static CONFIRMATION_TIMEOUT: Duration = Duration::from_secs(60);
+static POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// Recent signatures of each sniper searched for its snipe buy.
+static SNIPE_LOOKBACK_SIGNATURES: usize = 10;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub enum ConfirmationStatus {
+    Pending,
+    Confirmed,
+    Finalized,
+    Failed { error: String, logs: Vec<String> }
+}
+
+impl ToString for ConfirmationStatus {
+    fn to_string(&self) -> String {
+        match self {
+            Self::Pending => "unconfirmed".to_string(),
+            Self::Confirmed => "confirmed".to_string(),
+            Self::Finalized => "finalized".to_string(),
+            Self::Failed { error, .. } => format!("failed - {}", error)
+        }
+    }
+}
+
+/// Polls the signatures until each one is confirmed, finalized or failed. Signatures still
+/// unresolved after the timeout are returned as `Pending`.
+pub async fn track_signatures(client: &RpcClient, signatures: &[Signature]) -> Vec<ConfirmationStatus> {
+    let mut statuses = vec![ConfirmationStatus::Pending; signatures.len()];
+    let deadline = Instant::now() + CONFIRMATION_TIMEOUT;
+
+    while Instant::now() < deadline {
+        let pending: Vec<usize> = statuses.iter().enumerate()
+            .filter(|(_, status)| matches!(status, ConfirmationStatus::Pending))
+            .map(|(index, _)| index)
+            .collect();
+
+        if pending.is_empty() {
+            break;
+        }
+
+        let pending_signatures: Vec<Signature> = pending.iter().map(|index| signatures[*index]).collect();
+        if let Ok(response) = client.get_signature_statuses(&pending_signatures).await {
+            for (index, status) in pending.into_iter().zip(response.value) {
+                let Some(status) = status else { continue };
+
+                if let Some(err) = status.err {
+                    statuses[index] = ConfirmationStatus::Failed {
+                        error: err.to_string(),
+                        logs: get_transaction_logs(client, &signatures[index]).await
+                    };
+                    continue;
+                }
+
+                match status.confirmation_status {
+                    Some(TransactionConfirmationStatus::Finalized) => statuses[index] = ConfirmationStatus::Finalized,
+                    Some(TransactionConfirmationStatus::Confirmed) => statuses[index] = ConfirmationStatus::Confirmed,
+                    _ => {}
+                }
+            }
+        }
+
+        sleep_until(Instant::now() + POLL_INTERVAL).await;
+    }
+
+    statuses
+}
+
+async fn get_landed_transaction(client: &RpcClient, signature: &Signature) -> Option<(VersionedMessage, UiTransactionStatusMeta)> {
+    let config = RpcTransactionConfig {
+        encoding: Some(UiTransactionEncoding::Base64),
+        commitment: Some(CommitmentConfig::confirmed()),
+        max_supported_transaction_version: Some(0)
+    };
+
+    let transaction = client.get_transaction_with_config(signature, config).await.ok()?.transaction;
+    let meta = transaction.meta?;
+    let decoded = transaction.transaction.decode()?;
+
+    Some((decoded.message, meta))
+}
+
+fn balance_change(message: &VersionedMessage, meta: &UiTransactionStatusMeta, accounts: &[Pubkey]) -> i64 {
+    message.static_account_keys().iter().enumerate()
+        .filter(|(_, key)| accounts.contains(key))
+        .filter_map(|(index, _)| Some(*meta.post_balances.get(index)? as i64 - *meta.pre_balances.get(index)? as i64))
+        .sum()
+}
+
+/// Net lamport change of `accounts` in a landed transaction, fees included. Used to price sells
+/// after the fact since the backend only returns their signature.
+pub async fn get_balance_change(client: &RpcClient, signature: &Signature, accounts: &[Pubkey]) -> Option<i64> {
+    let (message, meta) = get_landed_transaction(client, signature).await?;
+
+    Some(balance_change(&message, &meta, accounts))
+}
+
+/// First of `wallets` that signed a landed transaction. Auto trades only return signatures, this
+/// tells which sniper each one belongs to.
+pub async fn get_signer(client: &RpcClient, signature: &Signature, wallets: &[Pubkey]) -> Option<Pubkey> {
+    let (message, _) = get_landed_transaction(client, signature).await?;
+    let signers = message.header().num_required_signatures as usize;
+
+    message.static_account_keys().iter()
+        .take(signers)
+        .find(|key| wallets.contains(key))
+        .copied()
+}
+
+/// Buys of `mint` by `wallets` that landed after `since`, as the wallet, its signature and the
+/// lamports it spent. Snipes fill on the backend without returning signatures, so the buys are
+/// looked up in the recent history of each wallet.
+pub async fn find_snipe_buys(client: &RpcClient, mint: &Pubkey, wallets: &[Pubkey], since: u64) -> Vec<(Pubkey, Signature, u64)> {
+    let mut buys = vec![];
+    for wallet in wallets {
+        let config = GetConfirmedSignaturesForAddress2Config {
+            limit: Some(SNIPE_LOOKBACK_SIGNATURES),
+            commitment: Some(CommitmentConfig::confirmed()),
+            ..Default::default()
+        };
+        let Ok(statuses) = client.get_signatures_for_address_with_config(wallet, config).await else { continue };
+
+        let landed = statuses.into_iter()
+            .filter(|status| status.err.is_none() && status.block_time.map_or(true, |block_time| block_time as u64 >= since))
+            .filter_map(|status| Signature::from_str(&status.signature).ok());
+
+        for signature in landed {
+            let Some((message, meta)) = get_landed_transaction(client, &signature).await else { continue };
+            if !message.static_account_keys().contains(mint) {
+                continue;
+            }
+
+            let change = balance_change(&message, &meta, &[*wallet]);
+            if change < 0 {
+                buys.push((*wallet, signature, change.unsigned_abs()));
+                break;
+            }
+        }
+    }
+
+    buys
+}
+
+async fn get_transaction_logs(client: &RpcClient, signature: &Signature) -> Vec<String> {
+    let config = RpcTransactionConfig {
+        encoding: Some(UiTransactionEncoding::Json),
+        commitment: Some(CommitmentConfig::confirmed()),
+        max_supported_transaction_version: Some(0)
+    };
+
+    match client.get_transaction_with_config(signature, config).await {
+        Ok(transaction) => match transaction.transaction.meta.map(|meta| meta.log_messages) {
+            Some(OptionSerializer::Some(logs)) => logs,
+            _ => vec![]
+        },
+        Err(_) => vec![]
+    }
+}
@@ -0,0 +1,193 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+use serde::Deserialize;
+use serde::Serialize;
+use solana_client::client_error::ClientError;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_transaction_status::UiTransactionEncoding;
+use tokio::time::interval;
+
+use crate::storage::transactions::unix_timestamp;
+
+use super::pumpfun::bonding_curve_address;
+use super::pumpfun::get_bonding_curve;
+use super::pumpfun::CREATE_DISCRIMINATOR;
+use super::pumpfun::PUMPFUN_PROGRAM_ID;
+
+/// Upper bound on signatures fetched per scan, older history is picked up by later scans.
+pub static MAX_SCANNED_SIGNATURES: usize = 1000;
+/// Spacing between transaction lookups, keeps a long scan under public RPC rate limits.
+static SCAN_REQUEST_INTERVAL: Duration = Duration::from_millis(100);
+/// A curve without trades for this long is considered abandoned.
+static ABANDONED_AFTER: Duration = Duration::from_secs(60 * 60 * 24);
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub enum TokenState {
+    Active,
+    Migrated,
+    Abandoned,
+    Unknown
+}
+
+impl ToString for TokenState {
+    fn to_string(&self) -> String {
+        match self {
+            Self::Active => "active".to_string(),
+            Self::Migrated => "migrated".to_string(),
+            Self::Abandoned => "abandoned".to_string(),
+            Self::Unknown => "unknown".to_string()
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DeployedToken {
+    #[serde(with = "crate::storage::pubkey_string")]
+    pub mint_id: Pubkey,
+    pub signature: String,
+    pub created_at: Option<i64>,
+    pub state: TokenState
+}
+
+pub struct DeployerScan {
+    pub tokens: Vec<DeployedToken>,
+    /// Newest signature scanned, later scans stop there.
+    pub newest_signature: Option<String>,
+    /// Signatures older than this were not scanned because the scan hit `MAX_SCANNED_SIGNATURES`
+    /// or stopped on `error`.
+    pub oldest_signature: Option<String>,
+    /// Request that ended the scan early, the signatures scanned before it are still in the scan.
+    pub error: Option<ClientError>
+}
+
+/// Walks the deployer's signature history newest first, between `before` and `until`, and picks
+/// out the transactions the deployer signed that call pump.fun `create`. A failing request ends
+/// the scan where it got to, it only errors when not a single signature was scanned.
+pub async fn scan_deployer(
+    client: &RpcClient,
+    deployer: &Pubkey,
+    mut before: Option<Signature>,
+    until: Option<Signature>
+) -> Result<DeployerScan, ClientError> {
+    let mut scan = DeployerScan { tokens: vec![], newest_signature: None, oldest_signature: None, error: None };
+    let mut throttle = interval(SCAN_REQUEST_INTERVAL);
+    let mut scanned = 0;
+
+    'pages: while scanned < MAX_SCANNED_SIGNATURES {
+        let config = GetConfirmedSignaturesForAddress2Config {
+            before,
+            until,
+            limit: Some((MAX_SCANNED_SIGNATURES - scanned).min(1000)),
+            commitment: Some(CommitmentConfig::confirmed())
+        };
+
+        throttle.tick().await;
+        let signatures = match client.get_signatures_for_address_with_config(deployer, config).await {
+            Ok(signatures) => signatures,
+            Err(err) => {
+                scan.error = Some(err);
+                break;
+            }
+        };
+        if signatures.is_empty() {
+            scan.oldest_signature = None;
+            break;
+        }
+
+        for status in signatures.iter() {
+            if let (None, Ok(signature)) = (&status.err, Signature::from_str(&status.signature)) {
+                throttle.tick().await;
+                match get_created_mint(client, deployer, &signature).await {
+                    Ok(Some(mint_id)) => scan.tokens.push(DeployedToken {
+                        mint_id,
+                        signature: status.signature.clone(),
+                        created_at: status.block_time,
+                        state: TokenState::Unknown
+                    }),
+                    Ok(None) => {},
+                    Err(err) => {
+                        scan.error = Some(err);
+                        break 'pages;
+                    }
+                }
+            }
+
+            scanned += 1;
+            if scan.newest_signature.is_none() {
+                scan.newest_signature = Some(status.signature.clone());
+            }
+            scan.oldest_signature = Some(status.signature.clone());
+        }
+
+        before = scan.oldest_signature.as_ref().and_then(|signature| Signature::from_str(signature).ok());
+    }
+
+    match scan.error.take() {
+        Some(err) if scan.newest_signature.is_none() => Err(err),
+        error => Ok(DeployerScan { error, ..scan })
+    }
+}
+
+async fn get_created_mint(client: &RpcClient, deployer: &Pubkey, signature: &Signature) -> Result<Option<Pubkey>, ClientError> {
+    let config = RpcTransactionConfig {
+        encoding: Some(UiTransactionEncoding::Base64),
+        commitment: Some(CommitmentConfig::confirmed()),
+        max_supported_transaction_version: Some(0)
+    };
+
+    let Some(transaction) = client.get_transaction_with_config(signature, config).await?.transaction.transaction.decode() else {
+        return Ok(None)
+    };
+
+    let account_keys = transaction.message.static_account_keys();
+    if account_keys.first() != Some(deployer) {
+        return Ok(None)
+    }
+
+    for instruction in transaction.message.instructions() {
+        if account_keys.get(instruction.program_id_index as usize) != Some(&PUMPFUN_PROGRAM_ID) {
+            continue;
+        }
+        if !instruction.data.starts_with(&CREATE_DISCRIMINATOR) {
+            continue;
+        }
+
+        return Ok(instruction.accounts.first().and_then(|index| account_keys.get(*index as usize)).copied())
+    }
+
+    Ok(None)
+}
+
+/// Classifies a pump.fun token from its bonding curve: migrated once the curve completes,
+/// abandoned when nobody traded on it for `ABANDONED_AFTER`.
+pub async fn get_token_state(client: &RpcClient, mint_id: &Pubkey) -> Result<TokenState, ClientError> {
+    let bonding_curve = bonding_curve_address(mint_id);
+    let Some(curve) = get_bonding_curve(client, mint_id).await? else {
+        return Ok(TokenState::Unknown)
+    };
+
+    if curve.complete {
+        return Ok(TokenState::Migrated)
+    }
+
+    let config = GetConfirmedSignaturesForAddress2Config {
+        before: None,
+        until: None,
+        limit: Some(1),
+        commitment: Some(CommitmentConfig::confirmed())
+    };
+    let last_activity = client.get_signatures_for_address_with_config(&bonding_curve, config).await?
+        .first()
+        .and_then(|status| status.block_time);
+
+    match last_activity {
+        Some(block_time) if unix_timestamp().saturating_sub(block_time as u64) < ABANDONED_AFTER.as_secs() => Ok(TokenState::Active),
+        _ => Ok(TokenState::Abandoned)
+    }
+}
@@ -0,0 +1,69 @@
+use std::str::FromStr;
+
+use solana_client::client_error::ClientError;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use spl_token_2022::extension::StateWithExtensions;
+use spl_token_2022::state::Account as TokenAccountState;
+
+/// Share of supply the largest outside holders can reach before a token is flagged as concentrated.
+pub static CONCENTRATION_THRESHOLD: f64 = 0.5;
+pub static CONCENTRATION_TOP_HOLDERS: usize = 5;
+
+pub struct Holder {
+    pub token_account: Pubkey,
+    pub owner: Option<Pubkey>,
+    pub amount: u64
+}
+
+pub struct HolderReport {
+    pub supply: u64,
+    pub decimals: u8,
+    pub holders: Vec<Holder>
+}
+
+impl HolderReport {
+    pub fn share(&self, holder: &Holder) -> f64 {
+        if self.supply == 0 {
+            return 0f64;
+        }
+
+        holder.amount as f64 / self.supply as f64
+    }
+    /// Combined share of the largest holders not matched by `is_own`.
+    pub fn outside_concentration(&self, is_own: impl Fn(&Holder) -> bool) -> f64 {
+        self.holders.iter()
+            .filter(|holder| !is_own(holder))
+            .take(CONCENTRATION_TOP_HOLDERS)
+            .map(|holder| self.share(holder))
+            .sum()
+    }
+}
+
+/// Fetches the largest token accounts of a mint (the RPC returns up to 20) together with their owners.
+pub async fn get_holders(client: &RpcClient, mint_id: &Pubkey) -> Result<HolderReport, ClientError> {
+    let supply = client.get_token_supply(mint_id).await?;
+    let largest_accounts = client.get_token_largest_accounts(mint_id).await?;
+
+    let token_accounts: Vec<(Pubkey, u64)> = largest_accounts.iter()
+        .filter_map(|balance| Some((
+            Pubkey::from_str(&balance.address).ok()?,
+            balance.amount.amount.parse().ok()?
+        )))
+        .collect();
+
+    let addresses: Vec<Pubkey> = token_accounts.iter().map(|(address, _)| *address).collect();
+    let accounts = client.get_multiple_accounts(&addresses).await?;
+
+    let holders = token_accounts.into_iter().zip(accounts).map(|((token_account, amount), account)| Holder {
+        token_account,
+        owner: account.and_then(|account| StateWithExtensions::<TokenAccountState>::unpack(&account.data).ok().map(|state| state.base.owner)),
+        amount
+    }).collect();
+
+    Ok(HolderReport {
+        supply: supply.amount.parse().unwrap_or(0),
+        decimals: supply.decimals,
+        holders
+    })
+}
@@ -0,0 +1,48 @@
+use solana_client::client_error::ClientError;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::message::Message;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::system_instruction;
+
+pub mod analysis;
+pub mod confirm;
+pub mod deployer;
+pub mod holders;
+pub mod pumpfun;
+pub mod token;
+pub mod verify;
+
+static DEFAULT_SOLANA_RPC_URL: &str = "https://api.mainnet-beta.solana.com";
+static DEFAULT_EXPLORER_URL: &str = "https://solscan.io/tx/{signature}";
+
+pub fn solana_rpc_url() -> String {
+    std::env::var("SOLANA_RPC_URL").unwrap_or_else(|_| DEFAULT_SOLANA_RPC_URL.to_string())
+}
+
+/// Builds the explorer link for a signature from the `EXPLORER_URL` template.
+pub fn explorer_url(signature: &str) -> String {
+    std::env::var("EXPLORER_URL")
+        .unwrap_or_else(|_| DEFAULT_EXPLORER_URL.to_string())
+        .replace("{signature}", signature)
+}
+
+pub fn new_solana_client() -> RpcClient {
+    RpcClient::new_with_commitment(solana_rpc_url(), CommitmentConfig::confirmed())
+}
+
+/// Fee the RPC quotes for a SOL transfer signed by `from` at the latest blockhash, kept back when a
+/// wallet is emptied.
+pub async fn get_transfer_fee(client: &RpcClient, from: &Pubkey) -> Result<u64, ClientError> {
+    let blockhash = client.get_latest_blockhash().await?;
+    let message = Message::new_with_blockhash(&[system_instruction::transfer(from, from, 0)], Some(from), &blockhash);
+
+    client.get_fee_for_message(&message).await
+}
+
+/// Enables the on-chain balance check in the sync loop. Any non-empty value other than `false` or `0` turns it on.
+pub fn verify_balances_enabled() -> bool {
+    std::env::var("SOLANA_VERIFY_BALANCES")
+        .map(|value| !value.is_empty() && value != "false" && value != "0")
+        .unwrap_or(false)
+}
@@ -0,0 +1,327 @@
+use serde::Deserialize;
+use serde::Serialize;
+use solana_client::client_error::ClientError;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+
+use super::analysis::PUMPFUN_DECIMALS;
+
+pub static PUMPFUN_PROGRAM_ID: Pubkey = pubkey!("6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P");
+/// Anchor discriminator of the pump.fun `create` instruction.
+pub static CREATE_DISCRIMINATOR: [u8; 8] = [24, 30, 200, 40, 5, 28, 7, 119];
+/// Real token reserves a fresh curve starts with, the curve completes once they are sold.
+pub static INITIAL_REAL_TOKEN_RESERVES: u64 = 793_100_000_000_000;
+pub static INITIAL_VIRTUAL_TOKEN_RESERVES: u64 = 1_073_000_000_000_000;
+pub static INITIAL_VIRTUAL_SOL_RESERVES: u64 = 30 * LAMPORTS_PER_SOL;
+pub static TOKEN_TOTAL_SUPPLY: u64 = 1_000_000_000_000_000;
+/// Fee pump.fun takes on both sides of a curve trade, in basis points.
+pub static TRADE_FEE_BPS: u64 = 100;
+
+pub fn bonding_curve_address(mint_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"bonding-curve", mint_id.as_ref()], &PUMPFUN_PROGRAM_ID).0
+}
+
+/// Extracts the mint from a bare address or a pump.fun link such as
+/// `https://pump.fun/coin/<mint>?include-nsfw=true`.
+pub fn parse_mint_input(input: &str) -> Option<Pubkey> {
+    let input = input.trim();
+    if let Ok(mint_id) = Pubkey::from_str(input) {
+        return Some(mint_id)
+    }
+
+    let path = input.split(['?', '#']).next()?;
+    let host_and_path = path.trim_start_matches("https://").trim_start_matches("http://").trim_start_matches("www.");
+    if !host_and_path.starts_with("pump.fun/") {
+        return None
+    }
+
+    Pubkey::from_str(host_and_path.trim_end_matches('/').rsplit('/').next()?).ok()
+}
+
+/// Fetches and decodes the bonding curve of `mint_id`, `None` if the account does not exist.
+pub async fn get_bonding_curve(client: &RpcClient, mint_id: &Pubkey) -> Result<Option<BondingCurve>, ClientError> {
+    let account = client.get_account_with_commitment(&bonding_curve_address(mint_id), client.commitment()).await?.value;
+    Ok(account.and_then(|account| BondingCurve::decode(&account.data)))
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct BondingCurve {
+    pub virtual_token_reserves: u64,
+    pub virtual_sol_reserves: u64,
+    pub real_token_reserves: u64,
+    pub real_sol_reserves: u64,
+    pub token_total_supply: u64,
+    pub complete: bool
+}
+
+impl BondingCurve {
+    /// Decodes the bonding curve account, five little endian u64 reserves and the `complete` flag
+    /// following the 8 byte account discriminator.
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        let read_u64 = |index: usize| -> Option<u64> {
+            let offset = 8 + index * 8;
+            Some(u64::from_le_bytes(data.get(offset..offset + 8)?.try_into().ok()?))
+        };
+
+        Some(Self {
+            virtual_token_reserves: read_u64(0)?,
+            virtual_sol_reserves: read_u64(1)?,
+            real_token_reserves: read_u64(2)?,
+            real_sol_reserves: read_u64(3)?,
+            token_total_supply: read_u64(4)?,
+            complete: *data.get(48)? != 0
+        })
+    }
+
+    /// Spot price of one whole token in SOL, from the virtual reserves.
+    pub fn price(&self) -> f64 {
+        if self.virtual_token_reserves == 0 {
+            return 0.0
+        }
+
+        let sol = self.virtual_sol_reserves as f64 / LAMPORTS_PER_SOL as f64;
+        let tokens = self.virtual_token_reserves as f64 / 10f64.powi(PUMPFUN_DECIMALS as i32);
+        sol / tokens
+    }
+
+    pub fn market_cap(&self) -> f64 {
+        self.price() * self.token_total_supply as f64 / 10f64.powi(PUMPFUN_DECIMALS as i32)
+    }
+
+    /// Percentage of the initial real token reserves already sold.
+    pub fn progress(&self) -> f64 {
+        if self.complete {
+            return 100.0
+        }
+
+        let sold = INITIAL_REAL_TOKEN_RESERVES.saturating_sub(self.real_token_reserves);
+        sold as f64 / INITIAL_REAL_TOKEN_RESERVES as f64 * 100.0
+    }
+
+    /// The curve of a token that was just launched.
+    pub fn launch() -> Self {
+        Self {
+            virtual_token_reserves: INITIAL_VIRTUAL_TOKEN_RESERVES,
+            virtual_sol_reserves: INITIAL_VIRTUAL_SOL_RESERVES,
+            real_token_reserves: INITIAL_REAL_TOKEN_RESERVES,
+            real_sol_reserves: 0,
+            token_total_supply: TOKEN_TOTAL_SUPPLY,
+            complete: false
+        }
+    }
+
+    /// Raw tokens `lamports` buy after the fee, from the constant product of the virtual reserves
+    /// and capped by the real token reserves.
+    pub fn buy_quote(&self, lamports: u64) -> u64 {
+        let sol_in = (lamports - fee(lamports)) as u128;
+        let product = self.virtual_sol_reserves as u128 * self.virtual_token_reserves as u128;
+        let tokens_left = product / (self.virtual_sol_reserves as u128 + sol_in) + 1;
+        let tokens_out = (self.virtual_token_reserves as u128).saturating_sub(tokens_left) as u64;
+
+        tokens_out.min(self.real_token_reserves)
+    }
+
+    /// Lamports selling `tokens` returns after the fee, capped by the real SOL reserves.
+    pub fn sell_quote(&self, tokens: u64) -> u64 {
+        let sol_out = self.gross_sell_quote(tokens);
+        sol_out - fee(sol_out)
+    }
+
+    fn gross_sell_quote(&self, tokens: u64) -> u64 {
+        let product = self.virtual_sol_reserves as u128 * self.virtual_token_reserves as u128;
+        let sol_left = product / (self.virtual_token_reserves as u128 + tokens as u128) + 1;
+        let sol_out = (self.virtual_sol_reserves as u128).saturating_sub(sol_left) as u64;
+
+        sol_out.min(self.real_sol_reserves)
+    }
+
+    /// Buys for up to `lamports` against the curve and returns the tokens bought and the lamports
+    /// spent, fee included. The curve completes once its real token reserves run out, a buy that
+    /// asks for more only pays for the tokens left and the rest is refunded like on pump.fun.
+    pub fn apply_buy(&mut self, lamports: u64) -> (u64, u64) {
+        let tokens_out = self.buy_quote(lamports);
+        let mut sol_in = lamports - fee(lamports);
+        let mut spent = lamports;
+        if tokens_out == self.real_token_reserves {
+            sol_in = sol_in.min(self.buy_cost(tokens_out));
+            spent = spent.min(sol_in + fee(sol_in));
+        }
+
+        self.virtual_sol_reserves += sol_in;
+        self.real_sol_reserves += sol_in;
+        self.virtual_token_reserves -= tokens_out;
+        self.real_token_reserves -= tokens_out;
+        self.complete = self.real_token_reserves == 0;
+
+        (tokens_out, spent)
+    }
+
+    /// Lamports before the fee that buy exactly `tokens` from the virtual reserves.
+    fn buy_cost(&self, tokens: u64) -> u64 {
+        let product = self.virtual_sol_reserves as u128 * tokens as u128;
+        let tokens_left = (self.virtual_token_reserves as u128).saturating_sub(tokens as u128).max(1);
+
+        (product / tokens_left + 1) as u64
+    }
+
+    /// Sells `tokens` against the curve and returns the lamports received after the fee.
+    pub fn apply_sell(&mut self, tokens: u64) -> u64 {
+        let sol_out = self.gross_sell_quote(tokens);
+
+        self.virtual_sol_reserves -= sol_out;
+        self.real_sol_reserves -= sol_out;
+        self.virtual_token_reserves += tokens;
+        self.real_token_reserves += tokens;
+
+        sol_out - fee(sol_out)
+    }
+}
+
+fn fee(lamports: u64) -> u64 {
+    lamports * TRADE_FEE_BPS / 10_000
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(curve: &BondingCurve) -> Vec<u8> {
+        let mut data = vec![0u8; 8];
+        data.extend(curve.virtual_token_reserves.to_le_bytes());
+        data.extend(curve.virtual_sol_reserves.to_le_bytes());
+        data.extend(curve.real_token_reserves.to_le_bytes());
+        data.extend(curve.real_sol_reserves.to_le_bytes());
+        data.extend(curve.token_total_supply.to_le_bytes());
+        data.push(curve.complete as u8);
+        data
+    }
+
+    fn fresh_curve() -> BondingCurve {
+        BondingCurve::launch()
+    }
+
+    #[test]
+    fn decodes_encoded_curve() {
+        let curve = BondingCurve { complete: true, real_sol_reserves: 85 * LAMPORTS_PER_SOL, ..fresh_curve() };
+        assert_eq!(BondingCurve::decode(&encode(&curve)), Some(curve));
+    }
+
+    #[test]
+    fn ignores_trailing_bytes() {
+        let curve = fresh_curve();
+        let mut data = encode(&curve);
+        data.extend([0xff; 32]);
+        assert_eq!(BondingCurve::decode(&data), Some(curve));
+    }
+
+    #[test]
+    fn rejects_truncated_data() {
+        let data = encode(&fresh_curve());
+        assert_eq!(BondingCurve::decode(&data[..48]), None);
+        assert_eq!(BondingCurve::decode(&[]), None);
+    }
+
+    #[test]
+    fn fresh_curve_has_no_progress() {
+        let curve = fresh_curve();
+        assert_eq!(curve.progress(), 0.0);
+        assert!((curve.price() - 30.0 / 1_073_000_000.0).abs() < 1e-15);
+        assert!((curve.market_cap() - 27.958993476).abs() < 1e-6);
+    }
+
+    #[test]
+    fn progress_follows_real_token_reserves() {
+        let half = BondingCurve { real_token_reserves: INITIAL_REAL_TOKEN_RESERVES / 2, ..fresh_curve() };
+        assert!((half.progress() - 50.0).abs() < 1e-9);
+
+        let sold_out = BondingCurve { real_token_reserves: 0, ..fresh_curve() };
+        assert_eq!(sold_out.progress(), 100.0);
+    }
+
+    #[test]
+    fn complete_curve_is_fully_progressed() {
+        let curve = BondingCurve { complete: true, ..fresh_curve() };
+        assert_eq!(curve.progress(), 100.0);
+    }
+
+    #[test]
+    fn parses_bare_mint() {
+        let mint_id = Pubkey::new_unique();
+        assert_eq!(parse_mint_input(&format!("  {}\n", mint_id)), Some(mint_id));
+    }
+
+    #[test]
+    fn parses_pumpfun_urls() {
+        let mint_id = Pubkey::new_unique();
+        for url in [
+            format!("https://pump.fun/coin/{}", mint_id),
+            format!("https://pump.fun/coin/{}?include-nsfw=true", mint_id),
+            format!("https://www.pump.fun/{}/", mint_id),
+            format!("pump.fun/coin/{}#chart", mint_id)
+        ] {
+            assert_eq!(parse_mint_input(&url), Some(mint_id), "{}", url);
+        }
+    }
+
+    #[test]
+    fn rejects_other_input() {
+        let mint_id = Pubkey::new_unique();
+        assert_eq!(parse_mint_input(&format!("https://example.com/coin/{}", mint_id)), None);
+        assert_eq!(parse_mint_input("https://pump.fun/board"), None);
+        assert_eq!(parse_mint_input("not a mint"), None);
+    }
+
+    #[test]
+    fn buy_moves_price_up() {
+        let mut curve = fresh_curve();
+        let price = curve.price();
+        let quote = curve.buy_quote(LAMPORTS_PER_SOL);
+
+        assert_eq!(curve.apply_buy(LAMPORTS_PER_SOL), (quote, LAMPORTS_PER_SOL));
+        assert!(quote > 0);
+        assert!(curve.price() > price);
+        assert_eq!(curve.real_sol_reserves, LAMPORTS_PER_SOL - LAMPORTS_PER_SOL / 100);
+        assert_eq!(curve.real_token_reserves, INITIAL_REAL_TOKEN_RESERVES - quote);
+    }
+
+    #[test]
+    fn round_trip_loses_the_fees() {
+        let mut curve = fresh_curve();
+        let (tokens, _) = curve.apply_buy(LAMPORTS_PER_SOL);
+        let lamports = curve.apply_sell(tokens);
+
+        assert!(lamports < LAMPORTS_PER_SOL * 98 / 100);
+        assert!(lamports > LAMPORTS_PER_SOL * 97 / 100);
+        assert_eq!(curve.real_token_reserves, INITIAL_REAL_TOKEN_RESERVES);
+    }
+
+    #[test]
+    fn sell_is_capped_by_real_sol() {
+        let curve = fresh_curve();
+        assert_eq!(curve.sell_quote(1_000_000_000), 0);
+    }
+
+    #[test]
+    fn buying_out_the_curve_completes_it() {
+        let mut curve = fresh_curve();
+        let (tokens, spent) = curve.apply_buy(1_000 * LAMPORTS_PER_SOL);
+
+        assert_eq!(tokens, INITIAL_REAL_TOKEN_RESERVES);
+        assert!(curve.complete);
+        assert_eq!(curve.progress(), 100.0);
+
+        // Only the tokens that were left are paid for, about 85 SOL plus the fee
+        assert_eq!(spent, curve.real_sol_reserves + fee(curve.real_sol_reserves));
+        assert!(spent > 85 * LAMPORTS_PER_SOL && spent < 87 * LAMPORTS_PER_SOL);
+    }
+
+    #[test]
+    fn empty_reserves_have_no_price() {
+        let curve = BondingCurve { virtual_token_reserves: 0, ..fresh_curve() };
+        assert_eq!(curve.price(), 0.0);
+        assert_eq!(curve.market_cap(), 0.0);
+    }
+}
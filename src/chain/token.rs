@@ -0,0 +1,133 @@
+use std::str::FromStr;
+
+use solana_account_decoder::UiAccountData;
+use solana_client::client_error::ClientError;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
+use solana_client::rpc_request::TokenAccountsFilter;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::program_pack::Pack;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+use spl_token_2022::extension::ExtensionType;
+
+static SIGNATURES_PAGE_SIZE: usize = 1000;
+/// Pages of a mint's history walked back to its first transaction, busier mints show no creation time.
+static MAX_CREATION_PAGES: usize = 5;
+
+pub struct TokenBalance {
+    pub amount: u64,
+    pub decimals: u8
+}
+
+impl TokenBalance {
+    pub fn ui_amount(&self) -> f64 {
+        self.amount as f64 / 10f64.powi(self.decimals as i32)
+    }
+}
+
+pub struct OwnedTokenAccount {
+    pub address: Pubkey,
+    pub mint_id: Pubkey,
+    pub token_program: Pubkey,
+    pub amount: u64,
+    pub decimals: u8,
+    pub lamports: u64
+}
+
+pub struct TokenAccount {
+    pub address: Pubkey,
+    pub balance: Option<TokenBalance>
+}
+
+/// Block time of the oldest transaction that touched `mint_id`, the one that created it.
+pub async fn get_created_at(client: &RpcClient, mint_id: &Pubkey) -> Result<Option<i64>, ClientError> {
+    let mut before = None;
+    let mut oldest = None;
+    for _ in 0..MAX_CREATION_PAGES {
+        let config = GetConfirmedSignaturesForAddress2Config {
+            before,
+            until: None,
+            limit: Some(SIGNATURES_PAGE_SIZE),
+            commitment: Some(CommitmentConfig::confirmed())
+        };
+
+        let signatures = client.get_signatures_for_address_with_config(mint_id, config).await?;
+        let Some(status) = signatures.last() else { return Ok(oldest) };
+        oldest = status.block_time;
+        if signatures.len() < SIGNATURES_PAGE_SIZE {
+            return Ok(oldest)
+        }
+
+        before = Signature::from_str(&status.signature).ok();
+    }
+
+    Ok(None)
+}
+
+/// Returns the token program that owns the mint, so legacy and token-2022 mints resolve to the right ATA.
+pub async fn get_token_program(client: &RpcClient, mint_id: &Pubkey) -> Result<Pubkey, ClientError> {
+    Ok(client.get_account(mint_id).await?.owner)
+}
+
+/// Looks up the associated token account of `owner` for `mint_id`. `balance` is `None` when the account does not exist yet.
+pub async fn get_token_account(client: &RpcClient, owner: &Pubkey, mint_id: &Pubkey, token_program: &Pubkey) -> Result<TokenAccount, ClientError> {
+    let address = get_associated_token_address_with_program_id(owner, mint_id, token_program);
+    let account = client.get_account_with_commitment(&address, client.commitment()).await?.value;
+
+    let balance = match account {
+        Some(_) => {
+            let balance = client.get_token_account_balance(&address).await?;
+            Some(TokenBalance {
+                amount: balance.amount.parse().unwrap_or(0),
+                decimals: balance.decimals
+            })
+        },
+        None => None
+    };
+
+    Ok(TokenAccount { address, balance })
+}
+
+/// Lists every legacy and token-2022 account owned by `owner`.
+pub async fn get_owned_token_accounts(client: &RpcClient, owner: &Pubkey) -> Result<Vec<OwnedTokenAccount>, ClientError> {
+    let mut accounts = vec![];
+
+    for token_program in [spl_token::id(), spl_token_2022::id()] {
+        let keyed_accounts = client.get_token_accounts_by_owner(owner, TokenAccountsFilter::ProgramId(token_program)).await?;
+        for keyed_account in keyed_accounts {
+            let UiAccountData::Json(parsed_account) = &keyed_account.account.data else { continue };
+            let info = &parsed_account.parsed["info"];
+
+            let (Ok(address), Some(Ok(mint_id))) = (
+                Pubkey::from_str(&keyed_account.pubkey),
+                info["mint"].as_str().map(Pubkey::from_str)
+            ) else { continue };
+
+            accounts.push(OwnedTokenAccount {
+                address,
+                mint_id,
+                token_program,
+                amount: info["tokenAmount"]["amount"].as_str().and_then(|amount| amount.parse().ok()).unwrap_or(0),
+                decimals: info["tokenAmount"]["decimals"].as_u64().unwrap_or(0) as u8,
+                lamports: keyed_account.account.lamports
+            });
+        }
+    }
+
+    Ok(accounts)
+}
+
+/// Rent an associated token account of `token_program` needs, paid by whoever creates it. Token-2022
+/// accounts carry the immutable owner extension and cost a little more.
+pub async fn token_account_rent(client: &RpcClient, token_program: &Pubkey) -> Result<u64, ClientError> {
+    let size = if token_program == &spl_token_2022::id() {
+        ExtensionType::try_calculate_account_len::<spl_token_2022::state::Account>(&[ExtensionType::ImmutableOwner])
+            .unwrap_or(spl_token::state::Account::LEN)
+    } else {
+        spl_token::state::Account::LEN
+    };
+
+    client.get_minimum_balance_for_rent_exemption(size).await
+}
@@ -0,0 +1,111 @@
+use moonbois_core::WalletDTO;
+use solana_client::client_error::ClientError;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+use spl_token_2022::extension::StateWithExtensions;
+use spl_token_2022::state::Account as TokenAccountState;
+
+use super::token::get_token_program;
+
+static MAX_MULTIPLE_ACCOUNTS: usize = 100;
+/// SOL balances this close count as equal, the backend and the RPC can be a couple of transaction
+/// fees apart while they read different slots.
+static SOL_TOLERANCE_LAMPORTS: u64 = 10_000;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum BalanceKind {
+    Sol,
+    Token
+}
+
+#[derive(Clone, Debug)]
+pub struct BalanceMismatch {
+    pub owner: Pubkey,
+    pub kind: BalanceKind,
+    pub reported: u64,
+    pub onchain: u64
+}
+
+/// Compares backend-reported balances against the configured Solana RPC, SOL balances within
+/// `SOL_TOLERANCE_LAMPORTS` are not reported. Token balances are only checked when `mint_id` is
+/// set, matching what `get_user_balances` reports.
+pub async fn verify_balances(
+    client: &RpcClient,
+    fee_payer: (Pubkey, u64),
+    wallets: &[WalletDTO],
+    mint_id: Option<Pubkey>
+) -> Result<Vec<BalanceMismatch>, ClientError> {
+    let mut mismatches = vec![];
+
+    let mut reported_sol = vec![fee_payer];
+    reported_sol.extend(wallets.iter().map(|wallet| (wallet.public_key, wallet.sol_balance)));
+
+    let owners: Vec<Pubkey> = reported_sol.iter().map(|(owner, _)| *owner).collect();
+    let accounts = get_multiple_accounts(client, &owners).await?;
+    for ((owner, reported), account) in reported_sol.into_iter().zip(accounts) {
+        let onchain = account.map(|account| account.lamports).unwrap_or(0);
+        if onchain.abs_diff(reported) > SOL_TOLERANCE_LAMPORTS {
+            mismatches.push(BalanceMismatch { owner, kind: BalanceKind::Sol, reported, onchain });
+        }
+    }
+
+    if let Some(mint_id) = mint_id {
+        let token_program = get_token_program(client, &mint_id).await?;
+        let token_accounts: Vec<Pubkey> = wallets.iter()
+            .map(|wallet| get_associated_token_address_with_program_id(&wallet.public_key, &mint_id, &token_program))
+            .collect();
+
+        let accounts = get_multiple_accounts(client, &token_accounts).await?;
+        for (wallet, account) in wallets.iter().zip(accounts) {
+            let onchain = account
+                .and_then(|account| StateWithExtensions::<TokenAccountState>::unpack(&account.data).ok().map(|state| state.base.amount))
+                .unwrap_or(0);
+            let reported = wallet.token_balance.unwrap_or(0);
+            if onchain != reported {
+                mismatches.push(BalanceMismatch { owner: wallet.public_key, kind: BalanceKind::Token, reported, onchain });
+            }
+        }
+    }
+
+    Ok(mismatches)
+}
+
+/// Mismatches of `current` that the previous check found as well. A balance that disagrees once is
+/// usually a transaction one side has seen and the other has not yet, so only these are flagged.
+pub fn persistent_mismatches(previous: &[BalanceMismatch], current: &[BalanceMismatch]) -> Vec<BalanceMismatch> {
+    current.iter()
+        .filter(|mismatch| previous.iter().any(|seen| seen.owner == mismatch.owner && seen.kind == mismatch.kind))
+        .cloned()
+        .collect()
+}
+
+async fn get_multiple_accounts(client: &RpcClient, pubkeys: &[Pubkey]) -> Result<Vec<Option<solana_sdk::account::Account>>, ClientError> {
+    let mut accounts = vec![];
+    for chunk in pubkeys.chunks(MAX_MULTIPLE_ACCOUNTS) {
+        accounts.extend(client.get_multiple_accounts(chunk).await?);
+    }
+
+    Ok(accounts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mismatch(owner: Pubkey, kind: BalanceKind) -> BalanceMismatch {
+        BalanceMismatch { owner, kind, reported: 1, onchain: 2 }
+    }
+
+    #[test]
+    fn flags_only_mismatches_seen_twice() {
+        let (fee_payer, sniper) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let previous = vec![mismatch(fee_payer, BalanceKind::Sol), mismatch(sniper, BalanceKind::Token)];
+        let current = vec![mismatch(fee_payer, BalanceKind::Sol), mismatch(sniper, BalanceKind::Sol)];
+
+        let flagged = persistent_mismatches(&previous, &current);
+        assert_eq!(flagged.len(), 1);
+        assert_eq!((flagged[0].owner, flagged[0].kind), (fee_payer, BalanceKind::Sol));
+        assert!(persistent_mismatches(&[], &current).is_empty());
+    }
+}
@@ -0,0 +1,230 @@
+use console::style;
+use dialoguer::theme::ColorfulTheme;
+use crate::storage::address_book::load_address_book;
+use crate::storage::address_book::save_address_book;
+use crate::storage::address_book::AddressBook;
+use crate::storage::address_book::AddressEntry;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::pubkey::Pubkey;
+use std::sync::Arc;
+
+use dialoguer::Confirm;
+use dialoguer::FuzzySelect;
+use dialoguer::Input;
+
+use crate::AppData;
+use crate::AppError;
+use crate::Menu;
+
+use super::main::MainMenu;
+use super::Handler;
+
+static FINGERPRINT_LENGTH: usize = 4;
+
+pub enum AddressBookOptions {
+    AddAddress,
+    ToggleAllowlist,
+    SetThreshold,
+    Back
+}
+
+impl ToString for AddressBookOptions {
+    fn to_string(&self) -> String {
+        match self {
+            Self::AddAddress => "AddAddress".to_string(),
+            Self::ToggleAllowlist => "ToggleAllowlist".to_string(),
+            Self::SetThreshold => "SetThreshold".to_string(),
+            Self::Back => "Back".to_string()
+        }
+    }
+}
+
+pub struct AddressBookMenu;
+impl Handler for AddressBookMenu {
+    async fn handle(&self, _app_data: &Arc<AppData>) -> Result<Option<Menu>, (Menu, AppError)> {
+        let mut address_book = load_address_book()
+            .map_err(|err| (Menu::Main(MainMenu), AppError::from(err)))?;
+
+        println!(
+            "allowlist_only: {}\nconfirm_threshold: {} {}",
+            if address_book.allowlist_only { style("on").green() } else { style("off").dim() },
+            address_book.confirm_threshold_lamports as f64 / LAMPORTS_PER_SOL as f64,
+            style("SOL").cyan()
+        );
+
+        let options = [
+            AddressBookOptions::AddAddress,
+            AddressBookOptions::ToggleAllowlist,
+            AddressBookOptions::SetThreshold,
+            AddressBookOptions::Back
+        ];
+        let mut selection: Vec<String> = address_book.entries.iter()
+            .map(|entry| format!("{} {}", entry.label, entry.address))
+            .collect();
+        selection.extend(options.iter().map(|option| option.to_string()));
+
+        let index = FuzzySelect::with_theme(&ColorfulTheme::default())
+            .with_prompt("Address book")
+            .default(0)
+            .max_length(10)
+            .items(&selection)
+            .interact()
+            .unwrap();
+
+        if index < address_book.entries.len() {
+            let entry = address_book.entries[index].clone();
+            let delete = Confirm::with_theme(&ColorfulTheme::default())
+                .with_prompt(format!("Remove {} from the address book?", entry.label))
+                .default(false)
+                .interact()
+                .unwrap();
+
+            if delete {
+                address_book.entries.retain(|saved| saved.address != entry.address);
+                save_address_book(&address_book)
+                    .map_err(|err| (Menu::AddressBook(AddressBookMenu), AppError::from(err)))?;
+            }
+
+            return Ok(Some(Menu::AddressBook(AddressBookMenu)))
+        }
+
+        match options[index - address_book.entries.len()] {
+            AddressBookOptions::AddAddress => {
+                let address: Pubkey = match Input::with_theme(&ColorfulTheme::default())
+                    .with_prompt("Enter the address")
+                    .interact_text()
+                    .unwrap() {
+                        Some(address) => address,
+                        None => return Ok(Some(Menu::AddressBook(AddressBookMenu)))
+                    };
+
+                if !prompt_save_address(&mut address_book, address)
+                    .map_err(|err| (Menu::AddressBook(AddressBookMenu), err))? {
+                        return Ok(Some(Menu::AddressBook(AddressBookMenu)))
+                    }
+            },
+            AddressBookOptions::ToggleAllowlist => {
+                address_book.allowlist_only = !address_book.allowlist_only;
+            },
+            AddressBookOptions::SetThreshold => {
+                let threshold: f64 = match Input::with_theme(&ColorfulTheme::default())
+                    .with_prompt("Enter the SOL amount that needs a second confirmation")
+                    .default(address_book.confirm_threshold_lamports as f64 / LAMPORTS_PER_SOL as f64)
+                    .interact_text()
+                    .unwrap() {
+                        Some(threshold) => threshold,
+                        None => return Ok(Some(Menu::AddressBook(AddressBookMenu)))
+                    };
+
+                address_book.confirm_threshold_lamports = (threshold.max(0f64) * LAMPORTS_PER_SOL as f64) as u64;
+            },
+            AddressBookOptions::Back => return Ok(Some(Menu::Main(MainMenu)))
+        }
+
+        save_address_book(&address_book)
+            .map_err(|err| (Menu::AddressBook(AddressBookMenu), AppError::from(err)))?;
+
+        Ok(Some(Menu::AddressBook(AddressBookMenu)))
+    }
+}
+
+/// Asks for a label and adds `address` to the book. Returns `false` when the prompt was cancelled.
+fn prompt_save_address(address_book: &mut AddressBook, address: Pubkey) -> Result<bool, AppError> {
+    let label: String = match Input::with_theme(&ColorfulTheme::default())
+        .with_prompt("Enter a label")
+        .interact_text()
+        .unwrap() {
+            Some(label) => label,
+            None => return Ok(false)
+        };
+
+    address_book.entries.retain(|entry| entry.address != address);
+    address_book.entries.push(AddressEntry { label, address });
+
+    Ok(true)
+}
+
+/// Picks the destination of an outgoing transfer from the address book. Pasting an address is
+/// only offered while allowlist-only mode is off.
+pub fn select_recipient() -> Result<Option<Pubkey>, AppError> {
+    let mut address_book = load_address_book()?;
+
+    let mut selection: Vec<String> = address_book.entries.iter()
+        .map(|entry| format!("{} {}", entry.label, entry.address))
+        .collect();
+    if !address_book.allowlist_only {
+        selection.push(format!("{}", "EnterAddress"));
+    }
+    selection.push(format!("{}", "Back"));
+
+    let index = FuzzySelect::with_theme(&ColorfulTheme::default())
+        .with_prompt("Select the receiver")
+        .default(0)
+        .max_length(10)
+        .items(&selection)
+        .interact()?;
+
+    if index == selection.len() - 1 {
+        return Ok(None)
+    }
+    if let Some(entry) = address_book.entries.get(index) {
+        return Ok(Some(entry.address))
+    }
+
+    let receiver: Pubkey = match Input::with_theme(&ColorfulTheme::default())
+        .with_prompt("Enter the receiver address")
+        .interact_text()
+        .unwrap() {
+            Some(value) => value,
+            None => return Ok(None)
+        };
+
+    let save = Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt("Save this address to the address book?")
+        .default(false)
+        .interact()?;
+
+    if save && prompt_save_address(&mut address_book, receiver)? {
+        save_address_book(&address_book)?;
+    }
+
+    Ok(Some(receiver))
+}
+
+/// Confirmation screen for an outgoing transfer, showing the receiver's label and a highlighted
+/// fingerprint. `lamports` is set for SOL transfers and checked against the confirm threshold.
+pub fn confirm_transfer(receiver: &Pubkey, amount: &str, lamports: Option<u64>) -> Result<bool, AppError> {
+    let address_book = load_address_book()?;
+    let address = receiver.to_string();
+    let (head, rest) = address.split_at(FINGERPRINT_LENGTH);
+    let (middle, tail) = rest.split_at(rest.len() - FINGERPRINT_LENGTH);
+
+    println!(
+        "send: {}\nto: {}\naddress: {}{}{}",
+        amount,
+        address_book.label(receiver).map(|label| style(label.to_string()).green()).unwrap_or(style("unknown address ⚠️".to_string()).yellow()),
+        style(head).bold().cyan(),
+        style(middle).dim(),
+        style(tail).bold().cyan()
+    );
+
+    let confirm = Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt(format!("Send {} to {}..{}?", amount, head, tail))
+        .default(false)
+        .interact()?;
+
+    if !confirm {
+        return Ok(false)
+    }
+
+    match lamports {
+        Some(lamports) if lamports >= address_book.confirm_threshold_lamports => Ok(Confirm::with_theme(&ColorfulTheme::default())
+            .with_prompt(format!(
+                "This is above your {} SOL threshold, send it anyway?",
+                address_book.confirm_threshold_lamports as f64 / LAMPORTS_PER_SOL as f64
+            ))
+            .default(false)
+            .interact()?),
+        _ => Ok(true)
+    }
+}
@@ -1,11 +1,15 @@
 use dialoguer::theme::ColorfulTheme;
 use crate::dialogue::loader::Loader;
+use crate::jobs::ladder::restore_ladders;
+use crate::paper::dto::user_dto;
+use crate::storage;
 use std::sync::Arc;
 
 use dialoguer::Confirm;
 use dialoguer::Input;
 use moonbois_core::rpc::MoonboisClientError;
 use moonbois_core::Credentials;
+use moonbois_core::UserDTO;
 use solana_sdk::signature::Keypair;
 use solana_sdk::signer::Signer;
 
@@ -14,11 +18,20 @@ use crate::AppError;
 use crate::Menu;
 
 use super::main::MainMenu;
+use super::panic::PanicExit;
+use super::snipe::OrphanedSnipes;
 use super::Handler;
 
 pub struct Login;
 impl Handler for Login {
     async fn handle(&self, app_data: &Arc<AppData>) -> Result<Option<Menu>, (Menu, AppError)> {
+        if let Some(paper) = &app_data.paper {
+            let user = user_dto(&*paper.read().await, None)
+                .map_err(|err| (Menu::Login(Login), AppError::from(err)))?;
+
+            return Ok(Some(logged_in(app_data, user).await))
+        }
+
         let private_key: String = match Input::with_theme(&ColorfulTheme::default())
             .with_prompt("Enter your private key to login")
             .interact_text()
@@ -60,15 +73,30 @@ impl Handler for Login {
         if let Err(err) = get_user_reponse {
             return Err((Menu::Login(Login), AppError::from(err)));
         } else if let Ok(user) = get_user_reponse {
-            let mut user_write = app_data.user.write().await;
-            user_write.0 = Some(user);
-            return Ok(Some(Menu::Main(MainMenu)))
+            return Ok(Some(logged_in(app_data, user).await))
         }
 
         return Err((Menu::Login(Login), AppError::Unhandled("Unhandled error".to_string())));
     }
 }
 
+/// Sets the user and picks up what the previous session left running.
+async fn logged_in(app_data: &Arc<AppData>, user: UserDTO) -> Menu {
+    storage::use_user_data_dir(user.id);
+    let mut user_write = app_data.user.write().await;
+    user_write.0 = Some(user);
+    drop(user_write);
+
+    restore_ladders(app_data).await;
+
+    let mut panic_on_login = app_data.panic_on_login.write().await;
+    if std::mem::take(&mut panic_on_login.0) {
+        return Menu::PanicExit(PanicExit)
+    }
+
+    Menu::OrphanedSnipes(OrphanedSnipes)
+}
+
 pub struct Signup {
     credentials: Credentials
 }
@@ -109,6 +137,7 @@ impl Handler for Signup {
 
             match get_user_reponse {
                 Ok(user) => {
+                    storage::use_user_data_dir(user.id);
                     let mut user_write = app_data.user.write().await;
                     user_write.0 = Some(user);
                     return Ok(Some(Menu::Main(MainMenu)))
@@ -0,0 +1,431 @@
+use console::style;
+use dialoguer::theme::ColorfulTheme;
+use crate::chain::analysis::analyze_token;
+use crate::chain::analysis::PUMPFUN_DECIMALS;
+use crate::chain::token::get_created_at;
+use crate::dialogue::loader::Loader;
+use crate::jobs::format_age;
+use crate::storage::archive::load_archived;
+use crate::storage::archive::restore_project;
+use crate::storage::creation_times::load_creation_times;
+use crate::storage::creation_times::save_creation_times;
+use crate::storage::migrations::load_migrated;
+use crate::storage::tickers::load_tickers;
+use crate::storage::tickers::save_tickers;
+use crate::storage::transactions::project_flows;
+use crate::storage::transactions::unix_timestamp;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::pubkey::Pubkey;
+use std::cmp::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+use dialoguer::FuzzySelect;
+use dialoguer::Input;
+
+use crate::AppData;
+use crate::AppError;
+use crate::Menu;
+
+use super::main::MainMenu;
+use super::portfolio::load_holdings;
+use super::portfolio::Holding;
+use super::project::ProjectMenu;
+use super::transactions::price_recorded_sells;
+use super::Handler;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ProjectSort {
+    Name,
+    Ticker,
+    Holdings,
+    Value,
+    Pnl,
+    Created
+}
+
+impl ToString for ProjectSort {
+    fn to_string(&self) -> String {
+        match self {
+            Self::Name => "Name".to_string(),
+            Self::Ticker => "Ticker".to_string(),
+            Self::Holdings => "Holdings".to_string(),
+            Self::Value => "Value".to_string(),
+            Self::Pnl => "P&L".to_string(),
+            Self::Created => "Created".to_string()
+        }
+    }
+}
+
+impl From<usize> for ProjectSort {
+    fn from(value: usize) -> Self {
+        match value {
+            0 => Self::Name,
+            1 => Self::Ticker,
+            2 => Self::Holdings,
+            3 => Self::Value,
+            4 => Self::Pnl,
+            5 => Self::Created,
+            _ => panic!("Received invalid project sort index")
+        }
+    }
+}
+
+pub enum ProjectListOptions {
+    Search,
+    Sort,
+    ShowArchived,
+    HideArchived,
+    Refresh,
+    Back
+}
+
+impl ToString for ProjectListOptions {
+    fn to_string(&self) -> String {
+        match self {
+            Self::Search => "Search".to_string(),
+            Self::Sort => "Sort".to_string(),
+            Self::ShowArchived => "ShowArchived".to_string(),
+            Self::HideArchived => "HideArchived".to_string(),
+            Self::Refresh => "Refresh".to_string(),
+            Self::Back => "Back".to_string()
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ProjectRow {
+    pub holding: Holding,
+    pub ticker: Option<String>,
+    /// Current value plus recorded sell proceeds minus recorded buys, in SOL. `None` while some
+    /// part of it is unknown.
+    pub pnl: Option<f64>,
+    pub created_at: Option<i64>,
+    pub migrated: bool,
+    pub archived: bool
+}
+
+impl ProjectRow {
+    fn matches(&self, query: &str) -> bool {
+        let query = query.to_lowercase();
+        self.holding.project.name.to_lowercase().contains(&query)
+            || self.ticker.as_ref().is_some_and(|ticker| ticker.to_lowercase().contains(&query))
+            || self.holding.project.pumpfun.mint_id.to_string().to_lowercase().starts_with(&query)
+    }
+
+    fn compare(&self, other: &Self, sort: ProjectSort) -> Ordering {
+        let by_float = |a: Option<f64>, b: Option<f64>| b.unwrap_or(f64::MIN).total_cmp(&a.unwrap_or(f64::MIN));
+        match sort {
+            ProjectSort::Name => self.holding.project.name.to_lowercase().cmp(&other.holding.project.name.to_lowercase()),
+            ProjectSort::Ticker => self.ticker.cmp(&other.ticker),
+            ProjectSort::Holdings => other.holding.tokens().cmp(&self.holding.tokens()),
+            ProjectSort::Value => by_float(self.holding.value(), other.holding.value()),
+            ProjectSort::Pnl => by_float(self.pnl, other.pnl),
+            ProjectSort::Created => other.created_at.cmp(&self.created_at)
+        }
+    }
+
+    fn created(&self) -> String {
+        self.created_at
+            .map(|created_at| format!("{} ago", format_age(Duration::from_secs(unix_timestamp().saturating_sub(created_at as u64)))))
+            .unwrap_or("unknown".to_string())
+    }
+}
+
+fn format_sol(value: Option<f64>) -> String {
+    value.map(|value| format!("{:.4}", value)).unwrap_or("n/a".to_string())
+}
+
+#[derive(Clone)]
+pub struct SelectProject {
+    pub sort: ProjectSort,
+    pub query: Option<String>,
+    /// Archived projects are hidden unless this is set.
+    pub show_archived: bool,
+    pub rows: Option<Vec<ProjectRow>>
+}
+impl SelectProject {
+    pub fn new() -> Self {
+        Self {
+            sort: ProjectSort::Name,
+            query: None,
+            show_archived: false,
+            rows: None
+        }
+    }
+}
+impl Handler for SelectProject {
+    async fn handle(&self, app_data: &Arc<AppData>) -> Result<Option<Menu>, (Menu, AppError)> {
+        let rows = match &self.rows {
+            Some(rows) => rows.clone(),
+            None => load_project_rows(app_data).await
+                .map_err(|err| (Menu::Main(MainMenu), err))?
+        };
+
+        let mut visible: Vec<&ProjectRow> = rows.iter()
+            .filter(|row| self.show_archived || !row.archived)
+            .filter(|row| self.query.as_ref().map_or(true, |query| row.matches(query)))
+            .collect();
+        visible.sort_by(|a, b| a.compare(b, self.sort));
+
+        println!(
+            "{:<16} {:<8} {:<10} {:>16} {:>10} {:>10} {:>10}",
+            "name", "ticker", "mint", "holdings", "value", "p&l", "created"
+        );
+        if let Some(query) = &self.query {
+            println!("{}", style(format!("filtered by \"{}\", {} of {} projects", query, visible.len(), rows.len())).dim());
+        }
+
+        let mut selection: Vec<String> = visible.iter().map(|row| {
+            let mint_id = row.holding.project.pumpfun.mint_id.to_string();
+            format!(
+                "{:<16} {:<8} {:<10} {:>16} {:>10} {:>10} {:>10}{}",
+                row.holding.project.name,
+                row.ticker.as_deref().unwrap_or("n/a"),
+                format!("{}..{}", &mint_id[0..4], &mint_id[mint_id.len() - 4..]),
+                row.holding.ui_tokens(),
+                format_sol(row.holding.value()),
+                format_sol(row.pnl),
+                row.created(),
+                match (row.migrated, row.archived) {
+                    (true, true) => " (migrated, archived)",
+                    (true, false) => " (migrated)",
+                    (false, true) => " (archived)",
+                    (false, false) => ""
+                }
+            )
+        }).collect();
+
+        let options = [
+            ProjectListOptions::Search,
+            ProjectListOptions::Sort,
+            if self.show_archived { ProjectListOptions::HideArchived } else { ProjectListOptions::ShowArchived },
+            ProjectListOptions::Refresh,
+            ProjectListOptions::Back
+        ];
+        selection.extend(options.iter().map(|option| option.to_string()));
+
+        let index = FuzzySelect::with_theme(&ColorfulTheme::default())
+            .with_prompt(format!("Select Project (sorted by {})", self.sort.to_string()))
+            .default(0)
+            .items(&selection)
+            .interact()
+            .unwrap();
+
+        if index < visible.len() {
+            return Ok(Some(Menu::ProjectDetails(ProjectDetails {
+                row: visible[index].clone(),
+                browser: SelectProject { rows: Some(rows.clone()), ..self.clone() }
+            })))
+        }
+
+        match options[index - visible.len()] {
+            ProjectListOptions::Search => {
+                let query: String = match Input::with_theme(&ColorfulTheme::default())
+                    .with_prompt("Search by name, ticker or mint prefix (empty to clear)")
+                    .allow_empty(true)
+                    .interact_text()
+                    .unwrap() {
+                        Some(query) => query,
+                        None => return Ok(Some(Menu::SelectProject(SelectProject { rows: Some(rows), ..self.clone() })))
+                    };
+
+                let query = Some(query.trim().to_string()).filter(|query| !query.is_empty());
+                Ok(Some(Menu::SelectProject(SelectProject { query, rows: Some(rows), ..self.clone() })))
+            },
+            ProjectListOptions::Sort => {
+                let sort = FuzzySelect::with_theme(&ColorfulTheme::default())
+                    .with_prompt("Sort by")
+                    .default(0)
+                    .items(&[
+                        ProjectSort::Name,
+                        ProjectSort::Ticker,
+                        ProjectSort::Holdings,
+                        ProjectSort::Value,
+                        ProjectSort::Pnl,
+                        ProjectSort::Created
+                    ])
+                    .interact()
+                    .map_err(|err| (Menu::SelectProject(SelectProject::new()), AppError::from(err)))?;
+
+                Ok(Some(Menu::SelectProject(SelectProject { sort: ProjectSort::from(sort), rows: Some(rows), ..self.clone() })))
+            },
+            ProjectListOptions::ShowArchived => Ok(Some(Menu::SelectProject(SelectProject { show_archived: true, rows: Some(rows), ..self.clone() }))),
+            ProjectListOptions::HideArchived => Ok(Some(Menu::SelectProject(SelectProject { show_archived: false, rows: Some(rows), ..self.clone() }))),
+            ProjectListOptions::Refresh => Ok(Some(Menu::SelectProject(SelectProject { rows: None, ..self.clone() }))),
+            ProjectListOptions::Back => Ok(Some(Menu::Main(MainMenu)))
+        }
+    }
+}
+
+pub struct ProjectDetails {
+    pub row: ProjectRow,
+    pub browser: SelectProject
+}
+impl Handler for ProjectDetails {
+    async fn handle(&self, app_data: &Arc<AppData>) -> Result<Option<Menu>, (Menu, AppError)> {
+        let row = &self.row;
+        let project = &row.holding.project;
+
+        println!(
+            "name: {} {}\nmint_id: {}\ndeployer: {}\ncreated: {}",
+            project.name,
+            style(row.ticker.as_deref().unwrap_or("n/a").to_uppercase()).magenta(),
+            project.pumpfun.mint_id,
+            project.deployer,
+            row.created()
+        );
+        if row.migrated {
+            println!("{}", style("curve: migrated ⚠️").yellow());
+        }
+        if row.archived {
+            println!("{}", style("archived").dim());
+        }
+        println!(
+            "holdings: {} TOKENS in {} wallets\nprice: {} {}\nvalue: {} {}\np&l: {} {}",
+            row.holding.ui_tokens(),
+            row.holding.wallets.len(),
+            row.holding.price.map(|price| format!("{:.10}", price)).unwrap_or("n/a".to_string()),
+            style("SOL").cyan(),
+            format_sol(row.holding.value()),
+            style("SOL").cyan(),
+            format_sol(row.pnl),
+            style("SOL").cyan()
+        );
+        for (wallet, amount) in row.holding.wallets.iter() {
+            println!("  - {} {}", &wallet[0..5], style(format!("{} TOKENS", *amount as f64 / 10f64.powi(PUMPFUN_DECIMALS as i32))).dim());
+        }
+        println!("");
+
+        let mut items = vec!["Open"];
+        if row.archived {
+            items.push("Restore");
+        }
+        items.push("Back");
+
+        let selection = FuzzySelect::with_theme(&ColorfulTheme::default())
+            .with_prompt("Project details")
+            .default(0)
+            .items(&items)
+            .interact()
+            .unwrap();
+
+        match items[selection] {
+            "Restore" => {
+                restore_project(project.id)
+                    .map_err(|err| (Menu::SelectProject(self.browser.clone()), AppError::from(err)))?;
+
+                let mut browser = self.browser.clone();
+                if let Some(rows) = &mut browser.rows {
+                    rows.iter_mut()
+                        .filter(|row| row.holding.project.id == project.id)
+                        .for_each(|row| row.archived = false);
+                }
+
+                return Ok(Some(Menu::SelectProject(browser)))
+            },
+            "Back" => return Ok(Some(Menu::SelectProject(self.browser.clone()))),
+            _ => {}
+        }
+
+        app_data.active_project.write().await.0 = Some(project.id);
+
+        Ok(Some(Menu::ProjectMenu(ProjectMenu)))
+    }
+}
+
+/// Builds the browser rows: holdings and curve price per project, the ticker from the cached token
+/// metadata, P&L from the recorded buys and sells and the creation time from the cached oldest
+/// transaction of each mint.
+async fn load_project_rows(app_data: &Arc<AppData>) -> Result<Vec<ProjectRow>, AppError> {
+    let holdings = load_holdings(app_data).await?;
+    let migrated = load_migrated()?;
+    let archived = load_archived()?;
+
+    let records = Loader::new()
+        .with_prompt("loading trade history")
+        .interact(price_recorded_sells(app_data))
+        .await?;
+
+    // Only mints without a cached ticker are analyzed, a failed lookup shows as n/a
+    let mut tickers = load_tickers().unwrap_or_default();
+    let missing: Vec<Pubkey> = holdings.iter()
+        .map(|holding| holding.project.pumpfun.mint_id)
+        .filter(|mint_id| !tickers.contains_key(&mint_id.to_string()))
+        .collect();
+
+    if !missing.is_empty() {
+        let found = Loader::new()
+            .with_prompt("loading token metadata")
+            .interact(async {
+                let mut found = vec![];
+                for mint_id in missing {
+                    let ticker = analyze_token(&app_data.solana_client, &mint_id).await.ok()
+                        .and_then(|report| report.metadata)
+                        .map(|metadata| metadata.symbol);
+                    if let Some(ticker) = ticker {
+                        found.push((mint_id.to_string(), ticker));
+                    }
+                }
+                found
+            })
+            .await;
+
+        if !found.is_empty() {
+            tickers.extend(found);
+            if let Err(err) = save_tickers(&tickers) {
+                app_data.notifications.write().await.push(format!("Unable to cache token tickers - {}", err));
+            }
+        }
+    }
+
+    // Creation times are cached the same way, a mint whose history could not be read shows as unknown
+    let mut creation_times = load_creation_times().unwrap_or_default();
+    let missing: Vec<Pubkey> = holdings.iter()
+        .map(|holding| holding.project.pumpfun.mint_id)
+        .filter(|mint_id| !creation_times.contains_key(&mint_id.to_string()))
+        .collect();
+
+    if !missing.is_empty() {
+        let found = Loader::new()
+            .with_prompt("loading token creation times")
+            .interact(async {
+                let mut found = vec![];
+                for mint_id in missing {
+                    if let Ok(Some(created_at)) = get_created_at(&app_data.solana_client, &mint_id).await {
+                        found.push((mint_id.to_string(), created_at));
+                    }
+                }
+                found
+            })
+            .await;
+
+        if !found.is_empty() {
+            creation_times.extend(found);
+            if let Err(err) = save_creation_times(&creation_times) {
+                app_data.notifications.write().await.push(format!("Unable to cache token creation times - {}", err));
+            }
+        }
+    }
+
+    let mut rows = vec![];
+    for holding in holdings.into_iter() {
+        let ticker = tickers.get(&holding.project.pumpfun.mint_id.to_string()).cloned();
+        let flows = project_flows(&records, holding.project.id);
+        let value = if holding.tokens() == 0 { Some(0.0) } else { holding.value() };
+        let pnl = value
+            .filter(|_| flows.unpriced_sells == 0)
+            .map(|value| value + (flows.received as f64 - flows.spent as f64) / LAMPORTS_PER_SOL as f64);
+
+        rows.push(ProjectRow {
+            created_at: creation_times.get(&holding.project.pumpfun.mint_id.to_string()).copied(),
+            migrated: migrated.contains(&holding.project.pumpfun.mint_id.to_string()),
+            archived: archived.contains(&holding.project.id),
+            holding,
+            ticker,
+            pnl
+        });
+    }
+
+    Ok(rows)
+}
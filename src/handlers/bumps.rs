@@ -2,6 +2,7 @@ use std::time::Duration;
 
 use dialoguer::theme::ColorfulTheme;
 use crate::dialogue::loader::Loader;
+use crate::paper::dispatch;
 use dialoguer::{FuzzySelect, Input};
 use moonbois_core::{EnableBumpsParams, PumpfunBumpStatus};
 use solana_sdk::native_token::LAMPORTS_PER_SOL;
@@ -68,6 +69,9 @@ impl Handler for BumpMenu {
 pub struct StartBumps;
 impl Handler for StartBumps {
     async fn handle(&self, app_data: &std::sync::Arc<crate::AppData>) -> Result<Option<Menu>, (Menu, AppError)> {
+        dispatch::live_only(app_data, "StartBumps")
+            .map_err(|err| (Menu::Bump(BumpMenu), err))?;
+
         let active_project_read = app_data.active_project.read().await; 
         let project_id = match &active_project_read.0 {
             Some(p) => p.clone(),
@@ -117,6 +121,9 @@ impl Handler for StartBumps {
 pub struct StopBumps;
 impl Handler for StopBumps {
     async fn handle(&self, app_data: &std::sync::Arc<crate::AppData>) -> Result<Option<Menu>, (Menu, AppError)> {
+        dispatch::live_only(app_data, "StopBumps")
+            .map_err(|err| (Menu::Bump(BumpMenu), err))?;
+
         let rpc_client = app_data.rpc_client.read().await;
         Loader::new()
             .with_prompt("stop_bumps in progress")
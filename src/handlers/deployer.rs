@@ -0,0 +1,202 @@
+use console::style;
+use dialoguer::theme::ColorfulTheme;
+use crate::chain::deployer::get_token_state;
+use crate::chain::deployer::scan_deployer;
+use crate::chain::deployer::DeployedToken;
+use crate::chain::deployer::TokenState;
+use crate::dialogue::loader::Loader;
+use crate::jobs::format_age;
+use crate::storage::deployer_history::load_deployer_history;
+use crate::storage::deployer_history::save_deployer_history;
+use crate::storage::deployer_history::DeployerHistory;
+use crate::storage::deployer_history::HistoryGap;
+use crate::storage::transactions::unix_timestamp;
+use solana_client::client_error::ClientError;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use std::cmp::Reverse;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use dialoguer::FuzzySelect;
+
+use crate::AppData;
+use crate::AppError;
+use crate::Menu;
+
+use super::presets::Deployers;
+use super::project::ProjectMenu;
+use super::Handler;
+
+pub enum DeployerProfileOptions {
+    Refresh,
+    ScanOlder,
+    Back
+}
+
+impl ToString for DeployerProfileOptions {
+    fn to_string(&self) -> String {
+        match self {
+            Self::Refresh => "Refresh".to_string(),
+            Self::ScanOlder => "ScanOlder".to_string(),
+            Self::Back => "Back".to_string()
+        }
+    }
+}
+
+pub struct DeployerProfile {
+    pub deployer: Pubkey,
+    pub from_project: bool
+}
+impl DeployerProfile {
+    fn back(&self) -> Menu {
+        if self.from_project {
+            Menu::ProjectMenu(ProjectMenu)
+        } else {
+            Menu::Deployers(Deployers)
+        }
+    }
+}
+impl Handler for DeployerProfile {
+    async fn handle(&self, app_data: &Arc<AppData>) -> Result<Option<Menu>, (Menu, AppError)> {
+        let history = match load_deployer_history(&self.deployer)
+            .map_err(|err| (self.back(), AppError::from(err)))? {
+                Some(history) => history,
+                None => update_history(app_data, &self.deployer, DeployerHistory::default(), false).await
+                    .map_err(|err| (self.back(), err))?
+            };
+
+        println!("deployer: {}", self.deployer);
+        println!("{:<46} {:>10} {:<10}", "mint_id", "created", "state");
+        for token in history.tokens.iter() {
+            let created = token.created_at
+                .map(|created_at| format!("{} ago", format_age(Duration::from_secs(unix_timestamp().saturating_sub(created_at as u64)))))
+                .unwrap_or("unknown".to_string());
+            let state = match token.state {
+                TokenState::Active => style(token.state.to_string()).green(),
+                TokenState::Migrated => style(token.state.to_string()).cyan(),
+                TokenState::Abandoned => style(token.state.to_string()).dim(),
+                TokenState::Unknown => style(token.state.to_string()).yellow()
+            };
+            println!("{:<46} {:>10} {}", token.mint_id.to_string(), created, state);
+        }
+        if history.tokens.is_empty() {
+            println!("{}", style("No pump.fun tokens created by this deployer were found").dim());
+        }
+        println!(
+            "\n{}",
+            style(format!("cached {} ago", format_age(Duration::from_secs(unix_timestamp().saturating_sub(history.updated_at))))).dim()
+        );
+        if !history.gaps.is_empty() {
+            println!("{}", style(format!("{} gaps between refreshes were not scanned yet", history.gaps.len())).yellow());
+        }
+        if history.oldest_signature.is_some() {
+            println!("{}", style("Older history was not scanned yet").dim());
+        }
+
+        let mut items = vec![DeployerProfileOptions::Refresh];
+        if history.oldest_signature.is_some() || !history.gaps.is_empty() {
+            items.push(DeployerProfileOptions::ScanOlder);
+        }
+        items.push(DeployerProfileOptions::Back);
+
+        let selection = FuzzySelect::with_theme(&ColorfulTheme::default())
+            .with_prompt("Deployer profile")
+            .default(0)
+            .items(&items)
+            .interact()
+            .map_err(|err| (self.back(), AppError::from(err)))?;
+
+        match items[selection] {
+            DeployerProfileOptions::Refresh => {
+                update_history(app_data, &self.deployer, history, false).await
+                    .map_err(|err| (self.back(), err))?;
+            },
+            DeployerProfileOptions::ScanOlder => {
+                update_history(app_data, &self.deployer, history, true).await
+                    .map_err(|err| (self.back(), err))?;
+            },
+            DeployerProfileOptions::Back => return Ok(Some(self.back()))
+        }
+
+        Ok(Some(Menu::DeployerProfile(DeployerProfile { deployer: self.deployer, from_project: self.from_project })))
+    }
+}
+
+/// Scans for newer (or with `older`, older) token creations, refreshes every token's state and
+/// writes the result to the local cache. A refresh that hits the scan limit or a failing request
+/// before reaching the previous newest signature leaves a gap, scanning older fills the gaps before
+/// going further back. Whatever was scanned before a request failed is kept.
+async fn update_history(app_data: &Arc<AppData>, deployer: &Pubkey, mut history: DeployerHistory, older: bool) -> Result<DeployerHistory, AppError> {
+    let parse = |signature: &Option<String>| signature.as_ref().and_then(|signature| Signature::from_str(signature).ok());
+
+    let client = &app_data.solana_client;
+    let (history, error) = Loader::new()
+        .with_prompt("scanning deployer history")
+        .interact(async {
+            let error = if older {
+                let error = if let Some(gap) = history.gaps.pop() {
+                    let scan = scan_deployer(client, deployer, Signature::from_str(&gap.before).ok(), Signature::from_str(&gap.until).ok()).await?;
+                    history.tokens.extend(scan.tokens);
+                    if let Some(before) = scan.oldest_signature {
+                        history.gaps.push(HistoryGap { before, until: gap.until });
+                    }
+                    scan.error
+                } else {
+                    let scan = scan_deployer(client, deployer, parse(&history.oldest_signature), None).await?;
+                    history.tokens.extend(scan.tokens);
+                    history.oldest_signature = scan.oldest_signature;
+                    scan.error
+                };
+                history.tokens.sort_by_key(|token| Reverse(token.created_at));
+                error
+            } else {
+                let scan = scan_deployer(client, deployer, None, parse(&history.newest_signature)).await?;
+                let mut tokens = scan.tokens;
+                tokens.extend(history.tokens);
+                history.tokens = tokens;
+                match (&history.newest_signature, scan.oldest_signature) {
+                    (None, oldest_signature) => history.oldest_signature = oldest_signature,
+                    (Some(until), Some(before)) => history.gaps.push(HistoryGap { before, until: until.clone() }),
+                    (Some(_), None) => {}
+                }
+                history.newest_signature = scan.newest_signature.or(history.newest_signature);
+                scan.error
+            };
+
+            let error = error.or(refresh_states(client, &mut history.tokens).await);
+            history.updated_at = unix_timestamp();
+
+            Ok::<_, ClientError>((history, error))
+        })
+        .await?;
+
+    save_deployer_history(deployer, &history)?;
+
+    if let Some(err) = error {
+        println!(
+            "{}
+  - {}",
+            style("The scan stopped early, what was found is cached and the rest is left for ScanOlder ⚠️").yellow(),
+            style(err.to_string()).dim()
+        );
+    }
+
+    Ok(history)
+}
+
+/// Reads the state of every token, a token whose curve cannot be read keeps its last state and
+/// the first error is returned.
+async fn refresh_states(client: &RpcClient, tokens: &mut [DeployedToken]) -> Option<ClientError> {
+    let mut error = None;
+    for token in tokens.iter_mut() {
+        match get_token_state(client, &token.mint_id).await {
+            Ok(state) => token.state = state,
+            Err(err) => error = error.or(Some(err))
+        }
+    }
+
+    error
+}
@@ -0,0 +1,312 @@
+use console::style;
+use dialoguer::theme::ColorfulTheme;
+use crate::jobs::format_age;
+use crate::jobs::ladder::set_ladder_status;
+use crate::jobs::ladder::spawn_ladder_job;
+use crate::storage::ladders::remove_ladder;
+use crate::storage::ladders::LadderStatus;
+use crate::storage::ladders::SellLadder;
+use crate::storage::ladders::Tranche;
+use crate::storage::ladders::TrancheOutcome;
+use crate::storage::ladders::TrancheTrigger;
+use crate::storage::transactions::unix_timestamp;
+use moonbois_core::ProjectDTO;
+use std::sync::Arc;
+use std::time::Duration;
+
+use dialoguer::Confirm;
+use dialoguer::FuzzySelect;
+use dialoguer::Input;
+
+use crate::AppData;
+use crate::AppError;
+use crate::Menu;
+
+use super::main::MainMenu;
+use super::project::ProjectMenu;
+use super::Handler;
+
+static MAX_TRANCHES: usize = 10;
+
+pub enum SellLadderOptions {
+    NewLadder,
+    Pause,
+    Resume,
+    Cancel,
+    Clear,
+    Back
+}
+
+impl ToString for SellLadderOptions {
+    fn to_string(&self) -> String {
+        match self {
+            Self::NewLadder => "NewLadder".to_string(),
+            Self::Pause => "Pause".to_string(),
+            Self::Resume => "Resume".to_string(),
+            Self::Cancel => "Cancel".to_string(),
+            Self::Clear => "Clear".to_string(),
+            Self::Back => "Back".to_string()
+        }
+    }
+}
+
+pub enum LadderModeOptions {
+    TimeSpaced,
+    PriceLevels
+}
+
+impl ToString for LadderModeOptions {
+    fn to_string(&self) -> String {
+        match self {
+            Self::TimeSpaced => "TimeSpaced".to_string(),
+            Self::PriceLevels => "PriceLevels".to_string()
+        }
+    }
+}
+
+impl From<usize> for LadderModeOptions {
+    fn from(value: usize) -> Self {
+        match value {
+            0 => Self::TimeSpaced,
+            1 => Self::PriceLevels,
+            _ => panic!("Received invalid ladder mode index")
+        }
+    }
+}
+
+pub struct SellLadderMenu;
+impl Handler for SellLadderMenu {
+    async fn handle(&self, app_data: &Arc<AppData>) -> Result<Option<Menu>, (Menu, AppError)> {
+        let project: ProjectDTO = match app_data.active_project.read().await.0 {
+            Some(project_id) => match app_data.projects.read().await.get(&project_id) {
+                Some(project) => project.clone(),
+                None => return Err((Menu::Main(MainMenu), AppError::ProjectNotFound))
+            },
+            None => return Err((Menu::Main(MainMenu), AppError::ProjectNotFound))
+        };
+
+        let ladder = app_data.sell_ladders.read().await.0.get(&project.id).cloned();
+        let options = match &ladder {
+            Some(ladder) => {
+                print_ladder(ladder);
+                match ladder.status {
+                    LadderStatus::Running => vec![SellLadderOptions::Pause, SellLadderOptions::Cancel, SellLadderOptions::Back],
+                    LadderStatus::Paused => vec![SellLadderOptions::Resume, SellLadderOptions::Cancel, SellLadderOptions::Back],
+                    LadderStatus::Completed | LadderStatus::Cancelled => vec![SellLadderOptions::NewLadder, SellLadderOptions::Clear, SellLadderOptions::Back]
+                }
+            },
+            None => {
+                println!("{}", style("No sell ladder on this project").dim());
+                vec![SellLadderOptions::NewLadder, SellLadderOptions::Back]
+            }
+        };
+
+        let selection = FuzzySelect::with_theme(&ColorfulTheme::default())
+            .with_prompt("Sell ladder")
+            .default(0)
+            .items(&options)
+            .interact()
+            .map_err(|err| (Menu::ProjectMenu(ProjectMenu), AppError::from(err)))?;
+
+        match options[selection] {
+            SellLadderOptions::NewLadder => {
+                let (tokens, funded): (u64, usize) = match &app_data.user.read().await.0 {
+                    Some(user) => (
+                        user.wallets.values().filter_map(|wallet| wallet.token_balance).sum(),
+                        user.wallets.values().filter(|wallet| wallet.sol_balance > 0).count()
+                    ),
+                    None => return Err((Menu::Main(MainMenu), AppError::UserNotFound))
+                };
+
+                // Tranches split the position by moving tokens between snipers, a lone wallet can only sell all at once
+                let blocked = if tokens == 0 {
+                    Some("None of your wallets hold this token")
+                } else if funded < 2 {
+                    Some("A ladder needs at least two funded sniper wallets to split the position between")
+                } else {
+                    None
+                };
+
+                if let Some(reason) = blocked {
+                    println!("{}", style(reason).dim());
+                    FuzzySelect::with_theme(&ColorfulTheme::default())
+                        .item("Back")
+                        .default(0)
+                        .interact()
+                        .unwrap();
+
+                    return Ok(Some(Menu::SellLadder(SellLadderMenu)))
+                }
+
+                let Some(ladder) = prompt_ladder(&project)
+                    .map_err(|err| (Menu::SellLadder(SellLadderMenu), err))? else {
+                        return Ok(Some(Menu::SellLadder(SellLadderMenu)))
+                    };
+
+                spawn_ladder_job(app_data, ladder).await;
+            },
+            SellLadderOptions::Pause => set_ladder_status(app_data, project.id, LadderStatus::Paused).await,
+            SellLadderOptions::Resume => set_ladder_status(app_data, project.id, LadderStatus::Running).await,
+            SellLadderOptions::Cancel => {
+                let confirm = Confirm::with_theme(&ColorfulTheme::default())
+                    .with_prompt("Cancel the remaining tranches? A tranche already selling still completes")
+                    .default(false)
+                    .interact()
+                    .map_err(|err| (Menu::SellLadder(SellLadderMenu), AppError::from(err)))?;
+
+                if confirm {
+                    set_ladder_status(app_data, project.id, LadderStatus::Cancelled).await;
+                }
+            },
+            SellLadderOptions::Clear => {
+                app_data.sell_ladders.write().await.0.remove(&project.id);
+                remove_ladder(project.id)
+                    .map_err(|err| (Menu::SellLadder(SellLadderMenu), AppError::from(err)))?;
+            },
+            SellLadderOptions::Back => return Ok(Some(Menu::ProjectMenu(ProjectMenu)))
+        }
+
+        Ok(Some(Menu::SellLadder(SellLadderMenu)))
+    }
+}
+
+fn print_ladder(ladder: &SellLadder) {
+    println!(
+        "status: {}\nstarted: {} ago\nprogress: {}/{} tranches",
+        ladder.status.to_string(),
+        format_age(Duration::from_secs(unix_timestamp().saturating_sub(ladder.created_at))),
+        ladder.sold_count(),
+        ladder.tranches.len()
+    );
+
+    let next = ladder.next_tranche();
+    for (index, tranche) in ladder.tranches.iter().enumerate() {
+        let trigger = match tranche.trigger {
+            TrancheTrigger::Delay { secs } => format!("{} after the previous tranche", format_age(Duration::from_secs(secs))),
+            TrancheTrigger::Price { price } => format!("at {} SOL", price)
+        };
+        let outcome = match &tranche.outcome {
+            Some(outcome @ TrancheOutcome::Sold { .. }) => style(outcome.to_string()).green(),
+            Some(outcome @ TrancheOutcome::Failed { .. }) => style(outcome.to_string()).red(),
+            None if next == Some(index) && !ladder.is_finished() => style("next".to_string()).yellow(),
+            None => style("waiting".to_string()).dim()
+        };
+
+        println!("  {}. {}% {} {}", index + 1, tranche.percent, trigger, outcome);
+    }
+}
+
+/// Walks through the tranches of a new ladder. Returns `None` when a prompt was cancelled.
+fn prompt_ladder(project: &ProjectDTO) -> Result<Option<SellLadder>, AppError> {
+    let count: usize = match Input::with_theme(&ColorfulTheme::default())
+        .with_prompt("Enter the number of tranches")
+        .default(4)
+        .validate_with(|val: &usize| -> Result<(), String> {
+            if *val < 2 || *val > MAX_TRANCHES {
+                return Err(format!("Enter between 2 and {} tranches", MAX_TRANCHES))
+            }
+
+            Ok(())
+        })
+        .interact_text()
+        .unwrap() {
+            Some(count) => count,
+            None => return Ok(None)
+        };
+
+    let mode = FuzzySelect::with_theme(&ColorfulTheme::default())
+        .with_prompt("Trigger the tranches by")
+        .default(0)
+        .items(&[LadderModeOptions::TimeSpaced, LadderModeOptions::PriceLevels])
+        .interact()?;
+    let mode = LadderModeOptions::from(mode);
+
+    let mut tranches = vec![];
+    let mut remaining = 100f64;
+    // The job only watches the first unsold tranche, so price levels have to climb
+    let mut last_price = 0f64;
+    for index in 0..count {
+        let percent = if index == count - 1 {
+            remaining
+        } else {
+            let left = count - index;
+            match Input::with_theme(&ColorfulTheme::default())
+                .with_prompt(format!("Enter the percentage of the position for tranche {}", index + 1))
+                .default(remaining / left as f64)
+                .validate_with(|val: &f64| -> Result<(), String> {
+                    if *val <= 0f64 || *val >= remaining {
+                        return Err(format!("Enter a percentage between 0 and {}", remaining))
+                    }
+
+                    Ok(())
+                })
+                .interact_text()
+                .unwrap() {
+                    Some(percent) => percent,
+                    None => return Ok(None)
+                }
+        };
+        remaining -= percent;
+
+        let trigger = match mode {
+            LadderModeOptions::TimeSpaced => {
+                let minutes: u64 = match Input::with_theme(&ColorfulTheme::default())
+                    .with_prompt(format!("Enter the minutes to wait before tranche {}", index + 1))
+                    .default(if index == 0 { 0 } else { 5 })
+                    .interact_text()
+                    .unwrap() {
+                        Some(minutes) => minutes,
+                        None => return Ok(None)
+                    };
+
+                TrancheTrigger::Delay { secs: minutes * 60 }
+            },
+            LadderModeOptions::PriceLevels => {
+                let price: f64 = match Input::with_theme(&ColorfulTheme::default())
+                    .with_prompt(format!("Enter the token price in SOL that triggers tranche {}", index + 1))
+                    .validate_with(|val: &f64| -> Result<(), String> {
+                        if *val <= last_price {
+                            return Err(format!("Enter a price above {}", last_price))
+                        }
+
+                        Ok(())
+                    })
+                    .interact_text()
+                    .unwrap() {
+                        Some(price) => price,
+                        None => return Ok(None)
+                    };
+                last_price = price;
+
+                TrancheTrigger::Price { price }
+            }
+        };
+
+        tranches.push(Tranche::new(percent, trigger));
+    }
+
+    let mut ladder = SellLadder {
+        project_id: project.id,
+        mint: project.pumpfun.mint_id.to_string(),
+        created_at: unix_timestamp(),
+        status: LadderStatus::Running,
+        tranches
+    };
+    print_ladder(&ladder);
+
+    let confirm = Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt(format!(
+            "{}\nStart the ladder?",
+            style("Tranches smaller than a wallet first move tokens between your snipers, so each one sells its exact percentage").dim()
+        ))
+        .default(false)
+        .interact()?;
+
+    if !confirm {
+        return Ok(None)
+    }
+
+    // Delays of the first tranche count from the moment the ladder starts
+    ladder.created_at = unix_timestamp();
+    Ok(Some(ladder))
+}
@@ -6,11 +6,21 @@ use dialoguer::FuzzySelect;
 use crate::AppData;
 use crate::AppError;
 use crate::Menu;
+use crate::paper::dispatch;
 
+use super::address_book::AddressBookMenu;
+use super::browser::SelectProject;
+use super::panic::PanicExit;
+use super::paper::PaperAccount;
+use super::risk::RiskLimits;
+use super::portfolio::Portfolio;
 use super::project::CreateProject;
-use super::project::SelectProject;
+use super::project::UntrackedTokens;
 use super::snipe::CreateSnipe;
+use super::snipe::PendingSnipes;
+use super::transactions::Transactions;
 use super::wallet::ImportWallet;
+use super::wallet::ReclaimRent;
 use super::wallet::RecoverSol;
 use super::wallet::WalletMenu;
 use super::Handler;
@@ -18,12 +28,21 @@ use super::Handler;
 pub struct MainMenu;
 pub enum MainMenuOptions {
     Snipe,
+    PendingSnipes,
     NewProject,
     LoadProject,
+    Portfolio,
+    PanicExit,
+    UntrackedTokens,
     Wallets,
+    AddressBook,
+    RiskLimits,
     RecoverSOL,
+    ReclaimRent,
     ImportWallet,
+    Transactions,
     Export,
+    PaperAccount,
     Exit
 }
 
@@ -31,33 +50,26 @@ impl ToString for MainMenuOptions {
     fn to_string(&self) -> String {
         match self {
             Self::Snipe => "Snipe".to_string(),
+            Self::PendingSnipes => "PendingSnipes".to_string(),
             Self::NewProject => "ImportToken".to_string(),
             Self::LoadProject => "Tokens".to_string(),
+            Self::Portfolio => "Portfolio".to_string(),
+            Self::PanicExit => "PanicExit".to_string(),
+            Self::UntrackedTokens => "UntrackedTokens".to_string(),
             Self::Wallets => "Wallets".to_string(),
+            Self::AddressBook => "AddressBook".to_string(),
+            Self::RiskLimits => "RiskLimits".to_string(),
             Self::ImportWallet => "ImportWallet".to_string(),
             Self::RecoverSOL => "RecoverSOL".to_string(),
+            Self::ReclaimRent => "ReclaimRent".to_string(),
+            Self::Transactions => "Transactions".to_string(),
             Self::Export => "Export".to_string(),
+            Self::PaperAccount => "PaperAccount".to_string(),
             Self::Exit => format!("{}", "Exit"),
         }
     }
 }
 
-impl From<usize> for MainMenuOptions {
-    fn from(value: usize) -> Self {
-        match value {
-            0 => Self::Snipe,
-            1 => Self::NewProject,
-            2 => Self::LoadProject,
-            3 => Self::Wallets,
-            4 => Self::ImportWallet,
-            5 => Self::RecoverSOL,
-            6 => Self::Export,
-            7 => Self::Exit,
-            _ => panic!("Received invalid main menu index")
-        }
-    }
-}
-
 impl Handler for MainMenu {
     async fn handle(&self, app_data: &Arc<AppData>) -> Result<Option<Menu>, (Menu, AppError)> {
         let mut active_project = app_data.active_project.write().await;
@@ -76,30 +88,52 @@ impl Handler for MainMenu {
         }
         drop(user);
 
-        let selection = match FuzzySelect::with_theme(&ColorfulTheme::default()).with_prompt("Main menu").default(0).items(vec![
+        let mut items = vec![
             MainMenuOptions::Snipe,
+            MainMenuOptions::PendingSnipes,
             MainMenuOptions::NewProject, 
             MainMenuOptions::LoadProject, 
+            MainMenuOptions::Portfolio,
+            MainMenuOptions::PanicExit,
+            MainMenuOptions::UntrackedTokens,
             MainMenuOptions::Wallets, 
+            MainMenuOptions::AddressBook,
+            MainMenuOptions::RiskLimits,
             MainMenuOptions::ImportWallet,
             MainMenuOptions::RecoverSOL, 
-            MainMenuOptions::Export, 
-            MainMenuOptions::Exit
-        ]).interact() {
+            MainMenuOptions::ReclaimRent,
+            MainMenuOptions::Transactions,
+            MainMenuOptions::Export
+        ];
+        if app_data.paper.is_some() {
+            items.push(MainMenuOptions::PaperAccount);
+        }
+        items.push(MainMenuOptions::Exit);
+
+        let selection = match FuzzySelect::with_theme(&ColorfulTheme::default()).with_prompt("Main menu").default(0).items(&items).interact() {
             Ok(selection) => selection,
             Err(err) => {
                 return Err((Menu::Main(MainMenu), AppError::from(err)));
             }
         };
 
-        match MainMenuOptions::from(selection) {
+        match items[selection] {
             MainMenuOptions::Snipe => return Ok(Some(Menu::CreateSnipe(CreateSnipe))),
+            MainMenuOptions::PendingSnipes => return Ok(Some(Menu::PendingSnipes(PendingSnipes))),
             MainMenuOptions::NewProject => return Ok(Some(Menu::CreateProject(CreateProject))),
-            MainMenuOptions::LoadProject => return Ok(Some(Menu::SelectProject(SelectProject))),
+            MainMenuOptions::LoadProject => return Ok(Some(Menu::SelectProject(SelectProject::new()))),
+            MainMenuOptions::Portfolio => return Ok(Some(Menu::Portfolio(Portfolio::new()))),
+            MainMenuOptions::PanicExit => return Ok(Some(Menu::PanicExit(PanicExit))),
+            MainMenuOptions::UntrackedTokens => return Ok(Some(Menu::UntrackedTokens(UntrackedTokens))),
             MainMenuOptions::Wallets => return Ok(Some(Menu::Wallet(WalletMenu))),
+            MainMenuOptions::AddressBook => return Ok(Some(Menu::AddressBook(AddressBookMenu))),
+            MainMenuOptions::RiskLimits => return Ok(Some(Menu::RiskLimits(RiskLimits))),
             MainMenuOptions::ImportWallet => return Ok(Some(Menu::ImportWallet(ImportWallet))),
             MainMenuOptions::RecoverSOL => return Ok(Some(Menu::RecoverSol(RecoverSol))),
+            MainMenuOptions::ReclaimRent => return Ok(Some(Menu::ReclaimRent(ReclaimRent))),
+            MainMenuOptions::Transactions => return Ok(Some(Menu::Transactions(Transactions))),
             MainMenuOptions::Export => return Ok(Some(Menu::Export(Export))),
+            MainMenuOptions::PaperAccount => return Ok(Some(Menu::PaperAccount(PaperAccount))),
             MainMenuOptions::Exit => return Ok(None)
         }
     }
@@ -108,6 +142,9 @@ impl Handler for MainMenu {
 pub struct Export;
 impl Handler for Export {
     async fn handle(&self, app_data: &Arc<AppData>) -> Result<Option<Menu>, (Menu, AppError)> {
+        dispatch::live_only(app_data, "Export")
+            .map_err(|err| (Menu::Main(MainMenu), err))?;
+
         let rpc_client = app_data.rpc_client.read().await;
         let export = rpc_client.export().await.map_err(|err| {
                 (Menu::Main(MainMenu), AppError::from(err))
@@ -11,6 +11,17 @@ pub mod snipe;
 pub mod main;
 pub mod trade;
 pub mod bumps;
+pub mod transactions;
+pub mod presets;
+pub mod deployer;
+pub mod portfolio;
+pub mod browser;
+pub mod address_book;
+pub mod risk;
+pub mod panic;
+pub mod trade_rules;
+pub mod ladder;
+pub mod paper;
 
 pub trait Handler {
     fn handle(&self, app_data: &Arc<AppData>) -> impl std::future::Future<Output = Result<Option<Menu>, (Menu, AppError)>> + Send;
@@ -0,0 +1,295 @@
+use console::style;
+use dialoguer::theme::ColorfulTheme;
+use crate::chain::analysis::PUMPFUN_DECIMALS;
+use crate::dialogue::loader::Loader;
+use crate::paper::dispatch;
+use crate::storage::transactions::TransactionRecord;
+use moonbois_core::ProjectDTO;
+use moonbois_core::WalletDTO;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::pubkey::Pubkey;
+use std::sync::Arc;
+use tokio::task::JoinSet;
+
+use dialoguer::Confirm;
+use dialoguer::FuzzySelect;
+
+use crate::AppData;
+use crate::AppError;
+use crate::Menu;
+
+use super::main::MainMenu;
+use super::transactions::confirm_transactions;
+use super::Handler;
+
+/// Outcome of the panic exit for a single project.
+struct ExitResult {
+    project: ProjectDTO,
+    tokens: u64,
+    result: Result<(), String>
+}
+
+/// Sells every position concurrently and optionally recovers the sniper SOL to the fee payer.
+pub struct PanicExit;
+impl Handler for PanicExit {
+    async fn handle(&self, app_data: &Arc<AppData>) -> Result<Option<Menu>, (Menu, AppError)> {
+        let (fee_payer, wallets): (Pubkey, Vec<WalletDTO>) = match &app_data.user.read().await.0 {
+            Some(user) => (user.public_key, user.wallets.values().cloned().collect()),
+            None => return Err((Menu::Main(MainMenu), AppError::UserNotFound))
+        };
+
+        let (positions, unloaded) = Loader::new()
+            .with_prompt("loading positions")
+            .interact(load_positions(app_data))
+            .await
+            .map_err(|err| (Menu::Main(MainMenu), err))?;
+
+        for (project, err) in unloaded.iter() {
+            println!("{}\n  - {}", style(format!("Unable to load the balances of {} ⚠️", project.name)).yellow(), style(err).dim());
+        }
+
+        if positions.is_empty() {
+            println!("{}", style("No project holds any tokens").dim());
+        } else {
+            println!("{:<16} {:>20} {:>8}", "project", "tokens", "wallets");
+            for (project, tokens, wallet_count) in positions.iter() {
+                println!(
+                    "{:<16} {:>20} {:>8}",
+                    project.name,
+                    ui_amount(*tokens),
+                    wallet_count
+                );
+            }
+            println!("");
+        }
+
+        let (mut results, mut records) = if positions.is_empty() {
+            (vec![], vec![])
+        } else {
+            let confirm = Confirm::with_theme(&ColorfulTheme::default())
+                .with_prompt(format!(
+                    "{}\nDo you want to continue?",
+                    style(format!("This will auto_sell {} projects at market", positions.len())).yellow()
+                ))
+                .default(false)
+                .interact()
+                .map_err(|err| (Menu::Main(MainMenu), AppError::from(err)))?;
+
+            if !confirm {
+                return Ok(Some(Menu::Main(MainMenu)))
+            }
+
+            let (results, records) = sell_positions(app_data, positions).await;
+            (results, confirm_transactions(app_data, records).await)
+        };
+        results.extend(unloaded.into_iter().map(|(project, err)| ExitResult {
+            project,
+            tokens: 0,
+            result: Err(format!("balances not loaded, nothing sold - {}", err))
+        }));
+
+        let recover = Confirm::with_theme(&ColorfulTheme::default())
+            .with_prompt("Recover SOL from every sniper to the fee_payer?")
+            .default(true)
+            .interact()
+            .map_err(|err| (Menu::Main(MainMenu), AppError::from(err)))?;
+
+        let recovered = if recover {
+            let recover_records = confirm_transactions(app_data, recover_sol(app_data, fee_payer, wallets).await).await;
+            let recovered = recover_records.iter()
+                .filter(|record| record.succeeded())
+                .filter_map(|record| record.lamports)
+                .sum();
+            records.extend(recover_records);
+
+            Some(recovered)
+        } else { None };
+
+        println!("\n{:<16} {:>20} {:<}", "project", "tokens", "result");
+        for exit in results.iter() {
+            let sells: Vec<&TransactionRecord> = records.iter()
+                .filter(|record| record.action == "auto_sell" && record.project_id == Some(exit.project.id))
+                .collect();
+
+            println!(
+                "{:<16} {:>20} {}",
+                exit.project.name,
+                ui_amount(exit.tokens),
+                match &exit.result {
+                    Ok(_) if sells.iter().all(|record| record.succeeded()) => style(format!("sold - {} transactions", sells.len())).green(),
+                    Ok(_) => style(format!(
+                        "partially sold - {} of {} transactions failed",
+                        sells.iter().filter(|record| !record.succeeded()).count(),
+                        sells.len()
+                    )).yellow(),
+                    Err(err) => style(format!("failed - {}", err)).red()
+                }
+            );
+        }
+
+        if let Some(recovered) = recovered {
+            println!("recovered: {} {}", recovered as f64 / LAMPORTS_PER_SOL as f64, style("SOL").cyan());
+        }
+
+        FuzzySelect::with_theme(&ColorfulTheme::default())
+            .item("Back")
+            .default(0)
+            .interact()
+            .unwrap();
+
+        Ok(Some(Menu::Main(MainMenu)))
+    }
+}
+
+fn ui_amount(tokens: u64) -> f64 {
+    tokens as f64 / 10f64.powi(PUMPFUN_DECIMALS as i32)
+}
+
+/// Every project with a non-zero sniper balance, with the total raw balance and wallet count,
+/// next to the projects whose balances could not be loaded so the rest can still be sold.
+/// Projects are fetched here rather than read from the sync loop so this also works right after login.
+async fn load_positions(app_data: &Arc<AppData>) -> Result<(Vec<(ProjectDTO, u64, usize)>, Vec<(ProjectDTO, String)>), AppError> {
+    let projects = dispatch::user_projects(app_data).await?;
+
+    let mut tasks = JoinSet::new();
+    for (_, project) in projects.clone() {
+        let app_data = Arc::clone(app_data);
+        tasks.spawn(async move {
+            let balances = dispatch::token_balances(&app_data, project.pumpfun.mint_id).await;
+
+            (project, balances)
+        });
+    }
+
+    let mut positions = vec![];
+    let mut unloaded = vec![];
+    let mut joined = vec![];
+    while let Some(result) = tasks.join_next().await {
+        match result {
+            Ok((project, Ok(balances))) => {
+                joined.push(project.id);
+                let total = balances.iter().map(|(_, amount)| amount).sum::<u64>();
+                if total > 0 {
+                    positions.push((project, total, balances.len()));
+                }
+            },
+            Ok((project, Err(err))) => {
+                joined.push(project.id);
+                unloaded.push((project, err.to_string()));
+            },
+            Err(_) => {}
+        }
+    }
+
+    // A panicked task takes its project with it, report it as not loaded
+    for (id, project) in projects {
+        if !joined.contains(&id) {
+            unloaded.push((project, "balance task failed".to_string()));
+        }
+    }
+    positions.sort_by(|a, b| b.1.cmp(&a.1));
+
+    Ok((positions, unloaded))
+}
+
+/// Runs `auto_sell` for every position at once and prints each project as it finishes.
+async fn sell_positions(app_data: &Arc<AppData>, positions: Vec<(ProjectDTO, u64, usize)>) -> (Vec<ExitResult>, Vec<TransactionRecord>) {
+    let total = positions.len();
+    let mut tasks = JoinSet::new();
+    for (project, tokens, _) in positions {
+        println!("{} {}", style("auto_sell in progress").dim(), project.name);
+
+        let app_data = Arc::clone(app_data);
+        tasks.spawn(async move {
+            let result = dispatch::auto_sell(&app_data, project.id).await;
+
+            (project, tokens, result)
+        });
+    }
+
+    let mut results = vec![];
+    let mut records = vec![];
+    while let Some(joined) = tasks.join_next().await {
+        let (project, tokens, result) = match joined {
+            Ok(joined) => joined,
+            Err(err) => {
+                println!("{}\n  - {}", style("auto_sell task failed ⚠️").yellow(), style(err.to_string()).dim());
+                continue;
+            }
+        };
+
+        let result = match result {
+            Ok(signatures) => {
+                println!(
+                    "[{}/{}] {} {}",
+                    results.len() + 1,
+                    total,
+                    project.name,
+                    style(format!("submitted {} transactions", signatures.len())).green()
+                );
+                records.extend(signatures.into_iter().map(|signature| {
+                    TransactionRecord::new("auto_sell", signature).with_project(project.id)
+                }));
+
+                Ok(())
+            },
+            Err(err) => {
+                println!("[{}/{}] {} {}", results.len() + 1, total, project.name, style(format!("failed - {}", err)).red());
+
+                Err(err.to_string())
+            }
+        };
+
+        results.push(ExitResult { project, tokens, result });
+    }
+
+    (results, records)
+}
+
+/// Sends the current balance of every sniper, minus the quoted transfer fee, to the fee payer at once.
+async fn recover_sol(app_data: &Arc<AppData>, fee_payer: Pubkey, wallets: Vec<WalletDTO>) -> Vec<TransactionRecord> {
+    let total = wallets.len();
+    let mut tasks = JoinSet::new();
+    for wallet in wallets {
+        let app_data = Arc::clone(app_data);
+        tasks.spawn(async move {
+            let fee = dispatch::transfer_fee(&app_data, &wallet.public_key).await?;
+            let lamports = dispatch::sol_balance(&app_data, &wallet.public_key).await?
+                .saturating_sub(fee);
+            if lamports == 0 {
+                return Ok::<_, AppError>((wallet, None))
+            }
+
+            let signature = dispatch::transfer_sol_from_sniper(&app_data, &wallet, fee_payer, lamports).await?;
+
+            Ok((wallet, Some((signature, lamports))))
+        });
+    }
+
+    let mut records = vec![];
+    let mut finished = 0;
+    while let Some(joined) = tasks.join_next().await {
+        finished += 1;
+        match joined {
+            Ok(Ok((wallet, Some((signature, lamports))))) => {
+                println!(
+                    "[{}/{}] {} {}",
+                    finished,
+                    total,
+                    &wallet.public_key.to_string()[0..5],
+                    style(format!("recovering {} SOL", lamports as f64 / LAMPORTS_PER_SOL as f64)).green()
+                );
+                records.push(TransactionRecord::new("recover_sol", signature)
+                    .with_wallet(wallet.public_key)
+                    .with_lamports(lamports));
+            },
+            Ok(Ok((wallet, None))) => {
+                println!("[{}/{}] {} {}", finished, total, &wallet.public_key.to_string()[0..5], style("nothing to recover").dim());
+            },
+            Ok(Err(err)) => println!("[{}/{}] {}", finished, total, style(format!("recover_sol failed - {}", err)).red()),
+            Err(err) => println!("[{}/{}] {}", finished, total, style(format!("recover_sol task failed - {}", err)).red())
+        }
+    }
+
+    records
+}
@@ -0,0 +1,163 @@
+use console::style;
+use dialoguer::theme::ColorfulTheme;
+use crate::paper::dispatch::sync_paper;
+use crate::paper::ledger::save_ledger;
+use crate::paper::ledger::PaperLedger;
+use crate::paper::PaperConfig;
+use crate::paper::PaperError;
+use crate::storage;
+use crate::storage::transactions::load_transactions;
+use crate::storage::transactions::project_flows;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use std::sync::Arc;
+
+use dialoguer::Confirm;
+use dialoguer::FuzzySelect;
+
+use crate::AppData;
+use crate::AppError;
+use crate::Menu;
+
+use super::main::MainMenu;
+use super::Handler;
+
+pub enum PaperAccountOptions {
+    Reset,
+    Back
+}
+
+impl ToString for PaperAccountOptions {
+    fn to_string(&self) -> String {
+        match self {
+            Self::Reset => "Reset".to_string(),
+            Self::Back => "Back".to_string()
+        }
+    }
+}
+
+impl From<usize> for PaperAccountOptions {
+    fn from(value: usize) -> Self {
+        match value {
+            0 => Self::Reset,
+            1 => Self::Back,
+            _ => panic!("Received invalid paper account index")
+        }
+    }
+}
+
+fn format_sol(lamports: f64) -> String {
+    format!("{:.4} {}", lamports / LAMPORTS_PER_SOL as f64, style("SOL").cyan())
+}
+
+fn format_pnl(lamports: f64) -> String {
+    let pnl = format!("{:+.4} SOL", lamports / LAMPORTS_PER_SOL as f64);
+    if lamports < 0.0 {
+        style(pnl).red().to_string()
+    } else {
+        style(pnl).green().to_string()
+    }
+}
+
+/// Balances and P&L of the paper ledger. Open positions are valued at what selling them on the
+/// simulated curve would return, SOL sent outside the ledger counts as a loss.
+pub struct PaperAccount;
+impl Handler for PaperAccount {
+    async fn handle(&self, app_data: &Arc<AppData>) -> Result<Option<Menu>, (Menu, AppError)> {
+        let Some(paper) = &app_data.paper else {
+            return Err((Menu::Main(MainMenu), AppError::from(PaperError::Unsupported("PaperAccount outside paper mode"))))
+        };
+
+        let records = load_transactions()
+            .map_err(|err| (Menu::Main(MainMenu), AppError::from(err)))?;
+
+        let ledger = paper.read().await;
+        println!(
+            "fee_payer: {}\nsnipers: {}\nstarting_balance: {}",
+            format_sol(ledger.lamports as f64),
+            format_sol(ledger.wallets.iter().map(|wallet| wallet.lamports).sum::<u64>() as f64),
+            format_sol(ledger.starting_lamports as f64)
+        );
+
+        let mut positions_value = 0u64;
+        for project in ledger.projects.iter() {
+            let value = ledger.position_value(project);
+            let flows = project_flows(&records, project.id);
+            positions_value += value;
+
+            if value == 0 && flows.spent == 0 && flows.received == 0 {
+                continue;
+            }
+            println!(
+                "  - {} {} {}",
+                style(project.name.to_uppercase()).magenta(),
+                format_sol(value as f64),
+                format_pnl(value as f64 + flows.received as f64 - flows.spent as f64)
+            );
+        }
+
+        let total_value = ledger.total_lamports() + positions_value;
+        println!(
+            "total_value: {}\npnl: {}",
+            format_sol(total_value as f64),
+            format_pnl(total_value as f64 - ledger.starting_lamports as f64)
+        );
+        drop(ledger);
+
+        let selection = FuzzySelect::with_theme(&ColorfulTheme::default())
+            .with_prompt("Paper account")
+            .default(0)
+            .items(&[
+                PaperAccountOptions::Reset,
+                PaperAccountOptions::Back
+            ])
+            .interact()
+            .map_err(|err| (Menu::Main(MainMenu), AppError::from(err)))?;
+
+        match PaperAccountOptions::from(selection) {
+            PaperAccountOptions::Reset => {
+                let reset = Confirm::with_theme(&ColorfulTheme::default())
+                    .with_prompt("Reset the paper account? Its trades, snipes, rules and ladders are deleted, settings are kept")
+                    .default(false)
+                    .interact()
+                    .unwrap();
+
+                if reset {
+                    reset_paper_account(app_data).await
+                        .map_err(|err| (Menu::PaperAccount(PaperAccount), err))?;
+                }
+
+                Ok(Some(Menu::PaperAccount(PaperAccount)))
+            },
+            PaperAccountOptions::Back => Ok(Some(Menu::Main(MainMenu)))
+        }
+    }
+}
+
+/// Starts over from the configured virtual balances and drops the trading state stored in paper
+/// mode. Settings such as the risk policy and snipe presets are kept.
+async fn reset_paper_account(app_data: &Arc<AppData>) -> Result<(), AppError> {
+    let Some(paper) = &app_data.paper else {
+        return Ok(())
+    };
+
+    for job in app_data.snipes.write().await.0.values_mut() {
+        job.abort();
+    }
+    app_data.snipes.write().await.0.clear();
+    app_data.sell_ladders.write().await.0.clear();
+    app_data.active_project.write().await.0 = None;
+
+    for file in storage::trading_state_files() {
+        storage::remove(file)?;
+    }
+
+    let ledger = PaperLedger::new(&PaperConfig::from_env());
+    save_ledger(&ledger)?;
+    *paper.write().await = ledger;
+
+    sync_paper(app_data, paper, false).await
+        .map_err(AppError::Unhandled)?;
+    app_data.notifications.write().await.push("Paper account reset".to_string());
+
+    Ok(())
+}
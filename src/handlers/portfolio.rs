@@ -0,0 +1,219 @@
+use console::style;
+use dialoguer::theme::ColorfulTheme;
+use crate::chain::analysis::PUMPFUN_DECIMALS;
+use crate::dialogue::loader::Loader;
+use crate::paper::dispatch;
+use crate::storage::export_csv;
+use crate::storage::transactions::unix_timestamp;
+use moonbois_core::ProjectDTO;
+use std::sync::Arc;
+
+use dialoguer::FuzzySelect;
+
+use crate::AppData;
+use crate::AppError;
+use crate::Menu;
+
+use super::main::MainMenu;
+use super::Handler;
+
+#[derive(Clone, Copy)]
+pub enum PortfolioSort {
+    Value,
+    Balance,
+    Name
+}
+
+pub enum PortfolioOptions {
+    SortByValue,
+    SortByBalance,
+    SortByName,
+    Refresh,
+    Export,
+    Back
+}
+
+impl ToString for PortfolioOptions {
+    fn to_string(&self) -> String {
+        match self {
+            Self::SortByValue => "SortByValue".to_string(),
+            Self::SortByBalance => "SortByBalance".to_string(),
+            Self::SortByName => "SortByName".to_string(),
+            Self::Refresh => "Refresh".to_string(),
+            Self::Export => "Export".to_string(),
+            Self::Back => "Back".to_string()
+        }
+    }
+}
+
+impl From<usize> for PortfolioOptions {
+    fn from(value: usize) -> Self {
+        match value {
+            0 => Self::SortByValue,
+            1 => Self::SortByBalance,
+            2 => Self::SortByName,
+            3 => Self::Refresh,
+            4 => Self::Export,
+            5 => Self::Back,
+            _ => panic!("Received invalid portfolio index")
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Holding {
+    pub project: ProjectDTO,
+    /// Raw token balance per sniper wallet, wallets without tokens are left out.
+    pub wallets: Vec<(String, u64)>,
+    /// Curve price of one whole token in SOL, `None` once the curve completed or could not be read.
+    pub price: Option<f64>
+}
+
+impl Holding {
+    pub fn tokens(&self) -> u64 {
+        self.wallets.iter().map(|(_, amount)| amount).sum()
+    }
+
+    pub fn ui_tokens(&self) -> f64 {
+        self.tokens() as f64 / 10f64.powi(PUMPFUN_DECIMALS as i32)
+    }
+
+    pub fn value(&self) -> Option<f64> {
+        self.price.map(|price| price * self.ui_tokens())
+    }
+}
+
+pub struct Portfolio {
+    pub sort: PortfolioSort,
+    pub holdings: Option<Vec<Holding>>
+}
+impl Portfolio {
+    pub fn new() -> Self {
+        Self {
+            sort: PortfolioSort::Value,
+            holdings: None
+        }
+    }
+}
+impl Handler for Portfolio {
+    async fn handle(&self, app_data: &Arc<AppData>) -> Result<Option<Menu>, (Menu, AppError)> {
+        let mut holdings = match &self.holdings {
+            Some(holdings) => holdings.clone(),
+            None => load_holdings(app_data).await
+                .map_err(|err| (Menu::Main(MainMenu), err))?
+        };
+
+        match self.sort {
+            PortfolioSort::Value => holdings.sort_by(|a, b| b.value().unwrap_or(0.0).total_cmp(&a.value().unwrap_or(0.0))),
+            PortfolioSort::Balance => holdings.sort_by(|a, b| b.tokens().cmp(&a.tokens())),
+            PortfolioSort::Name => holdings.sort_by(|a, b| a.project.name.to_lowercase().cmp(&b.project.name.to_lowercase()))
+        }
+
+        println!("{:<16} {:>20} {:>16}", "project", "tokens", "value");
+        for holding in holdings.iter() {
+            println!(
+                "{:<16} {:>20} {:>16}",
+                style(holding.project.name.to_uppercase()).magenta(),
+                holding.ui_tokens(),
+                holding.value().map(|value| format!("{:.4} SOL", value)).unwrap_or("n/a".to_string())
+            );
+            for (wallet, amount) in holding.wallets.iter() {
+                println!(
+                    "  - {} {}",
+                    &wallet[0..5],
+                    style(format!("{} TOKENS", *amount as f64 / 10f64.powi(PUMPFUN_DECIMALS as i32))).dim()
+                );
+            }
+        }
+        if holdings.is_empty() {
+            println!("{}", style("No projects to show").dim());
+        }
+
+        let total_value: f64 = holdings.iter().filter_map(|holding| holding.value()).sum();
+        let unpriced = holdings.iter().filter(|holding| holding.tokens() > 0 && holding.price.is_none()).count();
+        println!("\ntotal_value: {:.4} {}", total_value, style("SOL").cyan());
+        if unpriced > 0 {
+            println!("{}", style(format!("{} holdings without a curve price are not included", unpriced)).dim());
+        }
+
+        let selection = FuzzySelect::with_theme(&ColorfulTheme::default())
+            .with_prompt("Portfolio")
+            .default(0)
+            .items(&[
+                PortfolioOptions::SortByValue,
+                PortfolioOptions::SortByBalance,
+                PortfolioOptions::SortByName,
+                PortfolioOptions::Refresh,
+                PortfolioOptions::Export,
+                PortfolioOptions::Back
+            ])
+            .interact()
+            .map_err(|err| (Menu::Main(MainMenu), AppError::from(err)))?;
+
+        let sort = match PortfolioOptions::from(selection) {
+            PortfolioOptions::SortByValue => PortfolioSort::Value,
+            PortfolioOptions::SortByBalance => PortfolioSort::Balance,
+            PortfolioOptions::SortByName => PortfolioSort::Name,
+            PortfolioOptions::Refresh => return Ok(Some(Menu::Portfolio(Portfolio { sort: self.sort, holdings: None }))),
+            PortfolioOptions::Export => {
+                let rows: Vec<Vec<String>> = holdings.iter().flat_map(|holding| {
+                    holding.wallets.iter().map(|(wallet, amount)| {
+                        let ui_amount = *amount as f64 / 10f64.powi(PUMPFUN_DECIMALS as i32);
+                        vec![
+                            holding.project.name.clone(),
+                            holding.project.pumpfun.mint_id.to_string(),
+                            wallet.clone(),
+                            ui_amount.to_string(),
+                            holding.price.map(|price| (price * ui_amount).to_string()).unwrap_or_default()
+                        ]
+                    })
+                }).collect();
+
+                let path = export_csv(
+                    &format!("portfolio_{}.csv", unix_timestamp()),
+                    &["project", "mint_id", "wallet", "tokens", "value_sol"],
+                    &rows
+                ).map_err(|err| (Menu::Portfolio(Portfolio { sort: self.sort, holdings: Some(holdings.clone()) }), AppError::from(err)))?;
+
+                println!("{} {}", style("Exported to").green(), path.display());
+                FuzzySelect::with_theme(&ColorfulTheme::default())
+                    .item("Back")
+                    .default(0)
+                    .interact()
+                    .unwrap();
+
+                self.sort
+            },
+            PortfolioOptions::Back => return Ok(Some(Menu::Main(MainMenu)))
+        };
+
+        Ok(Some(Menu::Portfolio(Portfolio { sort, holdings: Some(holdings) })))
+    }
+}
+
+/// Fetches the sniper wallet balances of every project's mint together with its curve price. The
+/// backend client lock is only taken inside each `dispatch::token_balances` call, so the sync loop
+/// and background jobs keep running while a long portfolio loads.
+pub async fn load_holdings(app_data: &Arc<AppData>) -> Result<Vec<Holding>, AppError> {
+    let projects: Vec<ProjectDTO> = app_data.projects.read().await.values().cloned().collect();
+
+    Loader::new()
+        .with_prompt("loading portfolio")
+        .interact(async {
+            let mut holdings = vec![];
+            for project in projects {
+                let mut wallets = dispatch::token_balances(app_data, project.pumpfun.mint_id).await?;
+                wallets.sort_by(|a, b| b.1.cmp(&a.1));
+
+                let price = match dispatch::bonding_curve(app_data, &project.pumpfun.mint_id).await {
+                    Ok(Some(curve)) if !curve.complete => Some(curve.price()),
+                    _ => None
+                };
+
+                holdings.push(Holding { project, wallets, price });
+            }
+
+            Ok::<_, AppError>(holdings)
+        })
+        .await
+}
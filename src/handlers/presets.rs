@@ -0,0 +1,209 @@
+use dialoguer::theme::ColorfulTheme;
+use solana_sdk::pubkey::Pubkey;
+use std::sync::Arc;
+
+use dialoguer::Confirm;
+use dialoguer::FuzzySelect;
+use dialoguer::Input;
+use dialoguer::MultiSelect;
+use moonbois_core::WalletDTO;
+
+use crate::storage::presets::load_deployers;
+use crate::storage::presets::load_presets;
+use crate::storage::presets::remove_deployer;
+use crate::storage::presets::remove_preset;
+use crate::storage::presets::save_deployer;
+use crate::storage::presets::save_preset;
+use crate::storage::presets::DeployerEntry;
+use crate::storage::presets::SnipePreset;
+use crate::storage::presets::WalletSelection;
+use crate::AppData;
+use crate::AppError;
+use crate::Menu;
+
+use super::deployer::DeployerProfile;
+use super::snipe::print_snipe_summary;
+use super::snipe::prompt_snipe_limits;
+use super::snipe::prompt_wallet_count;
+use super::snipe::CreateSnipe;
+use super::Handler;
+
+pub struct SnipePresets;
+impl Handler for SnipePresets {
+    async fn handle(&self, _app_data: &Arc<AppData>) -> Result<Option<Menu>, (Menu, AppError)> {
+        let presets = load_presets()
+            .map_err(|err| (Menu::CreateSnipe(CreateSnipe), AppError::from(err)))?;
+
+        let mut selection = vec![format!("{}", "NewPreset")];
+        selection.extend(presets.iter().map(|preset| format!("{} {}", preset.name, match &preset.wallets {
+            WalletSelection::Count(count) => format!("{} wallets", count),
+            WalletSelection::Wallets(wallets) => format!("{} selected wallets", wallets.len())
+        })));
+        selection.push(format!("{}", "Back"));
+
+        let index = FuzzySelect::with_theme(&ColorfulTheme::default())
+            .with_prompt("Snipe presets")
+            .default(0)
+            .max_length(10)
+            .items(&selection)
+            .interact()
+            .unwrap();
+
+        if index == 0 {
+            return Ok(Some(Menu::CreatePreset(CreatePreset)))
+        }
+        if index == selection.len() - 1 {
+            return Ok(Some(Menu::CreateSnipe(CreateSnipe)))
+        }
+
+        let preset = &presets[index - 1];
+        let wallet_count = match &preset.wallets {
+            WalletSelection::Count(count) => *count,
+            WalletSelection::Wallets(wallets) => wallets.len()
+        };
+        println!("preset: {}", preset.name);
+        print_snipe_summary(None, wallet_count, &preset.limits);
+
+        let delete = Confirm::with_theme(&ColorfulTheme::default())
+            .with_prompt("Delete this preset?")
+            .default(false)
+            .interact()
+            .unwrap();
+
+        if delete {
+            remove_preset(&preset.name)
+                .map_err(|err| (Menu::SnipePresets(SnipePresets), AppError::from(err)))?;
+        }
+
+        Ok(Some(Menu::SnipePresets(SnipePresets)))
+    }
+}
+
+pub struct CreatePreset;
+impl Handler for CreatePreset {
+    async fn handle(&self, app_data: &Arc<AppData>) -> Result<Option<Menu>, (Menu, AppError)> {
+        let user_wallets: Vec<WalletDTO> = match &app_data.user.read().await.0 {
+            Some(user) => user.wallets.values().cloned().collect(),
+            None => return Err((Menu::SnipePresets(SnipePresets), AppError::UserNotFound))
+        };
+
+        let name: String = match Input::with_theme(&ColorfulTheme::default())
+            .with_prompt("Enter the preset name")
+            .interact_text()
+            .unwrap() {
+                Some(name) => name,
+                None => return Ok(Some(Menu::SnipePresets(SnipePresets)))
+            };
+
+        let mode = FuzzySelect::with_theme(&ColorfulTheme::default())
+            .with_prompt("Wallets")
+            .default(0)
+            .items(&["WalletCount", "SelectWallets"])
+            .interact()
+            .unwrap();
+
+        let wallets = if mode == 0 {
+            match prompt_wallet_count(user_wallets.len()) {
+                Some(count) => WalletSelection::Count(count),
+                None => return Ok(Some(Menu::SnipePresets(SnipePresets)))
+            }
+        } else {
+            let selection: Vec<String> = user_wallets.iter().map(|wallet| wallet.public_key.to_string()).collect();
+            let selected = MultiSelect::with_theme(&ColorfulTheme::default())
+                .with_prompt("Select the wallets to snipe with")
+                .items(&selection)
+                .interact()
+                .map_err(|err| (Menu::SnipePresets(SnipePresets), AppError::from(err)))?;
+
+            if selected.is_empty() {
+                return Ok(Some(Menu::SnipePresets(SnipePresets)))
+            }
+
+            WalletSelection::Wallets(selected.into_iter().map(|index| selection[index].clone()).collect())
+        };
+
+        let limits = match prompt_snipe_limits() {
+            Some(limits) => limits,
+            None => return Ok(Some(Menu::SnipePresets(SnipePresets)))
+        };
+
+        save_preset(&SnipePreset { name, wallets, limits })
+            .map_err(|err| (Menu::SnipePresets(SnipePresets), AppError::from(err)))?;
+
+        Ok(Some(Menu::SnipePresets(SnipePresets)))
+    }
+}
+
+pub struct Deployers;
+impl Handler for Deployers {
+    async fn handle(&self, _app_data: &Arc<AppData>) -> Result<Option<Menu>, (Menu, AppError)> {
+        let deployers = load_deployers()
+            .map_err(|err| (Menu::CreateSnipe(CreateSnipe), AppError::from(err)))?;
+
+        let mut selection = vec![format!("{}", "AddDeployer")];
+        selection.extend(deployers.iter().map(|entry| format!("{} {}", entry.label, entry.address)));
+        selection.push(format!("{}", "Back"));
+
+        let index = FuzzySelect::with_theme(&ColorfulTheme::default())
+            .with_prompt("Deployers")
+            .default(0)
+            .max_length(10)
+            .items(&selection)
+            .interact()
+            .unwrap();
+
+        if index == selection.len() - 1 {
+            return Ok(Some(Menu::CreateSnipe(CreateSnipe)))
+        }
+
+        if index == 0 {
+            let address: Pubkey = match Input::with_theme(&ColorfulTheme::default())
+                .with_prompt("Enter the deployer address")
+                .interact_text()
+                .unwrap() {
+                    Some(address) => address,
+                    None => return Ok(Some(Menu::Deployers(Deployers)))
+                };
+
+            let label: String = match Input::with_theme(&ColorfulTheme::default())
+                .with_prompt("Enter a label")
+                .interact_text()
+                .unwrap() {
+                    Some(label) => label,
+                    None => return Ok(Some(Menu::Deployers(Deployers)))
+                };
+
+            save_deployer(&DeployerEntry { label, address })
+                .map_err(|err| (Menu::Deployers(Deployers), AppError::from(err)))?;
+
+            return Ok(Some(Menu::Deployers(Deployers)))
+        }
+
+        let entry = &deployers[index - 1];
+        let action = FuzzySelect::with_theme(&ColorfulTheme::default())
+            .with_prompt(format!("{} {}", entry.label, entry.address))
+            .default(0)
+            .items(&["Profile", "Remove", "Back"])
+            .interact()
+            .unwrap();
+
+        match action {
+            0 => return Ok(Some(Menu::DeployerProfile(DeployerProfile { deployer: entry.address, from_project: false }))),
+            1 => {},
+            _ => return Ok(Some(Menu::Deployers(Deployers)))
+        }
+
+        let delete = Confirm::with_theme(&ColorfulTheme::default())
+            .with_prompt(format!("Remove {} from the address book?", entry.label))
+            .default(false)
+            .interact()
+            .unwrap();
+
+        if delete {
+            remove_deployer(&entry.address)
+                .map_err(|err| (Menu::Deployers(Deployers), AppError::from(err)))?;
+        }
+
+        Ok(Some(Menu::Deployers(Deployers)))
+    }
+}
@@ -1,10 +1,31 @@
+ use console::style;
  use dialoguer::theme::ColorfulTheme;
+ use crate::chain::analysis::analyze_token;
+ use crate::chain::analysis::PUMPFUN_DECIMALS;
+ use crate::chain::analysis::TokenAnalysisError;
+ use crate::chain::holders::get_holders;
+ use crate::chain::holders::CONCENTRATION_THRESHOLD;
+ use crate::chain::holders::CONCENTRATION_TOP_HOLDERS;
+ use crate::chain::pumpfun::bonding_curve_address;
+ use crate::chain::pumpfun::get_bonding_curve;
+ use crate::chain::pumpfun::parse_mint_input;
+ use crate::chain::token::get_owned_token_accounts;
+ use crate::chain::token::get_token_account;
+ use crate::chain::token::get_token_program;
+ use crate::chain::token::TokenBalance;
+use crate::paper::dispatch;
+ use crate::storage::archive::archive_project;
+ use crate::storage::migrations::mark_migrated;
  use crate::dialogue::loader::Loader;
 use std::sync::Arc;
 
 use dialoguer::Confirm;
 use dialoguer::FuzzySelect;
 use dialoguer::Input;
+use moonbois_core::ProjectDTO;
+use moonbois_core::WalletDTO;
+use solana_client::client_error::ClientError;
+use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_sdk::pubkey::Pubkey;
 
 use crate::AppData;
@@ -12,9 +33,14 @@ use crate::AppError;
 use crate::Menu;
 
 use super::bumps::BumpMenu;
+use super::ladder::SellLadderMenu;
+use super::deployer::DeployerProfile;
 use super::main::MainMenu;
 use super::trade::Buy;
+use super::trade::print_token_report;
 use super::trade::Sell;
+use super::trade_rules::TradeRules;
+use super::wallet::SendTokens;
 use super::Handler;
 
 pub enum ProjectMenuOptions {
@@ -22,7 +48,11 @@ pub enum ProjectMenuOptions {
     Sell,
     AutoBuy,
     AutoSell,
+    Rules,
+    Ladder,
     Bumps,
+    Holders,
+    Deployer,
     Delete,
     Back
 }
@@ -34,7 +64,11 @@ impl ToString for ProjectMenuOptions {
             Self::Sell => "Sell".to_string(),
             Self::AutoBuy => "AutoBuy".to_string(),
             Self::AutoSell => "AutoSell".to_string(),
+            Self::Rules => "Rules".to_string(),
+            Self::Ladder => "Ladder".to_string(),
             Self::Bumps => "Bumps".to_string(),
+            Self::Holders => "Holders".to_string(),
+            Self::Deployer => "Deployer".to_string(),
             Self::Delete => "Delete".to_string(),
             Self::Back => format!("{}", "Back")
         }
@@ -48,9 +82,13 @@ impl From<usize> for ProjectMenuOptions {
             1 => Self::Sell,
             2 => Self::AutoBuy,
             3 => Self::AutoSell,
-            4 => Self::Bumps,
-            5 => Self::Delete,
-            6 => Self::Back,
+            4 => Self::Rules,
+            5 => Self::Ladder,
+            6 => Self::Bumps,
+            7 => Self::Holders,
+            8 => Self::Deployer,
+            9 => Self::Delete,
+            10 => Self::Back,
             _ => panic!("Received invalid project menu index")
         }
     }
@@ -58,14 +96,18 @@ impl From<usize> for ProjectMenuOptions {
 
 pub struct ProjectMenu;
 impl Handler for ProjectMenu {
-    async fn handle(&self, _app_data: &Arc<AppData>) -> Result<Option<Menu>, (Menu, AppError)> {
+    async fn handle(&self, app_data: &Arc<AppData>) -> Result<Option<Menu>, (Menu, AppError)> {
         let mut items = vec![];
 
         items.push(ProjectMenuOptions::Buy);
         items.push(ProjectMenuOptions::Sell);
         items.push(ProjectMenuOptions::AutoBuy);
         items.push(ProjectMenuOptions::AutoSell);
+        items.push(ProjectMenuOptions::Rules);
+        items.push(ProjectMenuOptions::Ladder);
         items.push(ProjectMenuOptions::Bumps);
+        items.push(ProjectMenuOptions::Holders);
+        items.push(ProjectMenuOptions::Deployer);
         items.push(ProjectMenuOptions::Delete);
         items.push(ProjectMenuOptions::Back);
 
@@ -85,102 +127,255 @@ impl Handler for ProjectMenu {
             ProjectMenuOptions::Sell => return Ok(Some(Menu::Sell(Sell::new(false)))),
             ProjectMenuOptions::AutoBuy => return Ok(Some(Menu::Buy(Buy::new(true)))),
             ProjectMenuOptions::AutoSell => return Ok(Some(Menu::Sell(Sell::new(true)))),
+            ProjectMenuOptions::Rules => return Ok(Some(Menu::TradeRules(TradeRules))),
+            ProjectMenuOptions::Ladder => return Ok(Some(Menu::SellLadder(SellLadderMenu))),
             ProjectMenuOptions::Bumps => return Ok(Some(Menu::Bump(BumpMenu))),
+            ProjectMenuOptions::Holders => return Ok(Some(Menu::Holders(Holders))),
+            ProjectMenuOptions::Deployer => {
+                let deployer = match app_data.active_project.read().await.0 {
+                    Some(project_id) => match app_data.projects.read().await.get(&project_id) {
+                        Some(project) => project.deployer,
+                        None => return Err((Menu::Main(MainMenu), AppError::ProjectNotFound))
+                    },
+                    None => return Err((Menu::Main(MainMenu), AppError::ProjectNotFound))
+                };
+
+                return Ok(Some(Menu::DeployerProfile(DeployerProfile { deployer, from_project: true })))
+            },
             ProjectMenuOptions::Delete => return Ok(Some(Menu::DeleteProject(DeleteProject))),
             ProjectMenuOptions::Back => return Ok(Some(Menu::Main(MainMenu))),
         };
     }
 }
 
-pub struct SelectProject;
-impl Handler for SelectProject {
-    async fn handle(&self, app_data: &Arc<AppData>) -> Result<Option<Menu>, (Menu, AppError)> {
-        let projects = app_data.projects.read().await;
-        let (project_ids, mut selection): (Vec<i32>, Vec<String>) = projects.iter().map(|(key, value)| {
-            (key, value.name.clone())
-        }).collect();
-
-        selection.push(format!("{}", "Back"));
-
-        let index = FuzzySelect::with_theme(&ColorfulTheme::default())
-            .with_prompt("Select Project")
-            .default(0)
-            .max_length(10)
-            .items(&selection)
-            .interact()
-            .unwrap();
-
-        if index == selection.len() - 1 {
-            return Ok(Some(Menu::Main(MainMenu)))
-        }
-
-        let mut active_project = app_data.active_project.write().await;
-        active_project.0 = Some(project_ids[index]);
-
-        Ok(Some(Menu::ProjectMenu(ProjectMenu)))
-    }
-}
-
 pub struct CreateProject;
 impl Handler for CreateProject {
     async fn handle(&self, app_data: &Arc<AppData>) -> Result<Option<Menu>, (Menu, AppError)> {
-        let mint_id: Pubkey = match Input::with_theme(&ColorfulTheme::default())
-            .with_prompt("Enter contract address")
+        let input: String = match Input::with_theme(&ColorfulTheme::default())
+            .with_prompt("Enter contract address or pump.fun link")
+            .validate_with(|input: &String| -> Result<(), &str> {
+                parse_mint_input(input).map(|_| ()).ok_or("Not a mint address or pump.fun link")
+            })
             .interact_text()
             .unwrap() {
                 Some(result) => result,
                 None => return Ok(Some(Menu::Main(MainMenu)))
             };
+        let Some(mint_id) = parse_mint_input(&input) else {
+            return Ok(Some(Menu::CreateProject(CreateProject)))
+        };
+
+        let existing = app_data.projects.read().await.values()
+            .find(|project| project.pumpfun.mint_id == mint_id)
+            .map(|project| (project.id, project.name.clone()));
+
+        if let Some((project_id, name)) = existing {
+            println!("{}", style(format!("{} is already imported as {}", mint_id, name)).yellow());
+            let open = Confirm::with_theme(&ColorfulTheme::default())
+                .with_prompt("Open the existing project?")
+                .default(true)
+                .interact()
+                .unwrap();
+
+            if !open {
+                return Ok(Some(Menu::Main(MainMenu)))
+            }
+
+            app_data.active_project.write().await.0 = Some(project_id);
+            return Ok(Some(Menu::ProjectMenu(ProjectMenu)))
+        }
+
+        let (report, curve) = Loader::new()
+            .with_prompt("loading token")
+            .interact(async {
+                let report = analyze_token(&app_data.solana_client, &mint_id).await?;
+                let curve = get_bonding_curve(&app_data.solana_client, &mint_id).await?;
+                Ok::<_, TokenAnalysisError>((report, curve))
+            })
+            .await
+            .map_err(|err| (Menu::CreateProject(CreateProject), AppError::from(err)))?;
+
+        let Some(curve) = curve else {
+            return Err((Menu::CreateProject(CreateProject), AppError::Unhandled(format!("{} has no pump.fun bonding curve", mint_id))))
+        };
+
+        print_token_report(&report);
+        println!(
+            "price: {:.10} {}\nmarket_cap: {:.2} {}\ncurve_progress: {:.2}%",
+            curve.price(),
+            style("SOL").cyan(),
+            curve.market_cap(),
+            style("SOL").cyan(),
+            curve.progress()
+        );
+        if curve.complete {
+            println!("{}", style("curve: migrated ⚠️ curve-based buy and sell no longer work").yellow());
+        }
+        println!("");
+
+        let import = Confirm::with_theme(&ColorfulTheme::default())
+            .with_prompt("Import this token?")
+            .default(true)
+            .interact()
+            .unwrap();
+
+        if !import {
+            return Ok(Some(Menu::Main(MainMenu)))
+        }
     
-        let rpc_client = app_data.rpc_client.read().await;
+        let name = report.metadata.as_ref()
+            .map(|metadata| metadata.name.clone())
+            .unwrap_or(mint_id.to_string());
         let result = Loader::new()
             .with_prompt("import_token in progress")
-            .interact(rpc_client.create_project(mint_id))
+            .interact(dispatch::create_project(app_data, mint_id, name, &curve))
             .await;
-        drop(rpc_client);
 
         let project = match result {
             Ok(project) => project,
             Err(err) => return Err((Menu::Main(MainMenu), AppError::from(err)))
         };
-    
+
         let mut project_write = app_data.projects.write().await;
         project_write.insert(project.id, project.clone());
+        drop(project_write);
         let mut active_project = app_data.active_project.write().await;
         active_project.0 = Some(project.id);
+        drop(active_project);
+
+        // The project exists on the backend by now, a missing migration marker is only reported
+        if curve.complete {
+            if let Err(err) = mark_migrated(&mint_id) {
+                app_data.notifications.write().await.push(format!("Unable to mark {} as migrated - {}", project.name, err));
+            }
+        }
         
         Ok(Some(Menu::ProjectMenu(ProjectMenu)))
     }
 }
 
+pub enum DeleteProjectOptions {
+    Sell,
+    Transfer,
+    Archive,
+    Delete,
+    Cancel
+}
+
+impl ToString for DeleteProjectOptions {
+    fn to_string(&self) -> String {
+        match self {
+            Self::Sell => "SellAll".to_string(),
+            Self::Transfer => "TransferTokens".to_string(),
+            Self::Archive => "Archive".to_string(),
+            Self::Delete => "Delete".to_string(),
+            Self::Cancel => "Cancel".to_string()
+        }
+    }
+}
+
 pub struct DeleteProject;
 impl Handler for DeleteProject {
     async fn handle(&self, app_data: &Arc<AppData>) -> Result<Option<Menu>, (Menu, AppError)> {
-        let project_id = match app_data.active_project.read().await.0 {
-            Some(project_id) => project_id,
+        let project = match app_data.active_project.read().await.0 {
+            Some(project_id) => match app_data.projects.read().await.get(&project_id) {
+                Some(project) => project.clone(),
+                None => return Err((Menu::ProjectMenu(ProjectMenu), AppError::ProjectNotFound))
+            },
             None => return Err((Menu::ProjectMenu(ProjectMenu), AppError::ProjectNotFound))
         };
+        let wallets: Vec<WalletDTO> = match &app_data.user.read().await.0 {
+            Some(user) => user.wallets.values().cloned().collect(),
+            None => return Err((Menu::ProjectMenu(ProjectMenu), AppError::UserNotFound))
+        };
+
+        let holdings = Loader::new()
+            .with_prompt("checking holdings")
+            .interact(get_live_holdings(app_data, wallets, &project.pumpfun.mint_id))
+            .await
+            .map_err(|err| (Menu::ProjectMenu(ProjectMenu), AppError::from(err)))?;
+
+        let mut items = vec![];
+        if holdings.is_empty() {
+            println!("{}", style("No wallet holds this project's tokens").dim());
+        } else {
+            println!("{}", style("Your wallets still hold this project's tokens ⚠️").yellow());
+            for (wallet, balance) in holdings.iter() {
+                println!("  - {} {}", &wallet.public_key.to_string()[0..5], style(format!("{} TOKENS", balance.ui_amount())).dim());
+            }
+            println!("{}", style("Deleting the project removes the position from the client, archiving keeps it restorable").dim());
+
+            items.push(DeleteProjectOptions::Sell);
+            items.push(DeleteProjectOptions::Transfer);
+        }
+        items.push(DeleteProjectOptions::Archive);
+        items.push(DeleteProjectOptions::Delete);
+        items.push(DeleteProjectOptions::Cancel);
+
+        let selection = FuzzySelect::with_theme(&ColorfulTheme::default())
+            .with_prompt(format!("Remove {}", project.name))
+            .default(0)
+            .items(&items)
+            .interact()
+            .map_err(|err| (Menu::ProjectMenu(ProjectMenu), AppError::from(err)))?;
+
+        match items[selection] {
+            DeleteProjectOptions::Sell => return Ok(Some(Menu::Sell(Sell::new(true)))),
+            DeleteProjectOptions::Transfer => {
+                let mut selection: Vec<String> = holdings.iter()
+                    .map(|(wallet, balance)| format!("{} {} TOKENS", &wallet.public_key.to_string()[0..5], balance.ui_amount()))
+                    .collect();
+                selection.push(format!("{}", "Back"));
+
+                let index = FuzzySelect::with_theme(&ColorfulTheme::default())
+                    .with_prompt("Select Wallet")
+                    .default(0)
+                    .items(&selection)
+                    .interact()
+                    .unwrap();
+
+                if index == selection.len() - 1 {
+                    return Ok(Some(Menu::DeleteProject(DeleteProject)))
+                }
 
+                return Ok(Some(Menu::SendTokens(SendTokens { wallet: holdings[index].0.clone() })))
+            },
+            DeleteProjectOptions::Archive => {
+                archive_project(project.id)
+                    .map_err(|err| (Menu::ProjectMenu(ProjectMenu), AppError::from(err)))?;
+
+                app_data.active_project.write().await.0 = None;
+                app_data.notifications.write().await.push(format!("{} archived, restore it from Tokens with ShowArchived", project.name));
+
+                return Ok(Some(Menu::Main(MainMenu)))
+            },
+            DeleteProjectOptions::Delete => {},
+            DeleteProjectOptions::Cancel => return Ok(Some(Menu::ProjectMenu(ProjectMenu)))
+        }
+
+        let prompt = if holdings.is_empty() {
+            "Are you sure you want to delete this project?"
+        } else {
+            "Your wallets still hold tokens, are you sure you want to delete this project?"
+        };
         let delete = Confirm::with_theme(&ColorfulTheme::default())
-            .with_prompt("Are you sure you want to delete this project?")
+            .with_prompt(prompt)
             .default(false)
             .interact()
             .unwrap();
 
         if delete {
-            let rpc_client = app_data.rpc_client.read().await;
             let result = Loader::new()
                 .with_prompt("delete_project in progress")
-                .interact(rpc_client.delete_project(project_id))
+                .interact(dispatch::delete_project(app_data, project.id))
                 .await;
-            drop(rpc_client);
     
             if let Err(err) = result {
                 return Err((Menu::Main(MainMenu), AppError::from(err)))
             }
         
             let mut project_write = app_data.projects.write().await;
-            project_write.remove(&project_id);
+            project_write.remove(&project.id);
             let mut active_project = app_data.active_project.write().await;
             active_project.0 = None;
 
@@ -189,4 +384,260 @@ impl Handler for DeleteProject {
 
         Ok(Some(Menu::ProjectMenu(ProjectMenu)))
     }
-}
\ No newline at end of file
+}
+
+/// On-chain token balances of `mint_id` in the given wallets, wallets holding none are left out.
+async fn get_live_holdings(app_data: &AppData, wallets: Vec<WalletDTO>, mint_id: &Pubkey) -> Result<Vec<(WalletDTO, TokenBalance)>, ClientError> {
+    if let Some(paper) = &app_data.paper {
+        let ledger = paper.read().await;
+        return Ok(wallets.into_iter()
+            .filter_map(|wallet| {
+                let amount = ledger.wallet(&wallet.public_key)?.token_balance(mint_id);
+                (amount > 0).then_some((wallet, TokenBalance { amount, decimals: PUMPFUN_DECIMALS }))
+            })
+            .collect())
+    }
+
+    let client = &app_data.solana_client;
+    let token_program = get_token_program(client, mint_id).await?;
+
+    let mut holdings = vec![];
+    for wallet in wallets {
+        if let Some(balance) = get_token_account(client, &wallet.public_key, mint_id, &token_program).await?.balance {
+            if balance.amount > 0 {
+                holdings.push((wallet, balance));
+            }
+        }
+    }
+
+    Ok(holdings)
+}
+
+pub struct Holders;
+impl Handler for Holders {
+    async fn handle(&self, app_data: &Arc<AppData>) -> Result<Option<Menu>, (Menu, AppError)> {
+        let project = match app_data.active_project.read().await.0 {
+            Some(project_id) => match app_data.projects.read().await.get(&project_id) {
+                Some(project) => project.clone(),
+                None => return Err((Menu::Main(MainMenu), AppError::ProjectNotFound))
+            },
+            None => return Err((Menu::Main(MainMenu), AppError::ProjectNotFound))
+        };
+
+        let (fee_payer, own_wallets): (Pubkey, Vec<Pubkey>) = match &app_data.user.read().await.0 {
+            Some(user) => (user.public_key, user.wallets.values().map(|wallet| wallet.public_key).collect()),
+            None => return Err((Menu::ProjectMenu(ProjectMenu), AppError::UserNotFound))
+        };
+
+        let report = Loader::new()
+            .with_prompt("loading holders")
+            .interact(get_holders(&app_data.solana_client, &project.pumpfun.mint_id))
+            .await
+            .map_err(|err| (Menu::ProjectMenu(ProjectMenu), AppError::from(err)))?;
+
+        let bonding_curve = bonding_curve_address(&project.pumpfun.mint_id);
+        let label = |owner: &Option<Pubkey>| -> String {
+            match owner {
+                Some(owner) if owner == &bonding_curve => "bonding curve".to_string(),
+                Some(owner) if owner == &project.deployer => "deployer".to_string(),
+                Some(owner) if owner == &fee_payer => "fee_payer".to_string(),
+                Some(owner) if own_wallets.contains(owner) => "own wallet".to_string(),
+                _ => "".to_string()
+            }
+        };
+
+        println!("{:<4} {:<46} {:>20} {:>8} {}", "#", "owner", "amount", "share", "label");
+        for (index, holder) in report.holders.iter().enumerate() {
+            let label = label(&holder.owner);
+            let line = format!(
+                "{:<4} {:<46} {:>20} {:>7.2}% {}",
+                index + 1,
+                holder.owner.map(|owner| owner.to_string()).unwrap_or(holder.token_account.to_string()),
+                holder.amount as f64 / 10f64.powi(report.decimals as i32),
+                report.share(holder) * 100f64,
+                label
+            );
+
+            match label.as_str() {
+                "own wallet" | "fee_payer" => println!("{}", style(line).green()),
+                "deployer" => println!("{}", style(line).magenta()),
+                "bonding curve" => println!("{}", style(line).dim()),
+                _ => println!("{}", line)
+            }
+        }
+
+        let concentration = report.outside_concentration(|holder| {
+            matches!(label(&holder.owner).as_str(), "own wallet" | "fee_payer" | "bonding curve")
+        });
+
+        println!("");
+        if concentration > CONCENTRATION_THRESHOLD {
+            println!("{}", style(format!(
+                "⚠️ The top {} outside holders control {:.2}% of the supply",
+                CONCENTRATION_TOP_HOLDERS,
+                concentration * 100f64
+            )).yellow());
+        } else {
+            println!("top {} outside holders: {:.2}%", CONCENTRATION_TOP_HOLDERS, concentration * 100f64);
+        }
+
+        FuzzySelect::with_theme(&ColorfulTheme::default())
+            .item("Back")
+            .default(0)
+            .interact()
+            .unwrap();
+
+        Ok(Some(Menu::ProjectMenu(ProjectMenu)))
+    }
+}
+
+pub async fn select_project(app_data: &Arc<AppData>) -> Result<Option<ProjectDTO>, AppError> {
+    if let Some(project_id) = app_data.active_project.read().await.0 {
+        if let Some(project) = app_data.projects.read().await.get(&project_id) {
+            return Ok(Some(project.clone()))
+        }
+    }
+
+    let projects_read = app_data.projects.read().await;
+    let (projects, mut selection): (Vec<ProjectDTO>, Vec<String>) = projects_read.values().map(|project| {
+        (project.clone(), format!("{} {}", project.name, project.pumpfun.mint_id))
+    }).unzip();
+    drop(projects_read);
+
+    selection.push(format!("{}", "Back"));
+
+    let index = FuzzySelect::with_theme(&ColorfulTheme::default())
+        .with_prompt("Select Project")
+        .default(0)
+        .max_length(10)
+        .items(&selection)
+        .interact()?;
+
+    if index == selection.len() - 1 {
+        return Ok(None)
+    }
+
+    Ok(projects.get(index).cloned())
+}
+
+pub struct UntrackedToken {
+    pub mint_id: Pubkey,
+    pub decimals: u8,
+    /// Raw balance per sniper wallet holding the mint.
+    pub wallets: Vec<(Pubkey, u64)>
+}
+
+impl UntrackedToken {
+    pub fn ui_amount(&self) -> f64 {
+        self.wallets.iter().map(|(_, amount)| *amount).sum::<u64>() as f64 / 10f64.powi(self.decimals as i32)
+    }
+}
+
+/// Lists tokens held by the sniper wallets that have no matching project, e.g. bought outside the
+/// client or left behind by a deleted project, and offers to import them.
+pub struct UntrackedTokens;
+impl Handler for UntrackedTokens {
+    async fn handle(&self, app_data: &Arc<AppData>) -> Result<Option<Menu>, (Menu, AppError)> {
+        dispatch::live_only(app_data, "UntrackedTokens")
+            .map_err(|err| (Menu::Main(MainMenu), err))?;
+
+        let wallets: Vec<Pubkey> = match &app_data.user.read().await.0 {
+            Some(user) => user.wallets.values().map(|wallet| wallet.public_key).collect(),
+            None => return Err((Menu::Main(MainMenu), AppError::UserNotFound))
+        };
+        let tracked: Vec<Pubkey> = app_data.projects.read().await.values().map(|project| project.pumpfun.mint_id).collect();
+
+        let tokens = Loader::new()
+            .with_prompt("scanning wallets")
+            .interact(find_untracked_tokens(&app_data.solana_client, &wallets, &tracked))
+            .await
+            .map_err(|err| (Menu::Main(MainMenu), AppError::from(err)))?;
+
+        if tokens.is_empty() {
+            println!("{}", style("No untracked tokens found in the sniper wallets").green());
+            FuzzySelect::with_theme(&ColorfulTheme::default())
+                .item("Back")
+                .default(0)
+                .interact()
+                .unwrap();
+
+            return Ok(Some(Menu::Main(MainMenu)))
+        }
+
+        let mut selection: Vec<String> = tokens.iter().map(|token| format!(
+            "{} {} TOKENS in {} wallets",
+            token.mint_id,
+            token.ui_amount(),
+            token.wallets.len()
+        )).collect();
+        selection.push(format!("{}", "Back"));
+
+        let index = FuzzySelect::with_theme(&ColorfulTheme::default())
+            .with_prompt("Untracked tokens")
+            .default(0)
+            .max_length(10)
+            .items(&selection)
+            .interact()
+            .unwrap();
+
+        if index == selection.len() - 1 {
+            return Ok(Some(Menu::Main(MainMenu)))
+        }
+
+        let token = &tokens[index];
+        for (wallet, amount) in token.wallets.iter() {
+            println!(
+                "  - {} {}",
+                &wallet.to_string()[0..5],
+                style(format!("{} TOKENS", *amount as f64 / 10f64.powi(token.decimals as i32))).dim()
+            );
+        }
+
+        let import = Confirm::with_theme(&ColorfulTheme::default())
+            .with_prompt(format!("Import {} as a project?", token.mint_id))
+            .default(true)
+            .interact()
+            .unwrap();
+
+        if !import {
+            return Ok(Some(Menu::UntrackedTokens(UntrackedTokens)))
+        }
+
+        let rpc_client = app_data.rpc_client.read().await;
+        let result = Loader::new()
+            .with_prompt("import_token in progress")
+            .interact(rpc_client.create_project(token.mint_id))
+            .await;
+        drop(rpc_client);
+
+        let project = match result {
+            Ok(project) => project,
+            Err(err) => return Err((Menu::UntrackedTokens(UntrackedTokens), AppError::from(err)))
+        };
+
+        println!("{} {}", style("Imported").green(), project.name);
+        app_data.projects.write().await.insert(project.id, project);
+
+        Ok(Some(Menu::UntrackedTokens(UntrackedTokens)))
+    }
+}
+
+async fn find_untracked_tokens(client: &RpcClient, wallets: &[Pubkey], tracked: &[Pubkey]) -> Result<Vec<UntrackedToken>, ClientError> {
+    let mut tokens: Vec<UntrackedToken> = vec![];
+
+    for wallet in wallets {
+        let accounts = get_owned_token_accounts(client, wallet).await?;
+        for account in accounts.into_iter().filter(|account| account.amount > 0 && !tracked.contains(&account.mint_id)) {
+            match tokens.iter_mut().find(|token| token.mint_id == account.mint_id) {
+                Some(token) => token.wallets.push((*wallet, account.amount)),
+                None => tokens.push(UntrackedToken {
+                    mint_id: account.mint_id,
+                    decimals: account.decimals,
+                    wallets: vec![(*wallet, account.amount)]
+                })
+            }
+        }
+    }
+
+    Ok(tokens)
+}
@@ -0,0 +1,165 @@
+use console::style;
+use dialoguer::theme::ColorfulTheme;
+use crate::storage::risk::load_risk_policy;
+use crate::storage::risk::save_risk_policy;
+use crate::storage::risk::RiskPolicy;
+use crate::storage::risk::Spend;
+use crate::storage::snipes::SnipeLimits;
+use crate::storage::transactions::load_transactions;
+use crate::storage::transactions::unix_timestamp;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::pubkey::Pubkey;
+use std::sync::Arc;
+
+use dialoguer::FuzzySelect;
+use dialoguer::Input;
+
+use crate::AppData;
+use crate::AppError;
+use crate::Menu;
+
+use super::main::MainMenu;
+use super::Handler;
+
+pub enum RiskLimitOptions {
+    MaxTrade,
+    MaxAutoTrade,
+    MaxWallet,
+    MaxDaily,
+    MaxFeePayerShare,
+    Back
+}
+
+impl ToString for RiskLimitOptions {
+    fn to_string(&self) -> String {
+        match self {
+            Self::MaxTrade => "MaxTrade".to_string(),
+            Self::MaxAutoTrade => "MaxAutoTrade".to_string(),
+            Self::MaxWallet => "MaxWalletPer24h".to_string(),
+            Self::MaxDaily => "MaxPer24h".to_string(),
+            Self::MaxFeePayerShare => "MaxFeePayerShare".to_string(),
+            Self::Back => "Back".to_string()
+        }
+    }
+}
+
+impl From<usize> for RiskLimitOptions {
+    fn from(value: usize) -> Self {
+        match value {
+            0 => Self::MaxTrade,
+            1 => Self::MaxAutoTrade,
+            2 => Self::MaxWallet,
+            3 => Self::MaxDaily,
+            4 => Self::MaxFeePayerShare,
+            5 => Self::Back,
+            _ => panic!("Received invalid risk limit index")
+        }
+    }
+}
+
+fn format_limit(lamports: Option<u64>) -> String {
+    lamports.map(|lamports| format!("{} SOL", lamports as f64 / LAMPORTS_PER_SOL as f64)).unwrap_or("off".to_string())
+}
+
+pub struct RiskLimits;
+impl Handler for RiskLimits {
+    async fn handle(&self, _app_data: &Arc<AppData>) -> Result<Option<Menu>, (Menu, AppError)> {
+        let mut policy = load_risk_policy()
+            .map_err(|err| (Menu::Main(MainMenu), AppError::from(err)))?;
+
+        println!(
+            "max_trade: {}\nmax_auto_trade: {}\nmax_wallet_per_24h: {}\nmax_per_24h: {}\nmax_fee_payer_share: {}",
+            format_limit(policy.max_trade_lamports),
+            format_limit(policy.max_auto_trade_lamports),
+            format_limit(policy.max_wallet_lamports),
+            format_limit(policy.max_daily_lamports),
+            policy.max_fee_payer_share.map(|share| format!("{}%", share * 100.0)).unwrap_or("off".to_string())
+        );
+        println!("{}", style("Enter 0 to turn a limit off").dim());
+
+        let selection = FuzzySelect::with_theme(&ColorfulTheme::default())
+            .with_prompt("Risk limits")
+            .default(0)
+            .items(&[
+                RiskLimitOptions::MaxTrade,
+                RiskLimitOptions::MaxAutoTrade,
+                RiskLimitOptions::MaxWallet,
+                RiskLimitOptions::MaxDaily,
+                RiskLimitOptions::MaxFeePayerShare,
+                RiskLimitOptions::Back
+            ])
+            .interact()
+            .map_err(|err| (Menu::Main(MainMenu), AppError::from(err)))?;
+
+        let option = RiskLimitOptions::from(selection);
+        let limit = match option {
+            RiskLimitOptions::MaxTrade => &mut policy.max_trade_lamports,
+            RiskLimitOptions::MaxAutoTrade => &mut policy.max_auto_trade_lamports,
+            RiskLimitOptions::MaxWallet => &mut policy.max_wallet_lamports,
+            RiskLimitOptions::MaxDaily => &mut policy.max_daily_lamports,
+            RiskLimitOptions::MaxFeePayerShare => {
+                let share: f64 = match Input::with_theme(&ColorfulTheme::default())
+                    .with_prompt("Enter the max percentage of the fee payer balance per action")
+                    .validate_with(|val: &f64| -> Result<(), &str> {
+                        if *val < 0f64 || *val > 100f64 {
+                            return Err("Enter a percentage between 0 and 100")
+                        }
+
+                        Ok(())
+                    })
+                    .interact_text()
+                    .unwrap() {
+                        Some(share) => share,
+                        None => return Ok(Some(Menu::RiskLimits(RiskLimits)))
+                    };
+
+                policy.max_fee_payer_share = Some(share / 100.0).filter(|share| *share > 0.0);
+                save_risk_policy(&policy)
+                    .map_err(|err| (Menu::RiskLimits(RiskLimits), AppError::from(err)))?;
+
+                return Ok(Some(Menu::RiskLimits(RiskLimits)))
+            },
+            RiskLimitOptions::Back => return Ok(Some(Menu::Main(MainMenu)))
+        };
+
+        let amount: f64 = match Input::with_theme(&ColorfulTheme::default())
+            .with_prompt(format!("Enter the {} limit in SOL", option.to_string()))
+            .interact_text()
+            .unwrap() {
+                Some(amount) => amount,
+                None => return Ok(Some(Menu::RiskLimits(RiskLimits)))
+            };
+
+        *limit = Some((amount.max(0f64) * LAMPORTS_PER_SOL as f64) as u64).filter(|lamports| *lamports > 0);
+        save_risk_policy(&policy)
+            .map_err(|err| (Menu::RiskLimits(RiskLimits), AppError::from(err)))?;
+
+        Ok(Some(Menu::RiskLimits(RiskLimits)))
+    }
+}
+
+async fn fee_payer_balance(app_data: &Arc<AppData>) -> Result<u64, AppError> {
+    match &app_data.user.read().await.0 {
+        Some(user) => Ok(user.sol_balance),
+        None => Err(AppError::UserNotFound)
+    }
+}
+
+/// Checks a spend against the local risk policy before it is sent to the backend.
+pub async fn check_spend(app_data: &Arc<AppData>, spend: Spend) -> Result<(), AppError> {
+    let policy: RiskPolicy = load_risk_policy()?;
+    let records = load_transactions()?;
+
+    policy.check(&spend, &records, fee_payer_balance(app_data).await?, unix_timestamp())?;
+
+    Ok(())
+}
+
+pub async fn check_snipe_spend(app_data: &Arc<AppData>, wallet_count: usize, snipers: &[Pubkey], limits: &SnipeLimits) -> Result<(), AppError> {
+    let policy: RiskPolicy = load_risk_policy()?;
+    let records = load_transactions()?;
+
+    policy.check_snipe(wallet_count, snipers, limits, &records, fee_payer_balance(app_data).await?, unix_timestamp())?;
+
+    Ok(())
+}
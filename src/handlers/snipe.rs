@@ -1,103 +1,556 @@
+use console::style;
 use dialoguer::theme::ColorfulTheme;
 use crate::dialogue::loader::Loader;
 use std::sync::Arc;
+use std::time::Duration;
 
+use dialoguer::Confirm;
+use dialoguer::FuzzySelect;
 use dialoguer::Input;
+use moonbois_core::rpc::MoonboisClientError;
+use moonbois_core::CreateSnipeParams;
+use moonbois_core::WalletDTO;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
 use solana_sdk::pubkey::Pubkey;
 
+use crate::jobs::format_age;
+use crate::jobs::snipe::new_snipe_record;
+use crate::jobs::snipe::spawn_snipe_job;
+use crate::jobs::snipe::SnipeJob;
+use crate::jobs::snipe::SnipeStatus;
+use crate::jobs::snipe::wait_for_snipe;
+use crate::paper::dispatch;
+use crate::storage::presets::load_deployers;
+use crate::storage::presets::load_presets;
+use crate::storage::presets::save_deployer;
+use crate::storage::presets::DeployerEntry;
+use crate::storage::presets::WalletSelection;
+use crate::storage::snipes::load_snipes;
+use crate::storage::snipes::remove_snipe;
+use crate::storage::snipes::SnipeLimits;
+use crate::storage::snipes::SnipeRecord;
+use crate::storage::transactions::unix_timestamp;
 use crate::AppData;
 use crate::AppError;
 use crate::Menu;
 
 use super::main::MainMenu;
+use super::presets::Deployers;
+use super::presets::SnipePresets;
 use super::project::ProjectMenu;
+use super::risk::check_snipe_spend;
 use super::Handler;
 
 pub struct CreateSnipe;
 impl Handler for CreateSnipe {
     async fn handle(&self, app_data: &Arc<AppData>) -> Result<Option<Menu>, (Menu, AppError)> {
-        let sniper_count = match &app_data.user.read().await.0 {
-            Some(user) => user.wallets.len(),
+        let user_wallets: Vec<WalletDTO> = match &app_data.user.read().await.0 {
+            Some(user) => user.wallets.values().cloned().collect(),
             None => return Err((Menu::Main(MainMenu), AppError::UserNotFound))
         };
 
-        let wallet_count = match Input::with_theme(&ColorfulTheme::default())
-            .with_prompt("Enter wallet amount")
-            .default(5)
-            .validate_with(|val: &usize| -> Result<(), String> {
-                if val > &sniper_count {
-                    return Err("Wallet amount exceeds available wallets".to_string())
+        let presets = load_presets()
+            .map_err(|err| (Menu::Main(MainMenu), AppError::from(err)))?;
+
+        let mut selection = vec![format!("{}", "Manual")];
+        selection.extend(presets.iter().map(|preset| format!("Preset {}", preset.name)));
+        selection.push(format!("{}", "SnipePresets"));
+        selection.push(format!("{}", "Deployers"));
+        selection.push(format!("{}", "Back"));
+
+        let index = FuzzySelect::with_theme(&ColorfulTheme::default())
+            .with_prompt("Snipe")
+            .default(0)
+            .items(&selection)
+            .interact()
+            .unwrap();
+
+        let (wallet_selection, limits) = match index {
+            0 => {
+                let wallet_count = match prompt_wallet_count(user_wallets.len()) {
+                    Some(wallet_count) => wallet_count,
+                    None => return Ok(Some(Menu::Main(MainMenu)))
+                };
+                let limits = match prompt_snipe_limits() {
+                    Some(limits) => limits,
+                    None => return Ok(Some(Menu::Main(MainMenu)))
                 };
 
-                Ok(())
-            })
-            .interact_text()
-            .unwrap() {
-                Some(wallet_count) => wallet_count,
-                None => return Ok(Some(Menu::Main(MainMenu)))
-            };
+                (WalletSelection::Count(wallet_count), limits)
+            },
+            index if index <= presets.len() => {
+                let preset = presets[index - 1].clone();
+                (preset.wallets, preset.limits)
+            },
+            index if index == presets.len() + 1 => return Ok(Some(Menu::SnipePresets(SnipePresets))),
+            index if index == presets.len() + 2 => return Ok(Some(Menu::Deployers(Deployers))),
+            _ => return Ok(Some(Menu::Main(MainMenu)))
+        };
 
-        let deployer: Pubkey = match Input::with_theme(&ColorfulTheme::default())
-            .with_prompt("Enter the deployer address")
-            .interact_text()
-            .unwrap() {
+        // The backend picks the wallets of a counted snipe, so any of them may spend the full cap
+        let snipers: Vec<Pubkey> = match &wallet_selection {
+            WalletSelection::Count(_) => user_wallets.iter().map(|wallet| wallet.public_key).collect(),
+            WalletSelection::Wallets(public_keys) => user_wallets.iter()
+                .filter(|wallet| public_keys.contains(&wallet.public_key.to_string()))
+                .map(|wallet| wallet.public_key)
+                .collect()
+        };
+
+        let (wallet_count, wallet_ids) = match wallet_selection {
+            WalletSelection::Count(wallet_count) => {
+                if wallet_count > user_wallets.len() {
+                    return Err((Menu::Main(MainMenu), AppError::Unhandled("Wallet amount exceeds available wallets".to_string())))
+                }
+
+                (wallet_count, None)
+            },
+            WalletSelection::Wallets(public_keys) => {
+                let wallet_ids: Vec<_> = user_wallets.iter()
+                    .filter(|wallet| public_keys.contains(&wallet.public_key.to_string()))
+                    .map(|wallet| wallet.id)
+                    .collect();
+
+                if wallet_ids.len() < public_keys.len() {
+                    println!("{}", style(format!("{} preset wallets no longer exist and were skipped", public_keys.len() - wallet_ids.len())).yellow());
+                }
+                if wallet_ids.is_empty() {
+                    return Err((Menu::Main(MainMenu), AppError::Unhandled("None of the preset wallets exist".to_string())))
+                }
+
+                (wallet_ids.len(), Some(wallet_ids))
+            }
+        };
+
+        let deployer = match select_deployer()
+            .map_err(|err| (Menu::Main(MainMenu), err))? {
                 Some(deployer) => deployer,
                 None => return Ok(Some(Menu::Main(MainMenu)))
             };
 
-        let rpc_client = app_data.rpc_client.read().await;
+        if let Some(SnipeStatus::Pending) = app_data.snipes.read().await.0.get(&deployer).map(|job| &job.status) {
+            return Err((Menu::PendingSnipes(PendingSnipes), AppError::Unhandled(format!("A snipe on {} is already pending", deployer))))
+        }
+
+        print_snipe_summary(Some(&deployer), wallet_count, &limits);
+
+        check_snipe_spend(app_data, wallet_count, &snipers, &limits).await
+            .map_err(|err| (Menu::Main(MainMenu), err))?;
+
+        let confirm = Confirm::with_theme(&ColorfulTheme::default())
+            .with_prompt("Arm this snipe?")
+            .default(true)
+            .interact()
+            .unwrap();
+
+        if !confirm {
+            return Ok(Some(Menu::Main(MainMenu)))
+        }
+
+        let record = new_snipe_record(app_data, deployer, wallet_count, &limits).await;
+        let params = CreateSnipeParams {
+            deployer,
+            wallet_count,
+            wallet_ids,
+            max_lamports_per_wallet: limits.max_lamports_per_wallet,
+            max_total_lamports: limits.max_total_lamports
+        };
+
         let result = Loader::new()
             .with_prompt("Creating snipe")
-            .interact(rpc_client.create_snipe(deployer, wallet_count))
+            .interact(dispatch::create_snipe(app_data, params, &user_wallets))
             .await;
 
         let pending_snipe = match result {
             Ok(result) => result,
-            Err(err) => return Err((Menu::Main(MainMenu), AppError::from(err)))
+            Err(err) => return Err((Menu::Main(MainMenu), err))
         };
 
-        let deployer = pending_snipe.deployer;
+        spawn_snipe_job(app_data, record, pending_snipe).await;
 
-        let loader = Loader::new()
-            .with_prompt("Snipe pending")
-            .interact_with_cancel(pending_snipe)
-            .await;
+        Ok(Some(Menu::PendingSnipes(PendingSnipes)))
+    }
+}
 
-        match loader {
-            Ok(Some(Ok(result))) => {
-                let mut active_project_write = app_data.active_project.write().await;
-                active_project_write.0 = Some(result.id);
-                drop(active_project_write);
+pub fn prompt_wallet_count(sniper_count: usize) -> Option<usize> {
+    Input::with_theme(&ColorfulTheme::default())
+        .with_prompt("Enter wallet amount")
+        .default(5.min(sniper_count))
+        .validate_with(|val: &usize| -> Result<(), String> {
+            if val > &sniper_count {
+                return Err("Wallet amount exceeds available wallets".to_string())
+            };
 
-                let mut projects_write = app_data.projects.write().await;
-                projects_write.insert(result.id, result);
-                drop(projects_write);
+            Ok(())
+        })
+        .interact_text()
+        .unwrap()
+}
 
-                return Ok(Some(Menu::ProjectMenu(ProjectMenu)))
-            },
-            Ok(Some(Err(snipe_err))) => return Err((Menu::Main(MainMenu), AppError::from(snipe_err))),
-            Ok(None) => return Ok(Some(Menu::CancelSnipe(CancelSnipe { deployer }))),
-            Err(err) => return Err((Menu::CancelSnipe(CancelSnipe { deployer }), AppError::from(err)))
+/// Picks a deployer from the address book or reads a new address, offering to save it.
+pub fn select_deployer() -> Result<Option<Pubkey>, AppError> {
+    let deployers = load_deployers()?;
+
+    let mut selection = vec![format!("{}", "EnterAddress")];
+    selection.extend(deployers.iter().map(|entry| format!("{} {}", entry.label, entry.address)));
+    selection.push(format!("{}", "Back"));
+
+    let index = FuzzySelect::with_theme(&ColorfulTheme::default())
+        .with_prompt("Select deployer")
+        .default(0)
+        .max_length(10)
+        .items(&selection)
+        .interact()?;
+
+    if index == selection.len() - 1 {
+        return Ok(None)
+    }
+    if index > 0 {
+        return Ok(Some(deployers[index - 1].address))
+    }
+
+    let deployer: Pubkey = match Input::with_theme(&ColorfulTheme::default())
+        .with_prompt("Enter the deployer address")
+        .interact_text()
+        .unwrap() {
+            Some(deployer) => deployer,
+            None => return Ok(None)
+        };
+
+    let save = Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt("Save this deployer to the address book?")
+        .default(false)
+        .interact()?;
+
+    if save {
+        if let Some(label) = Input::<String>::with_theme(&ColorfulTheme::default())
+            .with_prompt("Enter a label")
+            .interact_text()
+            .unwrap() {
+                save_deployer(&DeployerEntry { label, address: deployer })?;
+            }
+    }
+
+    Ok(Some(deployer))
+}
+
+/// Prompts for the optional spend limits and expiry of a snipe. Zero means no limit.
+pub fn prompt_snipe_limits() -> Option<SnipeLimits> {
+    let max_sol_per_wallet: f64 = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt("Enter max SOL per wallet (0 for no limit)")
+        .default(0f64)
+        .interact_text()
+        .unwrap()?;
+
+    let max_total_sol: f64 = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt("Enter total SOL spend cap (0 for no limit)")
+        .default(0f64)
+        .interact_text()
+        .unwrap()?;
+
+    let expiry_minutes: u64 = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt("Enter expiry in minutes (0 for no expiry)")
+        .default(0)
+        .interact_text()
+        .unwrap()?;
+
+    let to_lamports = |sol: f64| (sol > 0f64).then(|| (sol * LAMPORTS_PER_SOL as f64) as u64);
+
+    Some(SnipeLimits {
+        max_lamports_per_wallet: to_lamports(max_sol_per_wallet),
+        max_total_lamports: to_lamports(max_total_sol),
+        expiry: (expiry_minutes > 0).then(|| expiry_minutes * 60)
+    })
+}
+
+pub fn print_snipe_summary(deployer: Option<&Pubkey>, wallet_count: usize, limits: &SnipeLimits) {
+    let format_sol = |lamports: Option<u64>| lamports
+        .map(|lamports| format!("{} {}", lamports as f64 / LAMPORTS_PER_SOL as f64, style("SOL").cyan()))
+        .unwrap_or("no limit".to_string());
+
+    if let Some(deployer) = deployer {
+        println!("deployer: {}", deployer);
+    }
+    println!(
+        "wallets: {}\nmax_per_wallet: {}\ntotal_cap: {}\nexpires_in: {}",
+        wallet_count,
+        format_sol(limits.max_lamports_per_wallet),
+        format_sol(limits.max_total_lamports),
+        limits.expiry.map(|expiry| format_age(Duration::from_secs(expiry))).unwrap_or("never".to_string())
+    );
+
+    if let (Some(per_wallet), Some(total)) = (limits.max_lamports_per_wallet, limits.max_total_lamports) {
+        if per_wallet * wallet_count as u64 > total {
+            println!("{}", style("The total cap is lower than max_per_wallet across all wallets, spend stops at the cap").yellow());
+        }
+    }
+    println!("");
+}
+
+pub struct PendingSnipes;
+impl Handler for PendingSnipes {
+    async fn handle(&self, app_data: &Arc<AppData>) -> Result<Option<Menu>, (Menu, AppError)> {
+        let snipes = app_data.snipes.read().await;
+        let mut jobs: Vec<&SnipeJob> = snipes.0.values().collect();
+        jobs.sort_by_key(|job| job.created_at);
+
+        let deployers: Vec<Pubkey> = jobs.iter().map(|job| job.deployer).collect();
+        let now = unix_timestamp();
+        let mut selection: Vec<String> = jobs.iter().map(|job| format!(
+            "{} {} wallets {} {}{}",
+            job.deployer,
+            job.wallet_count,
+            format_age(job.age()),
+            job.status.to_string(),
+            match (&job.status, job.expires_at) {
+                (SnipeStatus::Pending, Some(expires_at)) => format!(" expires in {}", format_age(Duration::from_secs(expires_at.saturating_sub(now)))),
+                _ => "".to_string()
+            }
+        )).collect();
+        drop(snipes);
+
+        selection.push(format!("{}", "Back"));
+
+        let index = FuzzySelect::with_theme(&ColorfulTheme::default())
+            .with_prompt("Pending snipes")
+            .default(0)
+            .max_length(10)
+            .items(&selection)
+            .interact()
+            .unwrap();
+
+        if index == selection.len() - 1 {
+            return Ok(Some(Menu::Main(MainMenu)))
+        }
+
+        let deployer = deployers[index];
+        let pending = matches!(
+            app_data.snipes.read().await.0.get(&deployer).map(|job| &job.status),
+            Some(SnipeStatus::Pending)
+        );
+
+        if !pending {
+            app_data.snipes.write().await.0.remove(&deployer);
+            return Ok(Some(Menu::PendingSnipes(PendingSnipes)))
+        }
+
+        let cancel = Confirm::with_theme(&ColorfulTheme::default())
+            .with_prompt(format!("Cancel the snipe on {}?", deployer))
+            .default(false)
+            .interact()
+            .unwrap();
+
+        if cancel {
+            return Ok(Some(Menu::CancelSnipe(CancelSnipe { deployer })))
         }
+
+        Ok(Some(Menu::PendingSnipes(PendingSnipes)))
     }
 }
 
 pub struct CancelSnipe {
-    deployer: Pubkey
+    pub deployer: Pubkey
 }
 impl Handler for CancelSnipe {
     async fn handle(&self, app_data: &Arc<AppData>) -> Result<Option<Menu>, (Menu, AppError)> {
-        let rpc_client = app_data.rpc_client.read().await;
         let result = Loader::new()
             .with_prompt("cancel_snipe in progress")
-            .interact(rpc_client.cancel_snipe(&self.deployer))
+            .interact(dispatch::cancel_snipe(app_data, &self.deployer))
             .await;
-        drop(rpc_client);
 
         if let Err(err) = result {
-            return Err((Menu::Main(MainMenu), AppError::from(err)))
-        }        
+            return Err((Menu::PendingSnipes(PendingSnipes), err))
+        }
+
+        if let Some(job) = app_data.snipes.write().await.0.get_mut(&self.deployer) {
+            job.abort();
+        }
 
-        Ok(Some(Menu::Main(MainMenu)))
+        remove_snipe(&self.deployer)
+            .map_err(|err| (Menu::PendingSnipes(PendingSnipes), AppError::from(err)))?;
+
+        Ok(Some(Menu::PendingSnipes(PendingSnipes)))
+    }
+}
+
+pub enum OrphanedSnipeOptions {
+    ReAttach,
+    Wait,
+    Cancel,
+    Forget,
+    Back
+}
+
+impl ToString for OrphanedSnipeOptions {
+    fn to_string(&self) -> String {
+        match self {
+            Self::ReAttach => "ReAttach".to_string(),
+            Self::Wait => "Wait".to_string(),
+            Self::Cancel => "Cancel".to_string(),
+            Self::Forget => "Forget".to_string(),
+            Self::Back => "Back".to_string()
+        }
+    }
+}
+
+impl From<usize> for OrphanedSnipeOptions {
+    fn from(value: usize) -> Self {
+        match value {
+            0 => Self::ReAttach,
+            1 => Self::Wait,
+            2 => Self::Cancel,
+            3 => Self::Forget,
+            4 => Self::Back,
+            _ => panic!("Received invalid orphaned snipe index")
+        }
     }
-}
\ No newline at end of file
+}
+
+/// Snipes persisted by a previous session that are not running in this one.
+pub struct OrphanedSnipes;
+impl Handler for OrphanedSnipes {
+    async fn handle(&self, app_data: &Arc<AppData>) -> Result<Option<Menu>, (Menu, AppError)> {
+        let running = app_data.snipes.read().await.0.keys().cloned().collect::<Vec<Pubkey>>();
+        let records: Vec<SnipeRecord> = load_snipes()
+            .map_err(|err| (Menu::Main(MainMenu), AppError::from(err)))?
+            .into_iter()
+            .filter(|record| !running.contains(&record.deployer))
+            .collect();
+
+        if records.is_empty() {
+            return Ok(Some(Menu::Main(MainMenu)))
+        }
+
+        let projects = Loader::new()
+            .with_prompt("reconciling pending snipes")
+            .interact(dispatch::user_projects(app_data))
+            .await
+            .map_err(|err| (Menu::Main(MainMenu), err))?;
+
+        let mut orphans = vec![];
+        for record in records {
+            if record.is_expired(unix_timestamp()) {
+                let result = Loader::new()
+                    .with_prompt("cancel_snipe in progress")
+                    .interact(dispatch::cancel_snipe(app_data, &record.deployer))
+                    .await;
+
+                match result {
+                    Ok(_) | Err(AppError::MoonboisClientError(MoonboisClientError::NotFound)) => {
+                        app_data.notifications.write().await.push(format!("Snipe on {} expired while offline and was cancelled", record.deployer));
+                        remove_snipe(&record.deployer)
+                            .map_err(|err| (Menu::Main(MainMenu), AppError::from(err)))?;
+                        continue;
+                    },
+                    Err(err) => return Err((Menu::Main(MainMenu), err))
+                }
+            }
+
+            let filled = projects.values()
+                .find(|project| project.deployer == record.deployer && !record.known_projects.contains(&project.id));
+
+            match filled {
+                Some(project) => {
+                    app_data.notifications.write().await.push(format!("Snipe on {} filled while offline, {} was added to your tokens", record.deployer, project.name));
+                    remove_snipe(&record.deployer)
+                        .map_err(|err| (Menu::Main(MainMenu), AppError::from(err)))?;
+                },
+                None => orphans.push(record)
+            }
+        }
+
+        if orphans.is_empty() {
+            return Ok(Some(Menu::Main(MainMenu)))
+        }
+
+        let mut selection: Vec<String> = orphans.iter().map(|record| format!(
+            "{} {} wallets {}",
+            record.deployer,
+            record.wallet_count,
+            format_age(Duration::from_secs(unix_timestamp().saturating_sub(record.created_at)))
+        )).collect();
+
+        selection.push(format!("{}", "Continue"));
+
+        let index = FuzzySelect::with_theme(&ColorfulTheme::default())
+            .with_prompt("Snipes left pending by a previous session")
+            .default(0)
+            .items(&selection)
+            .interact()
+            .unwrap();
+
+        if index == selection.len() - 1 {
+            return Ok(Some(Menu::Main(MainMenu)))
+        }
+
+        let record = orphans.swap_remove(index);
+        let deployer = record.deployer;
+
+        println!("{}", style(
+            "The backend reports no status for a snipe it is still running. ReAttach and Wait infer the fill from a \
+            new project of this deployer showing up, and otherwise only end at the snipe's expiry."
+        ).dim());
+
+        let selection = match FuzzySelect::with_theme(&ColorfulTheme::default())
+            .with_prompt(format!("Snipe on {}", deployer))
+            .default(0)
+            .items(vec![
+                OrphanedSnipeOptions::ReAttach,
+                OrphanedSnipeOptions::Wait,
+                OrphanedSnipeOptions::Cancel,
+                OrphanedSnipeOptions::Forget,
+                OrphanedSnipeOptions::Back
+            ])
+            .interact() {
+                Ok(selection) => selection,
+                Err(err) => return Err((Menu::OrphanedSnipes(OrphanedSnipes), AppError::from(err)))
+            };
+
+        match OrphanedSnipeOptions::from(selection) {
+            OrphanedSnipeOptions::ReAttach => {
+                spawn_snipe_job(app_data, record.clone(), wait_for_snipe(Arc::clone(app_data), record)).await;
+            },
+            OrphanedSnipeOptions::Wait => {
+                let loader = Loader::new()
+                    .with_prompt("Snipe pending")
+                    .interact_with_cancel(wait_for_snipe(Arc::clone(app_data), record))
+                    .await
+                    .map_err(|err| (Menu::OrphanedSnipes(OrphanedSnipes), AppError::from(err)))?;
+
+                match loader {
+                    Some(Ok(project)) => {
+                        remove_snipe(&deployer)
+                            .map_err(|err| (Menu::OrphanedSnipes(OrphanedSnipes), AppError::from(err)))?;
+
+                        app_data.active_project.write().await.0 = Some(project.id);
+                        app_data.projects.write().await.insert(project.id, project);
+
+                        return Ok(Some(Menu::ProjectMenu(ProjectMenu)))
+                    },
+                    Some(Err(err)) => return Err((Menu::OrphanedSnipes(OrphanedSnipes), err)),
+                    None => {}
+                }
+            },
+            OrphanedSnipeOptions::Cancel => {
+                let result = Loader::new()
+                    .with_prompt("cancel_snipe in progress")
+                    .interact(dispatch::cancel_snipe(app_data, &deployer))
+                    .await;
+
+                match result {
+                    Ok(_) | Err(AppError::MoonboisClientError(MoonboisClientError::NotFound)) => {},
+                    Err(err) => return Err((Menu::OrphanedSnipes(OrphanedSnipes), err))
+                }
+
+                remove_snipe(&deployer)
+                    .map_err(|err| (Menu::OrphanedSnipes(OrphanedSnipes), AppError::from(err)))?;
+            },
+            OrphanedSnipeOptions::Forget => {
+                remove_snipe(&deployer)
+                    .map_err(|err| (Menu::OrphanedSnipes(OrphanedSnipes), AppError::from(err)))?;
+            },
+            OrphanedSnipeOptions::Back => {}
+        }
+
+        Ok(Some(Menu::OrphanedSnipes(OrphanedSnipes)))
+    }
+}
@@ -1,9 +1,21 @@
+use console::style;
 use dialoguer::theme::ColorfulTheme;
+use crate::chain::analysis::analyze_token;
+use crate::chain::analysis::TokenReport;
 use crate::dialogue::loader::Loader;
+use crate::paper::dispatch;
+use crate::storage::migrations::is_migrated;
+use crate::storage::risk::Spend;
+use crate::storage::risk::SpendKind;
+use crate::storage::token_checks::acknowledge;
+use crate::storage::token_checks::is_acknowledged;
+use crate::storage::transactions::TransactionRecord;
 use solana_sdk::native_token::LAMPORTS_PER_SOL;
 use std::sync::Arc;
 
+use dialoguer::Confirm;
 use dialoguer::Input;
+use solana_sdk::pubkey::Pubkey;
 
 use crate::AppData;
 use crate::AppError;
@@ -11,6 +23,8 @@ use crate::Menu;
 
 use super::main::MainMenu;
 use super::project::ProjectMenu;
+use super::risk::check_spend;
+use super::transactions::report_transactions;
 use super::wallet::select_wallet;
 use super::Handler;
 
@@ -26,6 +40,19 @@ impl Sell {
 }
 impl Handler for Sell {
     async fn handle(&self, app_data: &Arc<AppData>) -> Result<Option<Menu>, (Menu, AppError)> {
+        let mint_id = match app_data.active_project.read().await.0 {
+            Some(project_id) => match app_data.projects.read().await.get(&project_id) {
+                Some(project) => project.pumpfun.mint_id,
+                None => return Err((Menu::Main(MainMenu), AppError::ProjectNotFound))
+            },
+            None => return Err((Menu::Main(MainMenu), AppError::ProjectNotFound))
+        };
+
+        if !confirm_curve_trade(&mint_id, "sell")
+            .map_err(|err| (Menu::ProjectMenu(ProjectMenu), err))? {
+                return Ok(Some(Menu::ProjectMenu(ProjectMenu)))
+            }
+
         if !self.auto {
             if let Some(wallet) = select_wallet(app_data).await
                 .map_err(|err| (Menu::Main(MainMenu), AppError::from(err)))? {
@@ -34,16 +61,21 @@ impl Handler for Sell {
                         None => return Err((Menu::Main(MainMenu), AppError::ProjectNotFound))
                     };
 
-                    let rpc_client = app_data.rpc_client.read().await;
                     let result = Loader::new()
                         .with_prompt("sell in progress")
-                        .interact(rpc_client.sell(project_id, wallet.id))
+                        .interact(dispatch::sell(app_data, project_id, &wallet))
                         .await;
-                    drop(rpc_client);
 
-                    if let Err(err) = result {
-                        return Err((Menu::ProjectMenu(ProjectMenu), AppError::from(err)))
-                    }
+                    let signature = match result {
+                        Ok(signature) => signature,
+                        Err(err) => return Err((Menu::ProjectMenu(ProjectMenu), AppError::from(err)))
+                    };
+
+                    report_transactions(app_data, vec![
+                        TransactionRecord::new("sell", signature)
+                            .with_project(project_id)
+                            .with_wallet(wallet.public_key)
+                    ]).await;
             }
         } else {
             let project_id = match app_data.active_project.read().await.0 {
@@ -51,16 +83,19 @@ impl Handler for Sell {
                 None => return Err((Menu::Main(MainMenu), AppError::ProjectNotFound))
             };
 
-            let rpc_client = app_data.rpc_client.read().await;
             let result = Loader::new()
                 .with_prompt("auto_sell in progress")
-                .interact(rpc_client.auto_sell(project_id))
+                .interact(dispatch::auto_sell(app_data, project_id))
                 .await;
-            drop(rpc_client);
 
-            if let Err(err) = result {
-                return Err((Menu::ProjectMenu(ProjectMenu), AppError::from(err)))
-            }
+            let signatures = match result {
+                Ok(signatures) => signatures,
+                Err(err) => return Err((Menu::ProjectMenu(ProjectMenu), AppError::from(err)))
+            };
+
+            report_transactions(app_data, signatures.into_iter().map(|signature| {
+                TransactionRecord::new("auto_sell", signature).with_project(project_id)
+            }).collect()).await;
         }
 
         Ok(Some(Menu::ProjectMenu(ProjectMenu)))
@@ -79,6 +114,24 @@ impl Buy {
 }
 impl Handler for Buy {
     async fn handle(&self, app_data: &Arc<AppData>) -> Result<Option<Menu>, (Menu, AppError)> {
+        let mint_id = match app_data.active_project.read().await.0 {
+            Some(project_id) => match app_data.projects.read().await.get(&project_id) {
+                Some(project) => project.pumpfun.mint_id,
+                None => return Err((Menu::Main(MainMenu), AppError::ProjectNotFound))
+            },
+            None => return Err((Menu::Main(MainMenu), AppError::ProjectNotFound))
+        };
+
+        if !confirm_token_safety(app_data, &mint_id).await
+            .map_err(|err| (Menu::ProjectMenu(ProjectMenu), err))? {
+                return Ok(Some(Menu::ProjectMenu(ProjectMenu)))
+            }
+
+        if !confirm_curve_trade(&mint_id, "buy")
+            .map_err(|err| (Menu::ProjectMenu(ProjectMenu), err))? {
+                return Ok(Some(Menu::ProjectMenu(ProjectMenu)))
+            }
+
         if !self.auto {
             if let Some(wallet) = select_wallet(app_data).await
                 .map_err(|err| (Menu::Main(MainMenu), AppError::from(err)))? {
@@ -95,17 +148,29 @@ impl Handler for Buy {
                             None => return Ok(Some(Menu::ProjectMenu(ProjectMenu)))
                         };
                     let amount = amount * LAMPORTS_PER_SOL as f64;
+
+                    check_spend(app_data, Spend {
+                        kind: SpendKind::Trade,
+                        lamports: amount as u64,
+                        wallets: vec![(wallet.public_key, amount as u64)]
+                    }).await.map_err(|err| (Menu::ProjectMenu(ProjectMenu), err))?;
                     
-                    let rpc_client = app_data.rpc_client.read().await;
                     let result = Loader::new()
                         .with_prompt("buy in progress")
-                        .interact(rpc_client.buy(project_id, wallet.id, amount as u64))
+                        .interact(dispatch::buy(app_data, project_id, &wallet, amount as u64))
                         .await;
-                    drop(rpc_client);
 
-                    if let Err(err) = result {
-                        return Err((Menu::ProjectMenu(ProjectMenu), AppError::from(err)))
-                    }
+                    let signature = match result {
+                        Ok(signature) => signature,
+                        Err(err) => return Err((Menu::ProjectMenu(ProjectMenu), AppError::from(err)))
+                    };
+
+                    report_transactions(app_data, vec![
+                        TransactionRecord::new("buy", signature)
+                            .with_project(project_id)
+                            .with_wallet(wallet.public_key)
+                            .with_lamports(amount as u64)
+                    ]).await;
             }
         } else {
             let project_id = match app_data.active_project.read().await.0 {
@@ -123,18 +188,98 @@ impl Handler for Buy {
 
             let amount = amount * LAMPORTS_PER_SOL as f64;
 
-            let rpc_client = app_data.rpc_client.read().await;
+            let wallets: Vec<(Pubkey, u64)> = match &app_data.user.read().await.0 {
+                Some(user) => user.wallets.values().map(|wallet| (wallet.public_key, amount as u64)).collect(),
+                None => return Err((Menu::Main(MainMenu), AppError::UserNotFound))
+            };
+            check_spend(app_data, Spend {
+                kind: SpendKind::AutoTrade,
+                lamports: (amount as u64).saturating_mul(wallets.len() as u64),
+                wallets
+            }).await.map_err(|err| (Menu::ProjectMenu(ProjectMenu), err))?;
+
             let result = Loader::new()
                 .with_prompt("buy in progress")
-                .interact(rpc_client.auto_buy(project_id, amount as u64))
+                .interact(dispatch::auto_buy(app_data, project_id, amount as u64))
                 .await;
-            drop(rpc_client);
 
-            if let Err(err) = result {
-                return Err((Menu::ProjectMenu(ProjectMenu), AppError::from(err)))
-            }
+            let signatures = match result {
+                Ok(signatures) => signatures,
+                Err(err) => return Err((Menu::ProjectMenu(ProjectMenu), AppError::from(err)))
+            };
+
+            report_transactions(app_data, signatures.into_iter().map(|signature| {
+                TransactionRecord::new("auto_buy", signature)
+                    .with_project(project_id)
+                    .with_lamports(amount as u64)
+            }).collect()).await;
         }
 
         Ok(Some(Menu::ProjectMenu(ProjectMenu)))
     }
-}
\ No newline at end of file
+}
+
+/// Shows the token safety report before the first buy on a mint. Tokens with warnings need an
+/// explicit confirmation, which is remembered so later buys go straight through. Paper buys skip
+/// the report, they risk no funds and tokens launched in the ledger do not exist on chain.
+pub async fn confirm_token_safety(app_data: &Arc<AppData>, mint_id: &Pubkey) -> Result<bool, AppError> {
+    if app_data.paper.is_some() || is_acknowledged(mint_id)? {
+        return Ok(true)
+    }
+
+    let report = Loader::new()
+        .with_prompt("analyzing token")
+        .interact(analyze_token(&app_data.solana_client, mint_id))
+        .await?;
+
+    print_token_report(&report);
+
+    let proceed = report.warnings.is_empty() || Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt("This token has warnings, do you want to continue with the buy?")
+        .default(false)
+        .interact()?;
+
+    if proceed {
+        acknowledge(mint_id)?;
+    }
+
+    Ok(proceed)
+}
+
+/// Warns before trading a mint whose bonding curve already completed, buys and sells go through
+/// the curve and will fail once the token migrated.
+pub fn confirm_curve_trade(mint_id: &Pubkey, action: &str) -> Result<bool, AppError> {
+    if !is_migrated(mint_id)? {
+        return Ok(true)
+    }
+
+    println!("{}", style("This token completed its bonding curve and migrated, curve-based buy and sell will no longer work ⚠️").yellow());
+
+    Ok(Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt(format!("Do you still want to {}?", action))
+        .default(false)
+        .interact()?)
+}
+
+pub fn print_token_report(report: &TokenReport) {
+    if let Some(metadata) = &report.metadata {
+        println!("token: {} {}", metadata.name, style(metadata.symbol.to_uppercase()).magenta());
+    }
+    println!(
+        "mint_id: {}\ntoken_program: {}\ndecimals: {}\nsupply: {}",
+        report.mint_id,
+        if report.token_program == spl_token_2022::id() { "token-2022" } else { "spl-token" },
+        report.decimals,
+        report.ui_supply()
+    );
+
+    if report.warnings.is_empty() {
+        println!("{}\n", style("No token warnings").green());
+        return;
+    }
+
+    for warning in report.warnings.iter() {
+        println!("{}", style(format!("⚠️ {}", warning.to_string())).yellow());
+    }
+    println!("");
+}
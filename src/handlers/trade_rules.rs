@@ -0,0 +1,286 @@
+use console::style;
+use dialoguer::theme::ColorfulTheme;
+use crate::chain::analysis::PUMPFUN_DECIMALS;
+use crate::dialogue::loader::Loader;
+use crate::jobs::format_age;
+use crate::paper::dispatch;
+use crate::storage::trade_rules::find_project_rules;
+use crate::storage::trade_rules::load_rule_log;
+use crate::storage::trade_rules::save_project_rules;
+use crate::storage::trade_rules::ProjectRules;
+use crate::storage::trade_rules::RuleTrigger;
+use crate::storage::transactions::load_transactions;
+use crate::storage::transactions::project_flows;
+use crate::storage::transactions::unix_timestamp;
+use moonbois_core::ProjectDTO;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use std::sync::Arc;
+use std::time::Duration;
+
+use dialoguer::FuzzySelect;
+use dialoguer::Input;
+
+use crate::AppData;
+use crate::AppError;
+use crate::Menu;
+
+use super::main::MainMenu;
+use super::project::ProjectMenu;
+use super::Handler;
+
+static RULE_LOG_LENGTH: usize = 5;
+
+pub enum TradeRulesOptions {
+    AddTakeProfit,
+    AddStopLoss,
+    SetEntryPrice,
+    Enable,
+    Disable,
+    Back
+}
+
+impl ToString for TradeRulesOptions {
+    fn to_string(&self) -> String {
+        match self {
+            Self::AddTakeProfit => "AddTakeProfit".to_string(),
+            Self::AddStopLoss => "AddStopLoss".to_string(),
+            Self::SetEntryPrice => "SetEntryPrice".to_string(),
+            Self::Enable => "Enable".to_string(),
+            Self::Disable => "Disable".to_string(),
+            Self::Back => "Back".to_string()
+        }
+    }
+}
+
+pub struct TradeRules;
+impl Handler for TradeRules {
+    async fn handle(&self, app_data: &Arc<AppData>) -> Result<Option<Menu>, (Menu, AppError)> {
+        let project: ProjectDTO = match app_data.active_project.read().await.0 {
+            Some(project_id) => match app_data.projects.read().await.get(&project_id) {
+                Some(project) => project.clone(),
+                None => return Err((Menu::Main(MainMenu), AppError::ProjectNotFound))
+            },
+            None => return Err((Menu::Main(MainMenu), AppError::ProjectNotFound))
+        };
+
+        let price = current_price(app_data, &project).await;
+        let stored = find_project_rules(project.id)
+            .map_err(|err| (Menu::ProjectMenu(ProjectMenu), AppError::from(err)))?;
+        let mut project_rules = match stored {
+            Some(project_rules) => project_rules,
+            None => ProjectRules::new(project.id, project.pumpfun.mint_id.to_string(), default_entry_price(app_data, &project, price).await)
+        };
+
+        println!(
+            "entry_price: {} {}\ncurrent_price: {}\nstatus: {}",
+            project_rules.entry_price,
+            style("SOL").cyan(),
+            match price {
+                Some(price) if project_rules.entry_price > 0.0 => format!("{} {} ({:.2}x)", price, style("SOL").cyan(), price / project_rules.entry_price),
+                Some(price) => format!("{} {}", price, style("SOL").cyan()),
+                None => "unavailable".to_string()
+            },
+            if project_rules.enabled { style("enabled").green() } else { style("disabled").dim() }
+        );
+
+        if project_rules.entry_price <= 0.0 {
+            println!("{}", style("Set an entry price to arm the rules ⚠️").yellow());
+        }
+
+        let log = load_rule_log()
+            .map_err(|err| (Menu::ProjectMenu(ProjectMenu), AppError::from(err)))?;
+        let events: Vec<_> = log.iter().rev().filter(|event| event.project_id == project.id).take(RULE_LOG_LENGTH).collect();
+        for event in events {
+            println!(
+                "  - {} ago {} at {} SOL {}",
+                format_age(Duration::from_secs(unix_timestamp().saturating_sub(event.timestamp))),
+                event.rules.join(", "),
+                event.price,
+                match &event.error {
+                    Some(err) => style(format!("failed - {}", err)).red(),
+                    None => style(format!("{} transactions", event.signatures.len())).green()
+                }
+            );
+        }
+
+        let options = [
+            TradeRulesOptions::AddTakeProfit,
+            TradeRulesOptions::AddStopLoss,
+            TradeRulesOptions::SetEntryPrice,
+            if project_rules.enabled { TradeRulesOptions::Disable } else { TradeRulesOptions::Enable },
+            TradeRulesOptions::Back
+        ];
+        let mut selection: Vec<String> = project_rules.rules.iter()
+            .map(|rule| format!("{} {}", rule.to_string(), if rule.fired { style("fired").dim() } else { style("armed").green() }))
+            .collect();
+        selection.extend(options.iter().map(|option| option.to_string()));
+
+        let index = FuzzySelect::with_theme(&ColorfulTheme::default())
+            .with_prompt("Trade rules")
+            .default(0)
+            .max_length(10)
+            .items(&selection)
+            .interact()
+            .map_err(|err| (Menu::ProjectMenu(ProjectMenu), AppError::from(err)))?;
+
+        if index < project_rules.rules.len() {
+            let rule = &project_rules.rules[index];
+            let actions = if rule.fired { vec!["Rearm", "Remove", "Back"] } else { vec!["Remove", "Back"] };
+
+            let action = FuzzySelect::with_theme(&ColorfulTheme::default())
+                .with_prompt(rule.to_string())
+                .default(0)
+                .items(&actions)
+                .interact()
+                .map_err(|err| (Menu::TradeRules(TradeRules), AppError::from(err)))?;
+
+            match actions[action] {
+                "Rearm" => project_rules.rules[index].fired = false,
+                "Remove" => { project_rules.rules.remove(index); },
+                _ => return Ok(Some(Menu::TradeRules(TradeRules)))
+            }
+        } else {
+            match options[index - project_rules.rules.len()] {
+                TradeRulesOptions::AddTakeProfit => {
+                    let multiple: f64 = match Input::with_theme(&ColorfulTheme::default())
+                        .with_prompt("Enter the multiple of the entry price to sell at, e.g. 2 for 2x")
+                        .validate_with(|val: &f64| -> Result<(), &str> {
+                            if *val <= 1f64 {
+                                return Err("Enter a multiple above 1")
+                            }
+
+                            Ok(())
+                        })
+                        .interact_text()
+                        .unwrap() {
+                            Some(multiple) => multiple,
+                            None => return Ok(Some(Menu::TradeRules(TradeRules)))
+                        };
+
+                    let Some(sell_share) = prompt_sell_share(app_data).await
+                        .map_err(|err| (Menu::TradeRules(TradeRules), err))? else {
+                            return Ok(Some(Menu::TradeRules(TradeRules)))
+                        };
+
+                    project_rules.add_rule(RuleTrigger::TakeProfit { multiple }, sell_share);
+                },
+                TradeRulesOptions::AddStopLoss => {
+                    let drawdown: f64 = match Input::with_theme(&ColorfulTheme::default())
+                        .with_prompt("Enter the percentage below the entry price to sell at")
+                        .validate_with(|val: &f64| -> Result<(), &str> {
+                            if *val <= 0f64 || *val >= 100f64 {
+                                return Err("Enter a percentage between 0 and 100")
+                            }
+
+                            Ok(())
+                        })
+                        .interact_text()
+                        .unwrap() {
+                            Some(drawdown) => drawdown,
+                            None => return Ok(Some(Menu::TradeRules(TradeRules)))
+                        };
+
+                    let Some(sell_share) = prompt_sell_share(app_data).await
+                        .map_err(|err| (Menu::TradeRules(TradeRules), err))? else {
+                            return Ok(Some(Menu::TradeRules(TradeRules)))
+                        };
+
+                    project_rules.add_rule(RuleTrigger::StopLoss { drawdown: drawdown / 100.0 }, sell_share);
+                },
+                TradeRulesOptions::SetEntryPrice => {
+                    let entry_price: f64 = match Input::with_theme(&ColorfulTheme::default())
+                        .with_prompt("Enter the entry price of one token in SOL")
+                        .default(project_rules.entry_price)
+                        .validate_with(|val: &f64| -> Result<(), &str> {
+                            if *val <= 0f64 {
+                                return Err("Enter a price above 0")
+                            }
+
+                            Ok(())
+                        })
+                        .interact_text()
+                        .unwrap() {
+                            Some(entry_price) => entry_price,
+                            None => return Ok(Some(Menu::TradeRules(TradeRules)))
+                        };
+
+                    project_rules.entry_price = entry_price;
+                },
+                TradeRulesOptions::Enable => project_rules.enabled = true,
+                TradeRulesOptions::Disable => project_rules.enabled = false,
+                TradeRulesOptions::Back => return Ok(Some(Menu::ProjectMenu(ProjectMenu)))
+            }
+        }
+
+        save_project_rules(&project_rules)
+            .map_err(|err| (Menu::TradeRules(TradeRules), AppError::from(err)))?;
+
+        Ok(Some(Menu::TradeRules(TradeRules)))
+    }
+}
+
+/// Asks for the percentage of the position a rule sells, as a share between 0 and 1. Selling part
+/// of a position moves tokens between snipers, so it is refused with a single funded sniper.
+async fn prompt_sell_share(app_data: &Arc<AppData>) -> Result<Option<f64>, AppError> {
+    let sell_share = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt("Enter the percentage of the position to sell")
+        .default(100f64)
+        .validate_with(|val: &f64| -> Result<(), &str> {
+            if *val <= 0f64 || *val > 100f64 {
+                return Err("Enter a percentage between 0 and 100")
+            }
+
+            Ok(())
+        })
+        .interact_text()
+        .unwrap()
+        .map(|percentage: f64| percentage / 100.0);
+
+    let funded = match &app_data.user.read().await.0 {
+        Some(user) => user.wallets.values().filter(|wallet| wallet.sol_balance > 0).count(),
+        None => return Err(AppError::UserNotFound)
+    };
+
+    match sell_share {
+        Some(sell_share) if sell_share < 1.0 && funded < 2 => Err(AppError::Unhandled(
+            "Selling part of a position needs at least two funded sniper wallets, add a wallet or sell 100%".to_string()
+        )),
+        sell_share => Ok(sell_share)
+    }
+}
+
+async fn current_price(app_data: &Arc<AppData>, project: &ProjectDTO) -> Option<f64> {
+    if let Some((mint, curve)) = &app_data.bonding_curve.read().await.0 {
+        if *mint == project.pumpfun.mint_id {
+            return Some(curve.price()).filter(|_| !curve.complete)
+        }
+    }
+
+    let result = Loader::new()
+        .with_prompt("loading bonding curve")
+        .interact(dispatch::bonding_curve(app_data, &project.pumpfun.mint_id))
+        .await;
+
+    match result {
+        Ok(Some(curve)) if !curve.complete => Some(curve.price()),
+        _ => None
+    }
+}
+
+/// Average buy price from the recorded buys while nothing was sold yet, the current price otherwise.
+async fn default_entry_price(app_data: &Arc<AppData>, project: &ProjectDTO, price: Option<f64>) -> f64 {
+    let tokens: u64 = match &app_data.user.read().await.0 {
+        Some(user) => user.wallets.values().filter_map(|wallet| wallet.token_balance).sum(),
+        None => 0
+    };
+
+    if let Ok(records) = load_transactions() {
+        let flows = project_flows(&records, project.id);
+        if flows.spent > 0 && flows.received == 0 && flows.unpriced_sells == 0 && tokens > 0 {
+            let sol = flows.spent as f64 / LAMPORTS_PER_SOL as f64;
+            return sol / (tokens as f64 / 10f64.powi(PUMPFUN_DECIMALS as i32))
+        }
+    }
+
+    price.unwrap_or(0.0)
+}
@@ -0,0 +1,166 @@
+use console::style;
+use dialoguer::theme::ColorfulTheme;
+use crate::chain::confirm::get_balance_change;
+use crate::chain::confirm::get_signer;
+use crate::chain::confirm::ConfirmationStatus;
+use crate::chain::explorer_url;
+use crate::dialogue::loader::Loader;
+use crate::paper::dispatch;
+use crate::storage::transactions::load_transactions;
+use crate::storage::transactions::record_transactions;
+use crate::storage::transactions::save_transactions;
+use crate::storage::transactions::TransactionRecord;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use dialoguer::FuzzySelect;
+
+use crate::AppData;
+use crate::AppError;
+use crate::Menu;
+
+use super::main::MainMenu;
+use super::Handler;
+
+static TRANSACTION_HISTORY_LENGTH: usize = 50;
+
+pub struct Transactions;
+impl Handler for Transactions {
+    async fn handle(&self, _app_data: &Arc<AppData>) -> Result<Option<Menu>, (Menu, AppError)> {
+        let mut transactions = load_transactions()
+            .map_err(|err| (Menu::Main(MainMenu), AppError::from(err)))?;
+        transactions.reverse();
+        transactions.truncate(TRANSACTION_HISTORY_LENGTH);
+
+        let mut selection: Vec<String> = transactions.iter().map(|record| format!(
+            "{} {} {} {}",
+            record.action,
+            &record.signature[0..8],
+            record.status.to_string(),
+            record.lamports.map(|lamports| format!("{} SOL", lamports as f64 / LAMPORTS_PER_SOL as f64)).unwrap_or("".to_string())
+        )).collect();
+
+        selection.push(format!("{}", "Back"));
+
+        let index = FuzzySelect::with_theme(&ColorfulTheme::default())
+            .with_prompt("Transactions")
+            .default(0)
+            .max_length(10)
+            .items(&selection)
+            .interact()
+            .unwrap();
+
+        if index == selection.len() - 1 {
+            return Ok(Some(Menu::Main(MainMenu)))
+        }
+
+        print_transaction(&transactions[index]);
+        FuzzySelect::with_theme(&ColorfulTheme::default())
+            .item("Back")
+            .default(0)
+            .interact()
+            .unwrap();
+
+        Ok(Some(Menu::Transactions(Transactions)))
+    }
+}
+
+/// Tracks freshly submitted transactions to confirmation, records them locally and prints the outcome inline.
+pub async fn report_transactions(app_data: &Arc<AppData>, records: Vec<TransactionRecord>) -> Vec<TransactionRecord> {
+    let records = confirm_transactions(app_data, records).await;
+
+    FuzzySelect::with_theme(&ColorfulTheme::default())
+        .item("Back")
+        .default(0)
+        .interact()
+        .unwrap();
+
+    records
+}
+
+/// Same as `report_transactions` without waiting on the user, for flows that keep going afterwards.
+pub async fn confirm_transactions(app_data: &Arc<AppData>, mut records: Vec<TransactionRecord>) -> Vec<TransactionRecord> {
+    Loader::new()
+        .with_prompt("confirming transactions")
+        .interact(dispatch::settle(app_data, &mut records))
+        .await;
+
+    if let Err(err) = record_transactions(&records) {
+        println!("{}\n  - {}", style("Unable to record transactions ⚠️").yellow(), style(err.to_string()).dim());
+    }
+
+    for record in records.iter() {
+        print_transaction(record);
+    }
+
+    records
+}
+
+/// Fills in the proceeds of recorded sells from their on-chain balance change, the backend only
+/// returns a signature for them. Each record is credited with the change of its own sniper, so
+/// an auto sell that moved several wallets in one transaction is not counted once per record.
+pub async fn price_recorded_sells(app_data: &Arc<AppData>) -> Result<Vec<TransactionRecord>, AppError> {
+    let mut records = load_transactions()?;
+    let (fee_payer, wallets): (Pubkey, Vec<Pubkey>) = match &app_data.user.read().await.0 {
+        Some(user) => (user.public_key, user.wallets.values().map(|wallet| wallet.public_key).collect()),
+        None => return Err(AppError::UserNotFound)
+    };
+
+    let mut signature_counts: HashMap<String, usize> = HashMap::new();
+    for record in records.iter() {
+        *signature_counts.entry(record.signature.clone()).or_default() += 1;
+    }
+
+    let mut updated = false;
+    for record in records.iter_mut() {
+        if !matches!(record.action.as_str(), "sell" | "auto_sell") || record.lamports.is_some() || !record.succeeded() {
+            continue;
+        }
+        let Ok(signature) = Signature::from_str(&record.signature) else { continue };
+
+        if record.wallet.is_none() {
+            if let Some(wallet) = get_signer(&app_data.solana_client, &signature, &wallets).await {
+                record.wallet = Some(wallet.to_string());
+                updated = true;
+            }
+        }
+
+        // Without a known sniper the whole change is only safe to use when no other record shares the transaction
+        let accounts = match record.wallet.as_deref().and_then(|wallet| Pubkey::from_str(wallet).ok()) {
+            Some(wallet) => vec![wallet],
+            None if signature_counts[&record.signature] == 1 => std::iter::once(fee_payer).chain(wallets.iter().copied()).collect(),
+            None => continue
+        };
+
+        if let Some(change) = get_balance_change(&app_data.solana_client, &signature, &accounts).await {
+            record.lamports = Some(change.max(0) as u64);
+            updated = true;
+        }
+    }
+
+    if updated {
+        save_transactions(&records)?;
+    }
+
+    Ok(records)
+}
+
+fn print_transaction(record: &TransactionRecord) {
+    let status = match &record.status {
+        ConfirmationStatus::Confirmed | ConfirmationStatus::Finalized => style(record.status.to_string()).green(),
+        ConfirmationStatus::Pending => style(record.status.to_string()).yellow(),
+        ConfirmationStatus::Failed { .. } => style(record.status.to_string()).red()
+    };
+
+    println!("{}: {}\n  - {}", record.action, status, style(explorer_url(&record.signature)).dim());
+
+    if let ConfirmationStatus::Failed { logs, .. } = &record.status {
+        for log in logs {
+            println!("    {}", style(log).dim());
+        }
+    }
+}
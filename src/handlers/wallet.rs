@@ -1,13 +1,28 @@
 use console::style;
 use dialoguer::theme::ColorfulTheme;
+use crate::chain::analysis::PUMPFUN_DECIMALS;
+use crate::chain::token::get_owned_token_accounts;
+use crate::chain::token::get_token_account;
+use crate::chain::token::get_token_program;
+use crate::chain::token::token_account_rent;
+use crate::chain::token::OwnedTokenAccount;
+use crate::chain::token::TokenBalance;
+use crate::chain::get_transfer_fee;
 use crate::dialogue::loader::Loader;
+use crate::paper::dispatch;
+use crate::storage::risk::Spend;
+use crate::storage::risk::SpendKind;
+use crate::storage::transactions::TransactionRecord;
+use moonbois_core::ProjectDTO;
 use moonbois_core::WalletDTO;
+use solana_client::client_error::ClientError;
 use solana_sdk::native_token::LAMPORTS_PER_SOL;
 use std::sync::Arc;
 
 use dialoguer::Confirm;
 use dialoguer::FuzzySelect;
 use dialoguer::Input;
+use dialoguer::MultiSelect;
 use solana_sdk::pubkey::Pubkey;
 use solana_sdk::signature::Keypair;
 
@@ -15,13 +30,22 @@ use crate::AppData;
 use crate::AppError;
 use crate::Menu;
 
+use super::address_book::confirm_transfer;
+use super::address_book::select_recipient;
 use super::main::MainMenu;
+use super::project::select_project;
+use super::risk::check_spend;
+use super::transactions::confirm_transactions;
+use super::transactions::report_transactions;
 use super::Handler;
 
+static CLOSE_ACCOUNTS_BATCH_SIZE: usize = 10;
+
 pub enum WalletMenuOptions {
     Withdraw,
     Deposit,
     Send,
+    SendTokens,
     Delete,
     Back
 }
@@ -32,6 +56,7 @@ impl ToString for WalletMenuOptions {
             Self::Withdraw => "Withdraw".to_string(),
             Self::Deposit => "Deposit".to_string(),
             Self::Send => "Send".to_string(),
+            Self::SendTokens => "SendTokens".to_string(),
             Self::Delete => "Delete".to_string(),
             Self::Back => format!("{}", "Back"),
         }
@@ -44,8 +69,9 @@ impl From<usize> for WalletMenuOptions {
             0 => Self::Withdraw,
             1 => Self::Deposit,
             2 => Self::Send,
-            3 => Self::Delete,
-            4 => Self::Back,
+            3 => Self::SendTokens,
+            4 => Self::Delete,
+            5 => Self::Back,
             _ => panic!("Received invalid main menu index")
         }
     }
@@ -60,6 +86,7 @@ impl Handler for WalletMenu {
                     WalletMenuOptions::Withdraw,
                     WalletMenuOptions::Deposit,
                     WalletMenuOptions::Send,
+                    WalletMenuOptions::SendTokens,
                     WalletMenuOptions::Delete,
                     WalletMenuOptions::Back
                 ]).interact() {
@@ -73,6 +100,7 @@ impl Handler for WalletMenu {
                     WalletMenuOptions::Withdraw => return Ok(Some(Menu::Withdraw(Withdraw { wallet }))),
                     WalletMenuOptions::Deposit => return Ok(Some(Menu::Deposit(Deposit { wallet }))),
                     WalletMenuOptions::Send => return Ok(Some(Menu::Send(SendSOL { wallet }))),
+                    WalletMenuOptions::SendTokens => return Ok(Some(Menu::SendTokens(SendTokens { wallet }))),
                     WalletMenuOptions::Delete => return Ok(Some(Menu::DeleteWallet(DeleteWallet { wallet }))),
                     WalletMenuOptions::Back => return Ok(Some(Menu::Main(MainMenu)))
                 }
@@ -85,6 +113,9 @@ impl Handler for WalletMenu {
 pub struct ImportWallet;
 impl Handler for ImportWallet {
     async fn handle(&self, app_data: &Arc<AppData>) -> Result<Option<Menu>, (Menu, AppError)> {
+        dispatch::live_only(app_data, "ImportWallet")
+            .map_err(|err| (Menu::Main(MainMenu), err))?;
+
         let private_key: String = match Input::with_theme(&ColorfulTheme::default())
             .with_prompt("Enter the private key to import")
             .interact_text()
@@ -118,18 +149,98 @@ impl Handler for ImportWallet {
     }
 }
 
+pub enum DeleteWalletOptions {
+    SweepAndDelete,
+    DeleteAnyway,
+    Cancel
+}
+
+impl ToString for DeleteWalletOptions {
+    fn to_string(&self) -> String {
+        match self {
+            Self::SweepAndDelete => "SweepAndDelete".to_string(),
+            Self::DeleteAnyway => "DeleteAnyway".to_string(),
+            Self::Cancel => "Cancel".to_string()
+        }
+    }
+}
+
+impl From<usize> for DeleteWalletOptions {
+    fn from(value: usize) -> Self {
+        match value {
+            0 => Self::SweepAndDelete,
+            1 => Self::DeleteAnyway,
+            2 => Self::Cancel,
+            _ => panic!("Received invalid delete wallet index")
+        }
+    }
+}
+
 pub struct DeleteWallet {
     pub wallet: WalletDTO 
 }
 impl Handler for DeleteWallet {
     async fn handle(&self, app_data: &Arc<AppData>) -> Result<Option<Menu>, (Menu, AppError)> {
-        let delete = Confirm::with_theme(&ColorfulTheme::default())
-            .with_prompt("Are you sure you want to delete this wallet?")
-            .default(false)
-            .interact()
-            .unwrap();
+        dispatch::live_only(app_data, "DeleteWallet")
+            .map_err(|err| (Menu::Wallet(WalletMenu), err))?;
+
+        let (mut sol_balance, mut token_accounts) = load_wallet_funds(app_data, &self.wallet).await
+            .map_err(|err| (Menu::Wallet(WalletMenu), err))?;
+
+        // Lamports that may stay behind, a sweep cannot move the fee of its own transfer
+        let mut dust_lamports = 0;
+        while sol_balance > dust_lamports || !token_accounts.is_empty() {
+            if dust_lamports == 0 {
+                println!("{}", style("This wallet still holds funds, deleting it can strand them ⚠️").yellow());
+            } else {
+                println!("{}", style("The wallet still holds funds after the sweep ⚠️").yellow());
+            }
+            print_wallet_funds(app_data, sol_balance, &token_accounts).await;
+
+            let selection = FuzzySelect::with_theme(&ColorfulTheme::default())
+                .with_prompt("Delete wallet")
+                .default(0)
+                .items(&[
+                    DeleteWalletOptions::SweepAndDelete,
+                    DeleteWalletOptions::DeleteAnyway,
+                    DeleteWalletOptions::Cancel
+                ])
+                .interact()
+                .map_err(|err| (Menu::Wallet(WalletMenu), AppError::from(err)))?;
+
+            match DeleteWalletOptions::from(selection) {
+                DeleteWalletOptions::SweepAndDelete => {
+                    sweep_wallet(app_data, &self.wallet, token_accounts).await
+                        .map_err(|err| (Menu::Wallet(WalletMenu), err))?;
+
+                    (sol_balance, token_accounts) = load_wallet_funds(app_data, &self.wallet).await
+                        .map_err(|err| (Menu::Wallet(WalletMenu), err))?;
+
+                    dust_lamports = dispatch::transfer_fee(app_data, &self.wallet.public_key).await
+                        .map_err(|err| (Menu::Wallet(WalletMenu), err))?;
+                },
+                DeleteWalletOptions::DeleteAnyway => break,
+                DeleteWalletOptions::Cancel => return Ok(Some(Menu::Wallet(WalletMenu)))
+            }
+        }
+
+        let prefix = self.wallet.public_key.to_string()[0..5].to_string();
+        let confirmation: String = match Input::with_theme(&ColorfulTheme::default())
+            .with_prompt(format!("Type {} to delete this wallet", prefix))
+            .interact_text()
+            .unwrap() {
+                Some(value) => value,
+                None => return Ok(Some(Menu::Wallet(WalletMenu)))
+            };
+
+        if confirmation.trim() != prefix {
+            println!("{}", style("Confirmation did not match, the wallet was not deleted").yellow());
+            FuzzySelect::with_theme(&ColorfulTheme::default())
+                .item("Back")
+                .default(0)
+                .interact()
+                .unwrap();
 
-        if !delete {
             return Ok(Some(Menu::Wallet(WalletMenu)));
         }
 
@@ -153,11 +264,226 @@ impl Handler for DeleteWallet {
     }
 }
 
+/// On-chain SOL balance and every non-empty token account of the wallet.
+async fn load_wallet_funds(app_data: &Arc<AppData>, wallet: &WalletDTO) -> Result<(u64, Vec<OwnedTokenAccount>), AppError> {
+    let result = Loader::new()
+        .with_prompt("checking wallet funds")
+        .interact(async {
+            let sol_balance = app_data.solana_client.get_balance(&wallet.public_key).await?;
+            let token_accounts: Vec<OwnedTokenAccount> = get_owned_token_accounts(&app_data.solana_client, &wallet.public_key).await?
+                .into_iter()
+                .filter(|account| account.amount > 0)
+                .collect();
+            Ok::<_, ClientError>((sol_balance, token_accounts))
+        })
+        .await?;
+
+    Ok(result)
+}
+
+async fn print_wallet_funds(app_data: &Arc<AppData>, sol_balance: u64, token_accounts: &[OwnedTokenAccount]) {
+    let projects = app_data.projects.read().await;
+    println!("  - {} {}", sol_balance as f64 / LAMPORTS_PER_SOL as f64, style("SOL").cyan());
+    for account in token_accounts {
+        let name = projects.values()
+            .find(|project| project.pumpfun.mint_id == account.mint_id)
+            .map(|project| project.name.to_uppercase())
+            .unwrap_or(account.mint_id.to_string());
+        println!("  - {} {}", account.amount as f64 / 10f64.powi(account.decimals as i32), style(name).magenta());
+    }
+}
+
+/// Empties a wallet ahead of deletion: tokens are sold or moved to fee_payer, the emptied token
+/// accounts are closed and the remaining SOL is withdrawn to fee_payer. Each step is confirmed
+/// before the next one starts, and when a step fails the transactions already sent are still
+/// confirmed and recorded before the error is returned. The outcome is shown once at the end.
+async fn sweep_wallet(app_data: &Arc<AppData>, wallet: &WalletDTO, token_accounts: Vec<OwnedTokenAccount>) -> Result<(), AppError> {
+    let mut settled = vec![];
+    let mut pending = vec![];
+    let result = send_sweep(app_data, wallet, token_accounts, &mut settled, &mut pending).await;
+
+    if !pending.is_empty() {
+        settled.extend(confirm_transactions(app_data, pending).await);
+    }
+    if !settled.is_empty() {
+        let confirmed = settled.iter().filter(|record| record.is_confirmed()).count();
+        println!("{}", style(format!("{} of {} sweep transactions confirmed", confirmed, settled.len())).dim());
+        FuzzySelect::with_theme(&ColorfulTheme::default())
+            .item("Back")
+            .default(0)
+            .interact()
+            .unwrap();
+    }
+
+    result
+}
+
+/// The steps of `sweep_wallet`. Sent transactions go into `pending` and move to `settled` once
+/// confirmed at the end of their step.
+async fn send_sweep(
+    app_data: &Arc<AppData>,
+    wallet: &WalletDTO,
+    token_accounts: Vec<OwnedTokenAccount>,
+    settled: &mut Vec<TransactionRecord>,
+    pending: &mut Vec<TransactionRecord>
+) -> Result<(), AppError> {
+    let fee_payer = match &app_data.user.read().await.0 {
+        Some(user) => user.public_key,
+        None => return Err(AppError::UserNotFound)
+    };
+    let projects: Vec<ProjectDTO> = app_data.projects.read().await.values().cloned().collect();
+
+    for account in token_accounts.iter() {
+        let project = projects.iter().find(|project| project.pumpfun.mint_id == account.mint_id);
+        let label = project.map(|project| project.name.to_uppercase()).unwrap_or(account.mint_id.to_string());
+
+        let mut items = vec!["TransferToFeePayer"];
+        if project.is_some() {
+            items.insert(0, "Sell");
+        }
+        items.push("Skip");
+
+        let selection = FuzzySelect::with_theme(&ColorfulTheme::default())
+            .with_prompt(format!("{} {}", account.amount as f64 / 10f64.powi(account.decimals as i32), label))
+            .default(0)
+            .items(&items)
+            .interact()?;
+
+        let rpc_client = app_data.rpc_client.read().await;
+        let record = match (items[selection], project) {
+            ("Sell", Some(project)) => Loader::new()
+                .with_prompt("sell in progress")
+                .interact(rpc_client.sell(project.id, wallet.id))
+                .await
+                .map(|signature| TransactionRecord::new("sell", signature).with_project(project.id).with_wallet(wallet.public_key)),
+            ("TransferToFeePayer", _) => Loader::new()
+                .with_prompt("send_tokens in progress")
+                .interact(rpc_client.transfer_tokens_from_sniper(wallet.id, account.mint_id, fee_payer, account.amount))
+                .await
+                .map(|signature| TransactionRecord::new("send_tokens", signature).with_wallet(wallet.public_key)),
+            _ => continue
+        };
+        drop(rpc_client);
+
+        pending.push(record?);
+    }
+    if !pending.is_empty() {
+        settled.extend(confirm_transactions(app_data, std::mem::take(pending)).await);
+    }
+
+    let closable: Vec<Pubkey> = get_owned_token_accounts(&app_data.solana_client, &wallet.public_key).await?
+        .into_iter()
+        .filter(|account| account.amount == 0)
+        .map(|account| account.address)
+        .collect();
+
+    for batch in closable.chunks(CLOSE_ACCOUNTS_BATCH_SIZE) {
+        let rpc_client = app_data.rpc_client.read().await;
+        let signature = Loader::new()
+            .with_prompt("close_token_accounts in progress")
+            .interact(rpc_client.close_token_accounts(wallet.id, batch.to_vec()))
+            .await?;
+        drop(rpc_client);
+
+        pending.push(TransactionRecord::new("close_token_accounts", signature).with_wallet(wallet.public_key));
+    }
+    if !pending.is_empty() {
+        settled.extend(confirm_transactions(app_data, std::mem::take(pending)).await);
+    }
+
+    let fee = get_transfer_fee(&app_data.solana_client, &wallet.public_key).await?;
+    let lamports = app_data.solana_client.get_balance(&wallet.public_key).await?.saturating_sub(fee);
+    if lamports > 0 {
+        let rpc_client = app_data.rpc_client.read().await;
+        let signature = Loader::new()
+            .with_prompt("withdraw in progress")
+            .interact(rpc_client.transfer_sol_from_sniper(wallet.id, fee_payer, lamports))
+            .await?;
+        drop(rpc_client);
+
+        pending.push(
+            TransactionRecord::new("withdraw", signature)
+                .with_wallet(wallet.public_key)
+                .with_lamports(lamports)
+        );
+    }
+    if !pending.is_empty() {
+        settled.extend(confirm_transactions(app_data, std::mem::take(pending)).await);
+    }
+
+    Ok(())
+}
+
 pub struct RecoverSol;
 impl Handler for RecoverSol {
     async fn handle(&self, app_data: &Arc<AppData>) -> Result<Option<Menu>, (Menu, AppError)> {
+        let (fee_payer, mut wallets): (Pubkey, Vec<WalletDTO>) = match &app_data.user.read().await.0 {
+            Some(user) => (user.public_key, user.wallets.values().cloned().collect()),
+            None => return Err((Menu::Main(MainMenu), AppError::UserNotFound))
+        };
+        wallets.sort_by(|a, b| b.sol_balance.cmp(&a.sol_balance));
+
+        // Every sniper signs the same single transfer, so one quote covers all of them
+        let fee = match wallets.first() {
+            Some(wallet) => Loader::new()
+                .with_prompt("quoting transfer fee")
+                .interact(dispatch::transfer_fee(app_data, &wallet.public_key))
+                .await
+                .map_err(|err| (Menu::Main(MainMenu), err))?,
+            None => 0
+        };
+
+        println!("{:<8} {:>16} {:>16} {:>14}", "wallet", "balance", "recover", "fee");
+        for wallet in wallets.iter() {
+            println!(
+                "{:<8} {:>16} {:>16} {:>14}",
+                &wallet.public_key.to_string()[0..5],
+                format!("{} SOL", wallet.sol_balance as f64 / LAMPORTS_PER_SOL as f64),
+                format!("{} SOL", recoverable_lamports(wallet, fee) as f64 / LAMPORTS_PER_SOL as f64),
+                format!("{} SOL", fee as f64 / LAMPORTS_PER_SOL as f64)
+            );
+        }
+        println!("");
+
+        let wallets: Vec<WalletDTO> = wallets.into_iter()
+            .filter(|wallet| recoverable_lamports(wallet, fee) > 0)
+            .collect();
+
+        if wallets.is_empty() {
+            println!("{}", style("No sniper holds enough SOL to cover the transfer fee").dim());
+            FuzzySelect::with_theme(&ColorfulTheme::default())
+                .item("Back")
+                .default(0)
+                .interact()
+                .unwrap();
+
+            return Ok(Some(Menu::Main(MainMenu)))
+        }
+
+        let selection: Vec<String> = wallets.iter().map(|wallet| format!(
+            "{} {} {}",
+            &wallet.public_key.to_string()[0..5],
+            recoverable_lamports(wallet, fee) as f64 / LAMPORTS_PER_SOL as f64,
+            "SOL"
+        )).collect();
+
+        let selected = MultiSelect::with_theme(&ColorfulTheme::default())
+            .with_prompt("Select the wallets to recover")
+            .items(&selection)
+            .defaults(&vec![true; selection.len()])
+            .interact()
+            .map_err(|err| (Menu::Main(MainMenu), AppError::from(err)))?;
+
+        if selected.is_empty() {
+            return Ok(Some(Menu::Main(MainMenu)))
+        }
+
+        let total: u64 = selected.iter().map(|index| recoverable_lamports(&wallets[*index], fee)).sum();
         let confirm = Confirm::with_theme(&ColorfulTheme::default())
-            .with_prompt(format!("{}\nDo you want to continue?", style("This will send all the SOL in your snipers to fee_payer").yellow()))
+            .with_prompt(format!(
+                "{}\nDo you want to continue?",
+                style(format!("This will send about {} SOL from {} snipers to fee_payer", total as f64 / LAMPORTS_PER_SOL as f64, selected.len())).yellow()
+            ))
             .default(false)
             .interact()
             .unwrap();
@@ -165,16 +491,183 @@ impl Handler for RecoverSol {
         if !confirm {
             return Ok(Some(Menu::Main(MainMenu)))
         }
-        
-        let rpc_client = app_data.rpc_client.read().await;
-        let result = Loader::new()
-            .with_prompt("recover_sol in progress")
-            .interact(rpc_client.recover_sol())
-            .await;
-        drop(rpc_client);
 
-        if let Err(err) = result {
-            return Err((Menu::Main(MainMenu), AppError::from(err)))
+        let mut results = vec![];
+        for index in selected {
+            let wallet = &wallets[index];
+
+            // The synced balance can be stale, the amount is read again right before sending
+            let amount = match dispatch::sol_balance(app_data, &wallet.public_key).await {
+                Ok(balance) => balance.saturating_sub(fee),
+                Err(err) => {
+                    results.push((wallet, 0, Err(err)));
+                    continue;
+                }
+            };
+            if amount == 0 {
+                results.push((wallet, 0, Err(AppError::Unhandled("balance no longer covers the transfer fee".to_string()))));
+                continue;
+            }
+
+            let result = Loader::new()
+                .with_prompt("recover_sol in progress")
+                .interact(dispatch::transfer_sol_from_sniper(app_data, wallet, fee_payer, amount))
+                .await;
+
+            results.push((wallet, amount, result));
+        }
+
+        println!("{:<8} {:>16} {:<}", "wallet", "amount", "result");
+        for (wallet, amount, result) in results.iter() {
+            println!(
+                "{:<8} {:>16} {}",
+                &wallet.public_key.to_string()[0..5],
+                format!("{} SOL", *amount as f64 / LAMPORTS_PER_SOL as f64),
+                match result {
+                    Ok(_) => style("success".to_string()).green(),
+                    Err(err) => style(format!("failed - {}", err)).red()
+                }
+            );
+        }
+
+        let records: Vec<TransactionRecord> = results.into_iter()
+            .filter_map(|(wallet, amount, result)| result.ok().map(|signature| {
+                TransactionRecord::new("recover_sol", signature)
+                    .with_wallet(wallet.public_key)
+                    .with_lamports(amount)
+            }))
+            .collect();
+
+        if records.is_empty() {
+            FuzzySelect::with_theme(&ColorfulTheme::default())
+                .item("Back")
+                .default(0)
+                .interact()
+                .unwrap();
+        } else {
+            report_transactions(app_data, records).await;
+        }
+
+        Ok(Some(Menu::Main(MainMenu)))
+    }
+}
+
+/// Lamports a sniper can send to fee_payer after paying `fee` for its own transfer, from the synced
+/// balance. Only used to pick wallets, the amount sent is read from the chain.
+fn recoverable_lamports(wallet: &WalletDTO, fee: u64) -> u64 {
+    wallet.sol_balance.saturating_sub(fee)
+}
+
+pub struct ReclaimRent;
+impl Handler for ReclaimRent {
+    async fn handle(&self, app_data: &Arc<AppData>) -> Result<Option<Menu>, (Menu, AppError)> {
+        dispatch::live_only(app_data, "ReclaimRent")
+            .map_err(|err| (Menu::Main(MainMenu), err))?;
+
+        let wallets: Vec<WalletDTO> = match &app_data.user.read().await.0 {
+            Some(user) => user.wallets.values().cloned().collect(),
+            None => return Err((Menu::Main(MainMenu), AppError::UserNotFound))
+        };
+
+        let empty_accounts = Loader::new()
+            .with_prompt("scanning token accounts")
+            .interact(async {
+                let mut empty_accounts = vec![];
+                for wallet in wallets {
+                    let accounts: Vec<OwnedTokenAccount> = get_owned_token_accounts(&app_data.solana_client, &wallet.public_key).await?
+                        .into_iter()
+                        .filter(|account| account.amount == 0)
+                        .collect();
+
+                    if !accounts.is_empty() {
+                        empty_accounts.push((wallet, accounts));
+                    }
+                }
+                Ok::<_, ClientError>(empty_accounts)
+            })
+            .await
+            .map_err(|err| (Menu::Main(MainMenu), AppError::from(err)))?;
+
+        if empty_accounts.is_empty() {
+            println!("{}", style("No empty token accounts found").dim());
+            FuzzySelect::with_theme(&ColorfulTheme::default())
+                .item("Back")
+                .default(0)
+                .interact()
+                .unwrap();
+
+            return Ok(Some(Menu::Main(MainMenu)))
+        }
+
+        println!("{:<8} {:>10} {:>16}", "wallet", "accounts", "recoverable");
+        let mut total_accounts = 0;
+        let mut total_lamports = 0;
+        for (wallet, accounts) in empty_accounts.iter() {
+            let lamports: u64 = accounts.iter().map(|account| account.lamports).sum();
+            println!(
+                "{:<8} {:>10} {:>16}",
+                &wallet.public_key.to_string()[0..5],
+                accounts.len(),
+                format!("{} SOL", lamports as f64 / LAMPORTS_PER_SOL as f64)
+            );
+            total_accounts += accounts.len();
+            total_lamports += lamports;
+        }
+        println!("{:<8} {:>10} {:>16}\n", style("total").bold(), total_accounts, format!("{} SOL", total_lamports as f64 / LAMPORTS_PER_SOL as f64));
+
+        let confirm = Confirm::with_theme(&ColorfulTheme::default())
+            .with_prompt(format!("Close {} token accounts and reclaim their rent?", total_accounts))
+            .default(false)
+            .interact()
+            .unwrap();
+
+        if !confirm {
+            return Ok(Some(Menu::Main(MainMenu)))
+        }
+
+        let mut closed_accounts = 0;
+        let mut records = vec![];
+        let mut failures = vec![];
+        for (wallet, accounts) in empty_accounts.iter() {
+            let batch_count = accounts.len().div_ceil(CLOSE_ACCOUNTS_BATCH_SIZE);
+            for (index, batch) in accounts.chunks(CLOSE_ACCOUNTS_BATCH_SIZE).enumerate() {
+                let addresses: Vec<Pubkey> = batch.iter().map(|account| account.address).collect();
+                let prompt = format!("close_token_accounts in progress {} ({}/{})", &wallet.public_key.to_string()[0..5], index + 1, batch_count);
+
+                let rpc_client = app_data.rpc_client.read().await;
+                let result = Loader::new()
+                    .with_prompt(&prompt)
+                    .interact(rpc_client.close_token_accounts(wallet.id, addresses))
+                    .await;
+                drop(rpc_client);
+
+                match result {
+                    Ok(signature) => {
+                        closed_accounts += batch.len();
+                        records.push(TransactionRecord::new("close_token_accounts", signature).with_wallet(wallet.public_key));
+                    },
+                    Err(err) => failures.push(format!("{} batch {}: {}", &wallet.public_key.to_string()[0..5], index + 1, err))
+                }
+            }
+        }
+
+        println!("closed {} of {} token accounts", closed_accounts, total_accounts);
+        for failure in failures.iter() {
+            println!("  - {}", style(failure).dim());
+        }
+
+        if !records.is_empty() {
+            report_transactions(app_data, records).await;
+        }
+
+        let recover = Confirm::with_theme(&ColorfulTheme::default())
+            .with_prompt("Recover the reclaimed SOL to fee_payer?")
+            .default(false)
+            .interact()
+            .unwrap();
+
+        if recover {
+            return Ok(Some(Menu::RecoverSol(RecoverSol)))
         }
 
         Ok(Some(Menu::Main(MainMenu)))
@@ -234,16 +727,24 @@ impl Handler for Withdraw {
             None => return Err((Menu::Main(MainMenu), AppError::UserNotFound))
         };
 
-        let rpc_client = app_data.rpc_client.read().await;
+        check_spend(app_data, Spend { kind: SpendKind::Withdraw, lamports: amount as u64, wallets: vec![] }).await
+            .map_err(|err| (Menu::Wallet(WalletMenu), err))?;
+
         let result = Loader::new()
             .with_prompt("withdraw in progress")
-            .interact( rpc_client.transfer_sol_from_sniper(self.wallet.id, receiver, amount as u64))
+            .interact(dispatch::transfer_sol_from_sniper(app_data, &self.wallet, receiver, amount as u64))
             .await;
-        drop(rpc_client);
 
-        if let Err(err) = result {
-            return Err((Menu::Main(MainMenu), AppError::from(err)))
-        }
+        let signature = match result {
+            Ok(signature) => signature,
+            Err(err) => return Err((Menu::Main(MainMenu), AppError::from(err)))
+        };
+
+        report_transactions(app_data, vec![
+            TransactionRecord::new("withdraw", signature)
+                .with_wallet(self.wallet.public_key)
+                .with_lamports(amount as u64)
+        ]).await;
 
         Ok(Some(Menu::Main(MainMenu)))
     }
@@ -263,16 +764,25 @@ impl Handler for Deposit {
             };
 
         let amount = amount * LAMPORTS_PER_SOL as f64;
-        let rpc_client = app_data.rpc_client.read().await;
+
+        check_spend(app_data, Spend { kind: SpendKind::Deposit, lamports: amount as u64, wallets: vec![] }).await
+            .map_err(|err| (Menu::Wallet(WalletMenu), err))?;
+
         let result = Loader::new()
             .with_prompt("deposit in progress")
-            .interact(rpc_client.transfer_sol_from_main(self.wallet.public_key, amount as u64))
+            .interact(dispatch::transfer_sol_from_main(app_data, self.wallet.public_key, amount as u64))
             .await;
-        drop(rpc_client);
 
-        if let Err(err) = result {
-            return Err((Menu::Main(MainMenu), AppError::from(err)))
-        }
+        let signature = match result {
+            Ok(signature) => signature,
+            Err(err) => return Err((Menu::Main(MainMenu), AppError::from(err)))
+        };
+
+        report_transactions(app_data, vec![
+            TransactionRecord::new("deposit", signature)
+                .with_wallet(self.wallet.public_key)
+                .with_lamports(amount as u64)
+        ]).await;
 
         Ok(Some(Menu::Main(MainMenu)))
     }
@@ -283,11 +793,9 @@ pub struct SendSOL {
 }
 impl Handler for SendSOL {
     async fn handle(&self, app_data: &Arc<AppData>) -> Result<Option<Menu>, (Menu, AppError)> {
-        let receiver: Pubkey = match Input::with_theme(&ColorfulTheme::default())
-            .with_prompt("Enter the receiver address")
-            .interact_text()
-            .unwrap() {
-                Some(value) => value,
+        let receiver = match select_recipient()
+            .map_err(|err| (Menu::Wallet(WalletMenu), err))? {
+                Some(receiver) => receiver,
                 None => return Ok(Some(Menu::Wallet(WalletMenu)))
             };
 
@@ -300,17 +808,171 @@ impl Handler for SendSOL {
             };
         let amount = amount * LAMPORTS_PER_SOL as f64;
 
-        let rpc_client = app_data.rpc_client.read().await;
+        check_spend(app_data, Spend {
+            kind: SpendKind::Send,
+            lamports: amount as u64,
+            wallets: vec![(self.wallet.public_key, amount as u64)]
+        }).await.map_err(|err| (Menu::Wallet(WalletMenu), err))?;
+
+        if !confirm_transfer(&receiver, &format!("{} SOL", amount / LAMPORTS_PER_SOL as f64), Some(amount as u64))
+            .map_err(|err| (Menu::Wallet(WalletMenu), err))? {
+                return Ok(Some(Menu::Wallet(WalletMenu)))
+            }
+
         let result = Loader::new()
             .with_prompt("send in progress")
-            .interact(rpc_client.transfer_sol_from_sniper(self.wallet.id, receiver, amount as u64))
+            .interact(dispatch::transfer_sol_from_sniper(app_data, &self.wallet, receiver, amount as u64))
             .await;
-        drop(rpc_client);
 
-        if let Err(err) = result {
-            return Err((Menu::Main(MainMenu), AppError::from(err)))
+        let signature = match result {
+            Ok(signature) => signature,
+            Err(err) => return Err((Menu::Main(MainMenu), AppError::from(err)))
+        };
+
+        report_transactions(app_data, vec![
+            TransactionRecord::new("send", signature)
+                .with_wallet(self.wallet.public_key)
+                .with_lamports(amount as u64)
+        ]).await;
+
+        Ok(Some(Menu::Main(MainMenu)))
+    }
+}
+
+pub struct SendTokens {
+    pub wallet: WalletDTO
+}
+impl Handler for SendTokens {
+    async fn handle(&self, app_data: &Arc<AppData>) -> Result<Option<Menu>, (Menu, AppError)> {
+        let project = match select_project(app_data).await
+            .map_err(|err| (Menu::Wallet(WalletMenu), err))? {
+                Some(project) => project,
+                None => return Ok(Some(Menu::Wallet(WalletMenu)))
+            };
+        let mint_id = project.pumpfun.mint_id;
+
+        // Paper tokens only exist in the ledger, there are no token accounts to look up
+        let (token_program, balance) = match &app_data.paper {
+            Some(paper) => {
+                let amount = paper.read().await.wallet(&self.wallet.public_key)
+                    .map(|wallet| wallet.token_balance(&mint_id))
+                    .unwrap_or(0);
+                (None, Some(TokenBalance { amount, decimals: PUMPFUN_DECIMALS }))
+            },
+            None => {
+                let (token_program, sender_account) = Loader::new()
+                    .with_prompt("loading token balance")
+                    .interact(async {
+                        let token_program = get_token_program(&app_data.solana_client, &mint_id).await?;
+                        let sender_account = get_token_account(&app_data.solana_client, &self.wallet.public_key, &mint_id, &token_program).await?;
+                        Ok::<_, ClientError>((token_program, sender_account))
+                    })
+                    .await
+                    .map_err(|err| (Menu::Wallet(WalletMenu), AppError::SolanaClientError(err)))?;
+                (Some(token_program), sender_account.balance)
+            }
+        };
+
+        let balance = match balance {
+            Some(balance) if balance.amount > 0 => balance,
+            _ => return Err((Menu::Wallet(WalletMenu), AppError::Unhandled(format!("Wallet holds no {} tokens", project.name))))
+        };
+
+        let receiver = match select_recipient()
+            .map_err(|err| (Menu::Wallet(WalletMenu), err))? {
+                Some(receiver) => receiver,
+                None => return Ok(Some(Menu::Wallet(WalletMenu)))
+            };
+
+        let max_amount = balance.ui_amount();
+        let amount: f64 = match Input::with_theme(&ColorfulTheme::default())
+            .with_prompt(format!("Enter the token amount (max {})", max_amount))
+            .default(max_amount)
+            .validate_with(|val: &f64| -> Result<(), String> {
+                if *val <= 0f64 || *val > max_amount {
+                    return Err("Amount exceeds the wallet token balance".to_string())
+                }
+
+                Ok(())
+            })
+            .interact_text()
+            .unwrap() {
+                Some(value) => value,
+                None => return Ok(Some(Menu::Wallet(WalletMenu)))
+            };
+        let amount = ((amount * 10f64.powi(balance.decimals as i32)) as u64).min(balance.amount);
+
+        // The sniper pays the rent of a receiver account that does not exist yet, refuse the send
+        // up front when it cannot, and check afterwards that the account was opened
+        let mut missing_account = None;
+        if let Some(token_program) = token_program {
+            let (receiver_account, rent, fee, sol_balance) = Loader::new()
+                .with_prompt("loading receiver account")
+                .interact(async {
+                    let receiver_account = get_token_account(&app_data.solana_client, &receiver, &mint_id, &token_program).await?;
+                    let rent = token_account_rent(&app_data.solana_client, &token_program).await?;
+                    let sol_balance = app_data.solana_client.get_balance(&self.wallet.public_key).await?;
+                    let fee = get_transfer_fee(&app_data.solana_client, &self.wallet.public_key).await?;
+                    Ok::<_, ClientError>((receiver_account, rent, fee, sol_balance))
+                })
+                .await
+                .map_err(|err| (Menu::Wallet(WalletMenu), AppError::SolanaClientError(err)))?;
+
+            if receiver_account.balance.is_none() {
+                if sol_balance < rent + fee {
+                    return Err((Menu::Wallet(WalletMenu), AppError::Unhandled(format!(
+                        "{} has no {} token account and the wallet cannot pay the {} SOL to open one",
+                        receiver, project.name, (rent + fee) as f64 / LAMPORTS_PER_SOL as f64
+                    ))))
+                }
+
+                println!(
+                    "{}",
+                    style(format!(
+                        "{} has no {} token account, it will be created and the {} SOL rent paid by the sender",
+                        receiver, project.name, rent as f64 / LAMPORTS_PER_SOL as f64
+                    )).yellow()
+                );
+                missing_account = Some(token_program);
+            }
         }
 
+        let display_amount = format!("{} {}", amount as f64 / 10f64.powi(balance.decimals as i32), project.name.to_uppercase());
+        if !confirm_transfer(&receiver, &display_amount, None)
+            .map_err(|err| (Menu::Wallet(WalletMenu), err))? {
+                return Ok(Some(Menu::Wallet(WalletMenu)))
+            }
+
+        let result = Loader::new()
+            .with_prompt("send_tokens in progress")
+            .interact(dispatch::transfer_tokens_from_sniper(app_data, &self.wallet, mint_id, receiver, amount))
+            .await;
+
+        let signature = match result {
+            Ok(signature) => signature,
+            Err(err) => return Err((Menu::Wallet(WalletMenu), AppError::from(err)))
+        };
+
+        let records = confirm_transactions(app_data, vec![
+            TransactionRecord::new("send_tokens", signature)
+                .with_project(project.id)
+                .with_wallet(self.wallet.public_key)
+        ]).await;
+
+        if let Some(token_program) = missing_account.filter(|_| records.iter().all(|record| record.is_confirmed())) {
+            match get_token_account(&app_data.solana_client, &receiver, &mint_id, &token_program).await {
+                Ok(account) if account.balance.is_some() => println!("{}", style(format!("Created the {} token account of {}", project.name, receiver)).green()),
+                Ok(_) => println!("{}", style(format!("The transfer confirmed but {} still has no {} token account ⚠️", receiver, project.name)).yellow()),
+                Err(err) => println!("{}", style(format!("Unable to check the {} token account of {} - {}", project.name, receiver, err)).yellow())
+            }
+        }
+
+        FuzzySelect::with_theme(&ColorfulTheme::default())
+            .item("Back")
+            .default(0)
+            .interact()
+            .unwrap();
+
         Ok(Some(Menu::Main(MainMenu)))
     }
-}
\ No newline at end of file
+}
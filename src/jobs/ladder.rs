@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use solana_sdk::pubkey::Pubkey;
+use tokio::time::sleep;
+
+use crate::paper::dispatch;
+use crate::storage::ladders::load_ladders;
+use crate::storage::ladders::save_ladder;
+use crate::storage::ladders::LadderStatus;
+use crate::storage::ladders::SellLadder;
+use crate::storage::ladders::TrancheOutcome;
+use crate::storage::ladders::TrancheTrigger;
+use crate::storage::transactions::unix_timestamp;
+use crate::AppData;
+
+use super::sell::sell_share;
+
+static LADDER_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Sell ladders of this session keyed by project. Pausing and cancelling only change the status,
+/// the job picks it up on its next poll so a tranche is never cut off mid-sell.
+pub struct SellLadders(pub HashMap<i32, SellLadder>);
+
+impl SellLadders {
+    pub fn active(&self) -> Vec<&SellLadder> {
+        let mut ladders: Vec<&SellLadder> = self.0.values().filter(|ladder| !ladder.is_finished()).collect();
+        ladders.sort_by_key(|ladder| ladder.created_at);
+        ladders
+    }
+}
+
+/// Updates the status of a ladder in memory and on disk.
+pub async fn set_ladder_status(app_data: &Arc<AppData>, project_id: i32, status: LadderStatus) {
+    let mut ladders = app_data.sell_ladders.write().await;
+    if let Some(ladder) = ladders.0.get_mut(&project_id) {
+        ladder.status = status;
+        if let Err(err) = save_ladder(ladder) {
+            app_data.notifications.write().await.push(format!("Unable to persist the sell ladder - {}", err));
+        }
+    }
+}
+
+/// Runs the tranches of a ladder in the background, one at a time. A failed tranche pauses the
+/// ladder and is retried once it is resumed.
+pub async fn spawn_ladder_job(app_data: &Arc<AppData>, ladder: SellLadder) {
+    let project_id = ladder.project_id;
+    let created_at = ladder.created_at;
+    if let Err(err) = save_ladder(&ladder) {
+        app_data.notifications.write().await.push(format!("Unable to persist the sell ladder - {}", err));
+    }
+    app_data.sell_ladders.write().await.0.insert(project_id, ladder);
+
+    let app_data = Arc::clone(app_data);
+    tokio::spawn(async move {
+        loop {
+            sleep(LADDER_POLL_INTERVAL).await;
+
+            // A newer ladder on the same project has its own job
+            let ladder = match app_data.sell_ladders.read().await.0.get(&project_id) {
+                Some(ladder) if ladder.is_finished() || ladder.created_at != created_at => return,
+                Some(ladder) if ladder.status == LadderStatus::Paused => continue,
+                Some(ladder) => ladder.clone(),
+                None => return
+            };
+
+            let Ok(mint) = Pubkey::from_str(&ladder.mint) else { return };
+            let Some(index) = ladder.next_tranche() else {
+                set_ladder_status(&app_data, project_id, LadderStatus::Completed).await;
+                return
+            };
+
+            let price = match ladder.tranches[index].trigger {
+                TrancheTrigger::Price { .. } => match dispatch::bonding_curve(&app_data, &mint).await {
+                    Ok(Some(curve)) if !curve.complete => Some(curve.price()),
+                    _ => None
+                },
+                TrancheTrigger::Delay { .. } => None
+            };
+
+            if !ladder.is_due(index, unix_timestamp(), price) {
+                continue;
+            }
+
+            // A trade rule is selling the project, the tranche is retried on the next poll
+            if !app_data.selling.write().await.claim(project_id) {
+                continue;
+            }
+
+            let outcome = match sell_share(&app_data, project_id, mint, ladder.sell_share(index)).await {
+                Ok((records, error)) => {
+                    let signatures = records.iter().map(|record| record.signature.clone()).collect();
+                    let error = error.or(records.iter()
+                        .find(|record| !record.succeeded())
+                        .map(|record| format!("{} failed", record.signature)));
+
+                    match error {
+                        Some(error) => TrancheOutcome::Failed { error, signatures },
+                        None => TrancheOutcome::Sold { signatures }
+                    }
+                },
+                Err(error) => TrancheOutcome::Failed { error, signatures: vec![] }
+            };
+            app_data.selling.write().await.release(project_id);
+
+            let name = app_data.projects.read().await.get(&project_id)
+                .map(|project| project.name.clone())
+                .unwrap_or(ladder.mint.clone());
+
+            let mut ladders = app_data.sell_ladders.write().await;
+            let Some(ladder) = ladders.0.get_mut(&project_id).filter(|ladder| ladder.created_at == created_at) else { return };
+            ladder.tranches[index].executed_at = Some(unix_timestamp());
+
+            let message = format!("Sell ladder on {} tranche {} {}", name, index + 1, outcome.to_string());
+            if matches!(outcome, TrancheOutcome::Failed { .. }) && ladder.status == LadderStatus::Running {
+                ladder.status = LadderStatus::Paused;
+            }
+            ladder.tranches[index].outcome = Some(outcome);
+            if ladder.next_tranche().is_none() {
+                ladder.status = LadderStatus::Completed;
+            }
+
+            let saved = save_ladder(ladder);
+            drop(ladders);
+
+            let mut notifications = app_data.notifications.write().await;
+            notifications.push(message);
+            if let Err(err) = saved {
+                notifications.push(format!("Unable to persist the sell ladder - {}", err));
+            }
+        }
+    });
+}
+
+/// Picks up the ladders left unfinished by a previous session. They come back paused so nothing
+/// sells until they are resumed.
+pub async fn restore_ladders(app_data: &Arc<AppData>) {
+    let ladders = match load_ladders() {
+        Ok(ladders) => ladders,
+        Err(err) => {
+            app_data.notifications.write().await.push(format!("Unable to load sell ladders - {}", err));
+            return
+        }
+    };
+
+    for mut ladder in ladders.into_iter().filter(|ladder| !ladder.is_finished()) {
+        if app_data.sell_ladders.read().await.0.contains_key(&ladder.project_id) {
+            continue;
+        }
+
+        ladder.status = LadderStatus::Paused;
+        app_data.notifications.write().await.push(format!(
+            "Restored a sell ladder on {} at {}/{} tranches, resume it from the project's Ladder menu",
+            ladder.mint,
+            ladder.sold_count(),
+            ladder.tranches.len()
+        ));
+        spawn_ladder_job(app_data, ladder).await;
+    }
+}
@@ -0,0 +1,16 @@
+use std::time::Duration;
+
+pub mod ladder;
+pub mod sell;
+pub mod snipe;
+pub mod trade_rules;
+
+pub fn format_age(age: Duration) -> String {
+    let seconds = age.as_secs();
+    match seconds {
+        0..=59 => format!("{}s", seconds),
+        60..=3599 => format!("{}m {}s", seconds / 60, seconds % 60),
+        3600..=86399 => format!("{}h {}m", seconds / 3600, (seconds % 3600) / 60),
+        _ => format!("{}d {}h", seconds / 86400, (seconds % 86400) / 3600)
+    }
+}
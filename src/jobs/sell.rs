@@ -0,0 +1,112 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use moonbois_core::WalletDTO;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::paper::dispatch;
+use crate::storage::trade_rules::plan_wallet_sells;
+use crate::storage::transactions::record_transactions;
+use crate::storage::transactions::TransactionRecord;
+use crate::AppData;
+
+/// Projects a background job is selling right now. Trade rules and sell ladders claim a project
+/// before selling and skip it while the other holds it, so two sells never split the same position.
+pub struct SellingProjects(pub HashSet<i32>);
+
+impl SellingProjects {
+    /// False while another job is selling the project.
+    pub fn claim(&mut self, project_id: i32) -> bool {
+        self.0.insert(project_id)
+    }
+
+    pub fn release(&mut self, project_id: i32) {
+        self.0.remove(&project_id);
+    }
+}
+
+/// Sells `share` of the current position as planned by `plan_wallet_sells`, or the whole project
+/// through `auto_sell` when every wallet is sold as it is. Token moves between snipers have to
+/// confirm before anything sells, so a move that did not land never turns into a larger sell. The
+/// transactions are tracked and recorded, a step failing part way through is returned next to the
+/// transactions that were already sent.
+pub async fn sell_share(app_data: &Arc<AppData>, project_id: i32, mint: Pubkey, share: f64) -> Result<(Vec<TransactionRecord>, Option<String>), String> {
+    let holdings = dispatch::token_balances(app_data, mint).await
+        .map_err(|err| err.to_string())?;
+    if holdings.is_empty() {
+        return Err("no wallet holds tokens".to_string())
+    }
+
+    let wallets: HashMap<String, WalletDTO> = match &app_data.user.read().await.0 {
+        Some(user) => user.wallets.clone(),
+        None => return Err("user not found".to_string())
+    };
+    let empty: Vec<String> = wallets.values()
+        .filter(|wallet| wallet.sol_balance > 0 && !holdings.iter().any(|(holder, _)| holder == &wallet.public_key.to_string()))
+        .map(|wallet| wallet.public_key.to_string())
+        .collect();
+
+    let Some(plan) = plan_wallet_sells(&holdings, &empty, share) else {
+        return Err("the position sits in a single sniper wallet and cannot be sold in part".to_string())
+    };
+
+    let mut records = vec![];
+    if plan.moves.is_empty() && plan.sells.len() == holdings.len() {
+        let signatures = dispatch::auto_sell(app_data, project_id).await
+            .map_err(|err| err.to_string())?;
+
+        records.extend(signatures.into_iter().map(|signature| {
+            TransactionRecord::new("auto_sell", signature).with_project(project_id)
+        }));
+        dispatch::settle(app_data, &mut records).await;
+        record_transactions(&records).map_err(|err| err.to_string())?;
+
+        return Ok((records, None))
+    }
+
+    let mut moves = vec![];
+    let mut error = None;
+    for token_move in plan.moves.iter() {
+        let (Some(from), Ok(to)) = (wallets.get(&token_move.from), token_move.to.parse::<Pubkey>()) else {
+            error = Some(format!("{} is not a sniper wallet", token_move.from));
+            break;
+        };
+
+        match dispatch::transfer_tokens_from_sniper(app_data, from, mint, to, token_move.amount).await {
+            Ok(signature) => moves.push(TransactionRecord::new("send_tokens", signature)
+                .with_project(project_id)
+                .with_wallet(from.public_key)),
+            Err(err) => {
+                error = Some(err.to_string());
+                break;
+            }
+        }
+    }
+    dispatch::settle(app_data, &mut moves).await;
+    if error.is_none() && moves.iter().any(|record| !record.is_confirmed()) {
+        error = Some("moving tokens between snipers did not confirm, nothing was sold".to_string());
+    }
+    records.extend(moves);
+
+    if error.is_none() {
+        let mut sells = vec![];
+        for wallet in plan.sells.iter().filter_map(|wallet| wallets.get(wallet)) {
+            match dispatch::sell(app_data, project_id, wallet).await {
+                Ok(signature) => sells.push(TransactionRecord::new("sell", signature)
+                    .with_project(project_id)
+                    .with_wallet(wallet.public_key)),
+                Err(err) => {
+                    error = Some(err.to_string());
+                    break;
+                }
+            }
+        }
+        dispatch::settle(app_data, &mut sells).await;
+        records.extend(sells);
+    }
+
+    record_transactions(&records).map_err(|err| err.to_string())?;
+
+    Ok((records, error))
+}
@@ -0,0 +1,238 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use moonbois_core::rpc::MoonboisClientError;
+use moonbois_core::ProjectDTO;
+use solana_sdk::pubkey::Pubkey;
+use tokio::task::JoinHandle;
+use tokio::select;
+use tokio::time::sleep;
+use tokio::time::sleep_until;
+use tokio::time::Instant;
+
+use crate::chain::confirm::find_snipe_buys;
+use crate::chain::confirm::ConfirmationStatus;
+use crate::paper::dispatch;
+use crate::storage::snipes::remove_snipe;
+use crate::storage::snipes::save_snipe;
+use crate::storage::snipes::SnipeLimits;
+use crate::storage::snipes::SnipeRecord;
+use crate::storage::transactions::record_transactions;
+use crate::storage::transactions::unix_timestamp;
+use crate::storage::transactions::TransactionRecord;
+use crate::AppData;
+use crate::AppError;
+
+static SNIPE_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+pub enum SnipeStatus {
+    Pending,
+    Filled(String),
+    Failed(String),
+    Expired,
+    Cancelled
+}
+
+impl ToString for SnipeStatus {
+    fn to_string(&self) -> String {
+        match self {
+            Self::Pending => "pending".to_string(),
+            Self::Filled(name) => format!("filled {}", name),
+            Self::Failed(reason) => format!("failed - {}", reason),
+            Self::Expired => "expired".to_string(),
+            Self::Cancelled => "cancelled".to_string()
+        }
+    }
+}
+
+pub struct SnipeJob {
+    pub deployer: Pubkey,
+    pub wallet_count: usize,
+    pub created_at: u64,
+    pub expires_at: Option<u64>,
+    pub status: SnipeStatus,
+    handle: JoinHandle<()>
+}
+
+impl SnipeJob {
+    pub fn abort(&mut self) {
+        self.handle.abort();
+        self.status = SnipeStatus::Cancelled;
+    }
+    pub fn age(&self) -> Duration {
+        Duration::from_secs(unix_timestamp().saturating_sub(self.created_at))
+    }
+}
+
+/// Snipes running in the background, keyed by deployer since the backend cancels them by deployer.
+pub struct SnipeJobs(pub HashMap<Pubkey, SnipeJob>);
+
+impl SnipeJobs {
+    pub fn pending_count(&self) -> usize {
+        self.0.values().filter(|job| matches!(job.status, SnipeStatus::Pending)).count()
+    }
+}
+
+/// Record of a snipe about to be armed. The deployer's existing projects are remembered so the
+/// sniped one can be told apart from them.
+pub async fn new_snipe_record(app_data: &AppData, deployer: Pubkey, wallet_count: usize, limits: &SnipeLimits) -> SnipeRecord {
+    let known_projects = app_data.projects.read().await.values()
+        .filter(|project| project.deployer == deployer)
+        .map(|project| project.id)
+        .collect();
+
+    SnipeRecord {
+        deployer,
+        wallet_count,
+        created_at: unix_timestamp(),
+        expires_at: limits.expiry.map(|expiry| unix_timestamp() + expiry),
+        max_lamports_per_wallet: limits.max_lamports_per_wallet,
+        max_total_lamports: limits.max_total_lamports,
+        known_projects
+    }
+}
+
+/// Waits on a pending snipe in the background. When it fills the project is added to `AppData.projects`
+/// and a notification is queued for the header, when it expires the snipe is cancelled on the backend.
+/// The snipe is persisted until it resolves so it survives a restart of the client.
+pub async fn spawn_snipe_job<E: ToString + Send + 'static>(
+    app_data: &Arc<AppData>,
+    record: SnipeRecord,
+    pending_snipe: impl Future<Output = Result<ProjectDTO, E>> + Send + 'static
+) {
+    let deployer = record.deployer;
+    if let Err(err) = save_snipe(&record) {
+        app_data.notifications.write().await.push(format!("Unable to persist the snipe on {} - {}", deployer, err));
+    }
+
+    let mut snipes = app_data.snipes.write().await;
+    let app_data_arc = Arc::clone(app_data);
+
+    let expires_at = record.expires_at;
+    let filled_record = record.clone();
+    let handle = tokio::spawn(async move {
+        let expiry = async {
+            match expires_at {
+                Some(expires_at) => sleep(Duration::from_secs(expires_at.saturating_sub(unix_timestamp()))).await,
+                None => std::future::pending::<()>().await
+            }
+        };
+
+        // Expiry goes first so a snipe that times out is cancelled here rather than reported as failed
+        let status = select! {
+            biased;
+            _ = expiry => {
+                match dispatch::cancel_snipe(&app_data_arc, &deployer).await {
+                    Ok(_) | Err(AppError::MoonboisClientError(MoonboisClientError::NotFound)) => {
+                        app_data_arc.notifications.write().await.push(format!("Snipe on {} expired and was cancelled", deployer));
+                        SnipeStatus::Expired
+                    },
+                    Err(err) => {
+                        // Keep the record so the snipe can still be cancelled from OrphanedSnipes after a restart
+                        app_data_arc.notifications.write().await.push(format!("Snipe on {} expired but cancel_snipe failed - {}", deployer, err));
+                        if let Some(job) = app_data_arc.snipes.write().await.0.get_mut(&deployer) {
+                            job.status = SnipeStatus::Failed(format!("cancel_snipe failed - {}", err));
+                        }
+                        return;
+                    }
+                }
+            },
+            result = pending_snipe => match result {
+                Ok(project) => {
+                    let name = project.name.clone();
+                    if app_data_arc.paper.is_none() {
+                        record_snipe_buys(&app_data_arc, &project, &filled_record).await;
+                    }
+                    app_data_arc.projects.write().await.insert(project.id, project);
+                    app_data_arc.notifications.write().await.push(format!("Snipe on {} filled, {} was added to your tokens", deployer, name));
+                    SnipeStatus::Filled(name)
+                },
+                Err(err) => {
+                    let err = err.to_string();
+                    app_data_arc.notifications.write().await.push(format!("Snipe on {} failed - {}", deployer, err));
+                    SnipeStatus::Failed(err)
+                }
+            }
+        };
+
+        let _ = remove_snipe(&deployer);
+
+        if let Some(job) = app_data_arc.snipes.write().await.0.get_mut(&deployer) {
+            job.status = status;
+        }
+    });
+
+    snipes.0.insert(deployer, SnipeJob {
+        deployer,
+        wallet_count: record.wallet_count,
+        created_at: record.created_at,
+        expires_at: record.expires_at,
+        status: SnipeStatus::Pending,
+        handle
+    });
+}
+
+/// Records the buys of a filled live snipe so they count towards the risk limits and the P&L, and
+/// checks them against the snipe's caps. A snipe that overspent is cancelled so the backend stops
+/// buying. Paper snipes are capped by the ledger and recorded by its sync when they launch.
+async fn record_snipe_buys(app_data: &AppData, project: &ProjectDTO, snipe: &SnipeRecord) {
+    let wallets: Vec<Pubkey> = match &app_data.user.read().await.0 {
+        Some(user) => user.wallets.values().map(|wallet| wallet.public_key).collect(),
+        None => return
+    };
+
+    let buys = find_snipe_buys(&app_data.solana_client, &project.pumpfun.mint_id, &wallets, snipe.created_at).await;
+    let spent: Vec<(Pubkey, u64)> = buys.iter().map(|(wallet, _, lamports)| (*wallet, *lamports)).collect();
+    if let Some(overspend) = snipe.overspend(&spent) {
+        let cancelled = match dispatch::cancel_snipe(app_data, &snipe.deployer).await {
+            Ok(_) | Err(AppError::MoonboisClientError(MoonboisClientError::NotFound)) => "it was cancelled".to_string(),
+            Err(err) => format!("cancel_snipe failed - {}", err)
+        };
+        app_data.notifications.write().await.push(format!("Snipe on {} went over its caps, {} ⚠️ - {}", snipe.deployer, cancelled, overspend));
+    }
+
+    let records: Vec<TransactionRecord> = buys.into_iter().map(|(wallet, signature, lamports)| {
+        let mut record = TransactionRecord::new("snipe", signature)
+            .with_project(project.id)
+            .with_wallet(wallet)
+            .with_lamports(lamports);
+        record.status = ConfirmationStatus::Confirmed;
+        record
+    }).collect();
+
+    if records.is_empty() {
+        app_data.notifications.write().await.push(format!("No buys found for the snipe on {}, it does not count towards the risk limits", project.deployer));
+    } else if let Err(err) = record_transactions(&records) {
+        app_data.notifications.write().await.push(format!("Unable to record the snipe buys on {} - {}", project.deployer, err));
+    }
+}
+
+/// Resolves once a project from the snipe's deployer shows up in the synced projects. Used for
+/// snipes armed in a previous session, whose `PendingSnipe` future was lost with the process. The
+/// backend has no snipe status to poll, so once `expires_at` passes the snipe is cancelled and the
+/// wait ends with an error instead of running forever.
+pub async fn wait_for_snipe(app_data: Arc<AppData>, record: SnipeRecord) -> Result<ProjectDTO, AppError> {
+    loop {
+        let project = app_data.projects.read().await.values()
+            .find(|project| project.deployer == record.deployer && !record.known_projects.contains(&project.id))
+            .cloned();
+
+        if let Some(project) = project {
+            return Ok(project)
+        }
+
+        if record.expires_at.is_some_and(|expires_at| unix_timestamp() >= expires_at) {
+            return match dispatch::cancel_snipe(&app_data, &record.deployer).await {
+                Ok(_) | Err(AppError::MoonboisClientError(MoonboisClientError::NotFound)) => {
+                    let _ = remove_snipe(&record.deployer);
+                    Err(AppError::Unhandled(format!("Snipe on {} expired and was cancelled", record.deployer)))
+                },
+                Err(err) => Err(AppError::Unhandled(format!("Snipe on {} expired but cancel_snipe failed - {}", record.deployer, err)))
+            }
+        }
+
+        sleep_until(Instant::now() + SNIPE_POLL_INTERVAL).await;
+    }
+}
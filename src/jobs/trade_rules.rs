@@ -0,0 +1,79 @@
+use std::str::FromStr;
+use std::sync::Arc;
+
+use solana_sdk::pubkey::Pubkey;
+
+use crate::paper::dispatch;
+use crate::storage::trade_rules::load_trade_rules;
+use crate::storage::trade_rules::log_rule_event;
+use crate::storage::trade_rules::mark_rules_fired;
+use crate::storage::trade_rules::ProjectRules;
+use crate::storage::trade_rules::RuleEvent;
+use crate::AppData;
+
+use super::sell::sell_share;
+
+/// Evaluates the take profit and stop loss rules of every project against its curve price, called
+/// from the sync loop. Fired rules are saved before selling so a rule never fires twice.
+pub async fn run_trade_rules(app_data: &Arc<AppData>) {
+    let armed: Vec<ProjectRules> = match load_trade_rules() {
+        Ok(rules) => rules.into_iter().filter(|rules| rules.is_armed()).collect(),
+        Err(_) => return
+    };
+
+    for mut project_rules in armed {
+        let Ok(mint) = Pubkey::from_str(&project_rules.mint) else { continue };
+        let price = match dispatch::bonding_curve(app_data, &mint).await {
+            Ok(Some(curve)) if !curve.complete => curve.price(),
+            _ => continue
+        };
+
+        let Some(firing) = project_rules.evaluate(price) else { continue };
+
+        // A ladder tranche is selling the project, the rule fires on a later pass instead
+        if !app_data.selling.write().await.claim(project_rules.project_id) {
+            continue;
+        }
+
+        let described: Vec<String> = firing.rules.iter()
+            .filter_map(|id| project_rules.find_rule(*id))
+            .map(|rule| rule.to_string())
+            .collect();
+
+        if let Err(err) = mark_rules_fired(project_rules.project_id, &firing.rules) {
+            app_data.selling.write().await.release(project_rules.project_id);
+            app_data.notifications.write().await.push(format!("Unable to save fired trade rules - {}", err));
+            continue;
+        }
+
+        let name = app_data.projects.read().await.get(&project_rules.project_id)
+            .map(|project| project.name.clone())
+            .unwrap_or(project_rules.mint.clone());
+        app_data.notifications.write().await.push(format!(
+            "Trade rule on {} fired at {} SOL - {}",
+            name,
+            price,
+            described.join(", ")
+        ));
+
+        let app_data = Arc::clone(app_data);
+        tokio::spawn(async move {
+            let mut event = RuleEvent::new(project_rules.project_id, described, price);
+            match sell_share(&app_data, project_rules.project_id, mint, firing.sell_share).await {
+                Ok((records, error)) => {
+                    event.signatures = records.iter().map(|record| record.signature.clone()).collect();
+                    event.error = error.or(records.iter()
+                        .find(|record| !record.succeeded())
+                        .map(|record| format!("{} failed", record.signature)));
+                },
+                Err(err) => event.error = Some(err)
+            }
+            app_data.selling.write().await.release(project_rules.project_id);
+
+            if let Some(err) = &event.error {
+                app_data.notifications.write().await.push(format!("Trade rule on {} failed - {}", name, err));
+            }
+            let _ = log_rule_event(event);
+        });
+    }
+}
@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::sync::Arc;
 use std::time::Duration;
 use console::style;
@@ -10,14 +11,27 @@ use handlers::bumps::BumpMenu;
 use handlers::bumps::StartBumps;
 use handlers::bumps::StopBumps;
 use handlers::Handler;
+use jobs::ladder::SellLadders;
+use jobs::sell::SellingProjects;
+use jobs::snipe::SnipeJobs;
+use chain::analysis::TokenAnalysisError;
+use chain::pumpfun::get_bonding_curve;
+use chain::pumpfun::BondingCurve;
+use chain::verify::persistent_mismatches;
+use chain::verify::verify_balances;
+use chain::verify::BalanceKind;
+use chain::verify::BalanceMismatch;
 use moonbois_core::rpc::MoonboisClient;
 use moonbois_core::rpc::MoonboisClientError;
 use moonbois_core::ProjectDTO;
 use moonbois_core::PendingSnipeError;
 use moonbois_core::PumpfunBumpStatus;
 use moonbois_core::UserDTO;
+use solana_client::client_error::ClientError;
+use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_sdk::native_token::LAMPORTS_PER_SOL;
 use solana_sdk::pubkey::ParsePubkeyError;
+use solana_sdk::pubkey::Pubkey;
 use tokio::sync::RwLock;
 use tokio::task::JoinHandle;
 use tokio::time::sleep_until;
@@ -29,9 +43,38 @@ use handlers::wallet::*;
 use handlers::snipe::*;
 use handlers::project::*;
 use handlers::main::*;
+use handlers::transactions::*;
+use handlers::presets::*;
+use handlers::deployer::*;
+use handlers::portfolio::*;
+use handlers::browser::*;
+use handlers::address_book::*;
+use handlers::risk::*;
+use handlers::panic::*;
+use handlers::trade_rules::*;
+use handlers::ladder::*;
+use handlers::paper::*;
+use paper::ledger::load_ledger;
+use paper::ledger::PaperLedger;
+use paper::PaperConfig;
+use paper::PaperError;
+use storage::ladders::LadderStatus;
+use storage::risk::RiskViolation;
+use storage::StorageError;
 
 pub mod handlers;
 pub mod dialogue;
+pub mod chain;
+pub mod storage;
+pub mod jobs;
+pub mod paper;
+
+static VERIFY_BALANCES_INTERVAL: u64 = 10;
+static BONDING_CURVE_INTERVAL: u64 = 4;
+static TRADE_RULES_INTERVAL: u64 = 4;
+static PAPER_MARKET_INTERVAL: u64 = 4;
+static PANIC_EXIT_ARG: &str = "--panic-exit";
+static PAPER_ARG: &str = "--paper";
 
 static BANNER: &str = r#"
  _____ _____ _____ _____ _____ _____ _____ _____ 
@@ -43,13 +86,27 @@ static BANNER: &str = r#"
 pub struct ActiveProject(pub Option<i32>);
 pub struct ActiveUser(pub Option<UserDTO>);
 pub struct BumpStatus(pub Option<PumpfunBumpStatus>);
+pub struct BalanceVerification(pub Option<Result<Vec<BalanceMismatch>, String>>);
+pub struct ActiveCurve(pub Option<(Pubkey, BondingCurve)>);
+/// Set by `--panic-exit`, sends the next login straight to `PanicExit`.
+pub struct PanicOnLogin(pub bool);
 
 pub struct AppData {
     pub rpc_client: RwLock<MoonboisClient>,
+    pub solana_client: RpcClient,
     pub user: RwLock<ActiveUser>,
     pub projects: RwLock<HashMap<i32, ProjectDTO>>,
     pub active_project: RwLock<ActiveProject>,
-    pub bump_status: RwLock<BumpStatus>
+    pub bump_status: RwLock<BumpStatus>,
+    pub balance_verification: RwLock<BalanceVerification>,
+    pub bonding_curve: RwLock<ActiveCurve>,
+    pub snipes: RwLock<SnipeJobs>,
+    pub sell_ladders: RwLock<SellLadders>,
+    pub selling: RwLock<SellingProjects>,
+    pub panic_on_login: RwLock<PanicOnLogin>,
+    /// Set in paper mode, trades and transfers then settle against this ledger instead of the backend.
+    pub paper: Option<RwLock<PaperLedger>>,
+    pub notifications: RwLock<Vec<String>>
 }
 
 pub enum Menu {
@@ -59,6 +116,7 @@ pub enum Menu {
     Signup(Signup),
     Wallet(WalletMenu),
     Send(SendSOL),
+    SendTokens(SendTokens),
     ImportWallet(ImportWallet),
     DeleteWallet(DeleteWallet),
     Buy(Buy),
@@ -67,14 +125,32 @@ pub enum Menu {
     DeleteProject(DeleteProject),
     CancelSnipe(CancelSnipe),
     CreateSnipe(CreateSnipe),
+    PendingSnipes(PendingSnipes),
+    OrphanedSnipes(OrphanedSnipes),
+    SnipePresets(SnipePresets),
+    CreatePreset(CreatePreset),
+    Deployers(Deployers),
+    DeployerProfile(DeployerProfile),
     Sell(Sell),
     Withdraw(Withdraw),
     Deposit(Deposit),
     ProjectMenu(ProjectMenu),
+    Holders(Holders),
     CreateProject(CreateProject),
     SelectProject(SelectProject),
+    ProjectDetails(ProjectDetails),
+    UntrackedTokens(UntrackedTokens),
     RecoverSol(RecoverSol),
-    Export(Export)
+    ReclaimRent(ReclaimRent),
+    Export(Export),
+    AddressBook(AddressBookMenu),
+    RiskLimits(RiskLimits),
+    Portfolio(Portfolio),
+    PanicExit(PanicExit),
+    TradeRules(TradeRules),
+    SellLadder(SellLadderMenu),
+    PaperAccount(PaperAccount),
+    Transactions(Transactions)
 }
 impl Handler for Menu {
     async fn handle(&self, app_data: &Arc<AppData>) -> Result<Option<Menu>, (Menu, AppError)> {
@@ -85,7 +161,10 @@ impl Handler for Menu {
             Menu::CreateProject(handler) => handler.handle(app_data).await,
             Menu::Wallet(handler) => handler.handle(app_data).await,
             Menu::ProjectMenu(handler) => handler.handle(app_data).await,
+            Menu::Holders(handler) => handler.handle(app_data).await,
             Menu::SelectProject(handler) => handler.handle(app_data).await,
+            Menu::ProjectDetails(handler) => handler.handle(app_data).await,
+            Menu::UntrackedTokens(handler) => handler.handle(app_data).await,
             Menu::StartBumps(handler) => handler.handle(app_data).await,
             Menu::StopBumps(handler) => handler.handle(app_data).await,
             Menu::Bump(handler) => handler.handle(app_data).await,
@@ -93,14 +172,30 @@ impl Handler for Menu {
             Menu::DeleteWallet(handler) => handler.handle(app_data).await, 
             Menu::ImportWallet(handler) => handler.handle(app_data).await, 
             Menu::RecoverSol(handler) => handler.handle(app_data).await,
+            Menu::ReclaimRent(handler) => handler.handle(app_data).await,
             Menu::CancelSnipe(handler) => handler.handle(app_data).await,
             Menu::CreateSnipe(handler) => handler.handle(app_data).await,
+            Menu::PendingSnipes(handler) => handler.handle(app_data).await,
+            Menu::OrphanedSnipes(handler) => handler.handle(app_data).await,
+            Menu::SnipePresets(handler) => handler.handle(app_data).await,
+            Menu::CreatePreset(handler) => handler.handle(app_data).await,
+            Menu::Deployers(handler) => handler.handle(app_data).await,
+            Menu::DeployerProfile(handler) => handler.handle(app_data).await,
             Menu::DeleteProject(handler) => handler.handle(app_data).await,
             Menu::Sell(handler) => handler.handle(app_data).await,
             Menu::Export(handler) => handler.handle(app_data).await,
+            Menu::AddressBook(handler) => handler.handle(app_data).await,
+            Menu::RiskLimits(handler) => handler.handle(app_data).await,
+            Menu::Portfolio(handler) => handler.handle(app_data).await,
+            Menu::PanicExit(handler) => handler.handle(app_data).await,
+            Menu::TradeRules(handler) => handler.handle(app_data).await,
+            Menu::SellLadder(handler) => handler.handle(app_data).await,
+            Menu::PaperAccount(handler) => handler.handle(app_data).await,
             Menu::Send(handler) => handler.handle(app_data).await,
+            Menu::SendTokens(handler) => handler.handle(app_data).await,
             Menu::Deposit(handler) => handler.handle(app_data).await,
             Menu::Withdraw(handler) => handler.handle(app_data).await,
+            Menu::Transactions(handler) => handler.handle(app_data).await,
         }
     }
 }
@@ -116,11 +211,30 @@ impl App {
             app_data,
             socket_handle: tokio::spawn(async move {
                 loop {
+                    if app_data_arc.paper.is_some() && app_data_arc.user.read().await.0.is_some() { break; }
                     let rpc_client = app_data_arc.rpc_client.read().await;
                     if rpc_client.jwt.is_some() { drop(rpc_client); break; }
+                    drop(rpc_client);
                     sleep_until(Instant::now() + Duration::from_millis(500)).await;
                 }
                 
+                let verify_enabled = chain::verify_balances_enabled();
+                let mut unconfirmed_mismatches: Vec<BalanceMismatch> = vec![];
+                let mut iteration: u64 = 0;
+
+                if let Some(ledger) = &app_data_arc.paper {
+                    loop {
+                        paper::dispatch::sync_paper(&app_data_arc, ledger, iteration % PAPER_MARKET_INTERVAL == 0).await?;
+
+                        if iteration % TRADE_RULES_INTERVAL == 0 {
+                            jobs::trade_rules::run_trade_rules(&app_data_arc).await;
+                        }
+                        iteration += 1;
+
+                        sleep_until(Instant::now() + Duration::from_millis(500)).await;
+                    }
+                }
+
                 loop {
                     let rpc_client = app_data_arc.rpc_client.read().await;
                     let projects = match rpc_client.get_user_projects().await {
@@ -159,6 +273,47 @@ impl App {
                         }
                     }
 
+                    if verify_enabled && iteration % VERIFY_BALANCES_INTERVAL == 0 {
+                        let reported = app_data_arc.user.read().await.0.as_ref().map(|user| {
+                            ((user.public_key, user.sol_balance), user.wallets.values().cloned().collect::<Vec<_>>())
+                        });
+
+                        if let Some((fee_payer, wallets)) = reported {
+                            let result = match verify_balances(&app_data_arc.solana_client, fee_payer, &wallets, mint_id).await {
+                                Ok(mismatches) => {
+                                    let flagged = persistent_mismatches(&unconfirmed_mismatches, &mismatches);
+                                    unconfirmed_mismatches = mismatches;
+                                    Ok(flagged)
+                                },
+                                Err(err) => Err(err.to_string())
+                            };
+                            app_data_arc.balance_verification.write().await.0 = Some(result);
+                        }
+                    }
+
+                    match mint_id {
+                        Some(mint_id) if iteration % BONDING_CURVE_INTERVAL == 0 => {
+                            if let Ok(Some(curve)) = get_bonding_curve(&app_data_arc.solana_client, &mint_id).await {
+                                if curve.complete && !storage::migrations::is_migrated(&mint_id).unwrap_or(true) {
+                                    if storage::migrations::mark_migrated(&mint_id).is_ok() {
+                                        app_data_arc.notifications.write().await.push(format!(
+                                            "{} completed its bonding curve and migrated, curve-based buy and sell will no longer work",
+                                            mint_id
+                                        ));
+                                    }
+                                }
+                                app_data_arc.bonding_curve.write().await.0 = Some((mint_id, curve));
+                            }
+                        },
+                        Some(_) => {},
+                        None => app_data_arc.bonding_curve.write().await.0 = None
+                    }
+
+                    if iteration % TRADE_RULES_INTERVAL == 0 {
+                        jobs::trade_rules::run_trade_rules(&app_data_arc).await;
+                    }
+                    iteration += 1;
+
                     let rpc_client = app_data_arc.rpc_client.read().await;
                     let bump_status = match rpc_client.get_bumps_status().await {
                         Ok(bump_status) => Some(bump_status),
@@ -179,9 +334,13 @@ impl App {
         loop {
             std::process::Command::new("clear").status().unwrap();
             println!("{}", style(BANNER).bold());
+
+            if self.app_data.paper.is_some() {
+                println!("mode: {}", style("paper").yellow());
+            }
             
             if let Some(user) = &self.app_data.user.read().await.0 {
-                let sniper_balance = user.wallets.iter().map(|(_, y)| y.sol_balance).reduce(|x, y| x + y).unwrap() as f64 / LAMPORTS_PER_SOL as f64;
+                let sniper_balance = user.wallets.iter().map(|(_, y)| y.sol_balance).sum::<u64>() as f64 / LAMPORTS_PER_SOL as f64;
                 let user_balance = user.sol_balance as f64 / LAMPORTS_PER_SOL as f64;
                 println!(
                     "fee_payer: {}\nfee_payer_balance: {}\nsniper_sol_balance: {}",
@@ -200,6 +359,55 @@ impl App {
                     }
                 }
             }
+
+            match &self.app_data.balance_verification.read().await.0 {
+                Some(Ok(mismatches)) if mismatches.is_empty() => println!("balance_check: {}", style("verified").green()),
+                Some(Ok(mismatches)) => {
+                    println!("balance_check: {}", style(format!("{} mismatches ⚠️", mismatches.len())).yellow());
+                    for mismatch in mismatches {
+                        let (unit, decimals) = match mismatch.kind {
+                            BalanceKind::Sol => ("SOL", 9),
+                            BalanceKind::Token => ("TOKENS", 6)
+                        };
+                        println!(
+                            "  - {} {}",
+                            &mismatch.owner.to_string()[0..5],
+                            style(format!(
+                                "backend {} {unit}, onchain {} {unit}",
+                                mismatch.reported as f64 / 10f64.powi(decimals),
+                                mismatch.onchain as f64 / 10f64.powi(decimals)
+                            )).dim()
+                        );
+                    }
+                },
+                Some(Err(err)) => println!("balance_check: {}", style(format!("failed - {}", err)).dim()),
+                None => {}
+            }
+
+            let pending_snipes = self.app_data.snipes.read().await.pending_count();
+            if pending_snipes > 0 {
+                println!("pending_snipes: {}", pending_snipes);
+            }
+
+            for ladder in self.app_data.sell_ladders.read().await.active() {
+                let name = self.app_data.projects.read().await.get(&ladder.project_id)
+                    .map(|project| project.name.to_uppercase())
+                    .unwrap_or(ladder.mint.clone());
+                println!(
+                    "sell_ladder: {} {}/{} tranches {}",
+                    style(name).magenta(),
+                    ladder.sold_count(),
+                    ladder.tranches.len(),
+                    match ladder.status {
+                        LadderStatus::Running => style(ladder.status.to_string()).green(),
+                        _ => style(ladder.status.to_string()).yellow()
+                    }
+                );
+            }
+
+            for notification in std::mem::take(&mut *self.app_data.notifications.write().await) {
+                println!("{}", style(notification).green());
+            }
     
             if let Some(active_project) = &self.app_data.active_project.read().await.0 {
                 if let Some(bump_status) = &self.app_data.bump_status.read().await.0 {
@@ -216,7 +424,27 @@ impl App {
                         "mint_id: {}\ndeployer: {}",
                         project.pumpfun.mint_id,
                         project.deployer
-                    )
+                    );
+                    match &self.app_data.bonding_curve.read().await.0 {
+                        Some((mint_id, curve)) if mint_id == &project.pumpfun.mint_id => {
+                            println!(
+                                "price: {:.10} {}\nmarket_cap: {:.2} {}\nreserves: {} {} virtual, {} {} real\ncurve_progress: {:.2}%",
+                                curve.price(),
+                                style("SOL").cyan(),
+                                curve.market_cap(),
+                                style("SOL").cyan(),
+                                curve.virtual_sol_reserves as f64 / LAMPORTS_PER_SOL as f64,
+                                style("SOL").cyan(),
+                                curve.real_sol_reserves as f64 / LAMPORTS_PER_SOL as f64,
+                                style("SOL").cyan(),
+                                curve.progress()
+                            );
+                            if curve.complete {
+                                println!("{}", style("curve: migrated ⚠️ curve-based buy and sell no longer work").yellow());
+                            }
+                        },
+                        _ => {}
+                    }
                 }
             }
     
@@ -354,6 +582,56 @@ impl App {
 
                     current_menu = menu;
                 }
+                Err((menu, AppError::SolanaClientError(err))) => {
+                    println!("{}\n  - {}", style("Solana RPC error occured ⚠️").yellow(), style(err.to_string()).dim());
+                    Select::with_theme(&ColorfulTheme::default())
+                        .items(&vec!["Back"])
+                        .default(0)
+                        .interact()
+                        .unwrap();
+
+                    current_menu = menu;
+                }
+                Err((menu, AppError::RiskViolation(err))) => {
+                    println!("{}\n  - {}", style("Blocked by risk limits ⚠️").yellow(), style(err.to_string()).dim());
+                    Select::with_theme(&ColorfulTheme::default())
+                        .items(&vec!["Back"])
+                        .default(0)
+                        .interact()
+                        .unwrap();
+
+                    current_menu = menu;
+                }
+                Err((menu, AppError::PaperError(err))) => {
+                    println!("{}\n  - {}", style("Paper trade rejected ⚠️").yellow(), style(err.to_string()).dim());
+                    Select::with_theme(&ColorfulTheme::default())
+                        .items(&vec!["Back"])
+                        .default(0)
+                        .interact()
+                        .unwrap();
+
+                    current_menu = menu;
+                }
+                Err((menu, AppError::TokenAnalysisError(err))) => {
+                    println!("{}\n  - {}", style("Token analysis failed ⚠️").yellow(), style(err.to_string()).dim());
+                    Select::with_theme(&ColorfulTheme::default())
+                        .items(&vec!["Back"])
+                        .default(0)
+                        .interact()
+                        .unwrap();
+
+                    current_menu = menu;
+                }
+                Err((menu, AppError::StorageError(err))) => {
+                    println!("{}\n  - {}", style("Local storage error occured ⚠️").yellow(), style(err.to_string()).dim());
+                    Select::with_theme(&ColorfulTheme::default())
+                        .items(&vec!["Back"])
+                        .default(0)
+                        .interact()
+                        .unwrap();
+
+                    current_menu = menu;
+                }
                 Err((menu, AppError::DialogueError(err))) => {
                     println!("{}\n  - {}", style("Dialogue error occured ⚠️").yellow(), style(err.to_string()).dim());
                     Select::with_theme(&ColorfulTheme::default())
@@ -400,11 +678,34 @@ impl App {
 
 #[tokio::main]
 pub async fn main() {
+    dotenv::dotenv().ok();
+
+    let paper = if paper::paper_mode_requested(PAPER_ARG) {
+        storage::use_paper_data_dir();
+        match load_ledger() {
+            Ok(Some(ledger)) => Some(RwLock::new(ledger)),
+            Ok(None) => Some(RwLock::new(PaperLedger::new(&PaperConfig::from_env()))),
+            Err(err) => {
+                println!("{}\n  - {}", style("Unable to load the paper ledger ⚠️").yellow(), style(err.to_string()).dim());
+                return
+            }
+        }
+    } else { None };
+
     let app_data = Arc::new(AppData {
         active_project: RwLock::new(ActiveProject(None)),
         bump_status: RwLock::new(BumpStatus(None)),
+        balance_verification: RwLock::new(BalanceVerification(None)),
+        bonding_curve: RwLock::new(ActiveCurve(None)),
+        snipes: RwLock::new(SnipeJobs(HashMap::new())),
+        sell_ladders: RwLock::new(SellLadders(HashMap::new())),
+        selling: RwLock::new(SellingProjects(HashSet::new())),
+        panic_on_login: RwLock::new(PanicOnLogin(std::env::args().skip(1).any(|arg| arg == PANIC_EXIT_ARG))),
+        paper,
+        notifications: RwLock::new(vec![]),
         projects: RwLock::new(HashMap::new()),
         rpc_client: RwLock::new(MoonboisClient::new()),
+        solana_client: chain::new_solana_client(),
         user: RwLock::new(ActiveUser(None))
     });
 
@@ -418,12 +719,22 @@ pub enum AppError {
     MoonboisClientError(#[from] MoonboisClientError),
     #[error("Pending snipe error: {0}")]
     PendingSnipeError(#[from] PendingSnipeError),
+    #[error("Solana client error: {0}")]
+    SolanaClientError(#[from] ClientError),
+    #[error("Token analysis error: {0}")]
+    TokenAnalysisError(#[from] TokenAnalysisError),
+    #[error("Risk limit: {0}")]
+    RiskViolation(#[from] RiskViolation),
+    #[error("Storage error: {0}")]
+    StorageError(#[from] StorageError),
     #[error("Dialogue error: {0}")]
     DialogueError(#[from] dialoguer::Error),
     #[error("Parse pubkey error: {0}")]
     ParsePubkeyError(#[from] ParsePubkeyError),
     #[error("Loader error: {0}")]
     LoaderError(#[from] LoaderError),
+    #[error("Paper trading error: {0}")]
+    PaperError(#[from] PaperError),
     #[error("Project not found")]
     ProjectNotFound,
     #[error("User not found")]
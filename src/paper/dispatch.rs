@@ -0,0 +1,459 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use moonbois_core::CreateSnipeParams;
+use moonbois_core::ProjectDTO;
+use moonbois_core::WalletDTO;
+use solana_client::client_error::ClientError;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use tokio::sync::RwLock;
+
+use crate::chain::confirm::get_signer;
+use crate::chain::confirm::track_signatures;
+use crate::chain::get_transfer_fee;
+use crate::chain::confirm::ConfirmationStatus;
+use crate::chain::pumpfun::get_bonding_curve;
+use crate::chain::pumpfun::BondingCurve;
+use crate::jobs::snipe::new_snipe_record;
+use crate::jobs::snipe::wait_for_snipe;
+use crate::storage::snipes::SnipeLimits;
+use crate::storage::transactions::record_transactions;
+use crate::storage::transactions::unix_timestamp;
+use crate::storage::transactions::TransactionRecord;
+use crate::AppData;
+use crate::AppError;
+
+use super::dto::project_dto;
+use super::dto::project_dtos;
+use super::dto::user_dto;
+use super::ledger::save_ledger;
+use super::ledger::NETWORK_FEE_LAMPORTS;
+use super::ledger::PaperLedger;
+use super::PaperError;
+
+// Every backend call that moves funds goes through here. In paper mode it runs against the ledger
+// in `AppData.paper` instead, with the same inputs and outputs, so handlers and jobs are written
+// once for both modes.
+
+pub type PendingSnipe = Pin<Box<dyn Future<Output = Result<ProjectDTO, String>> + Send>>;
+
+/// Applies `operation` to the ledger and persists it, also when the operation failed part way.
+async fn with_ledger<T>(paper: &RwLock<PaperLedger>, operation: impl FnOnce(&mut PaperLedger) -> Result<T, PaperError>) -> Result<T, AppError> {
+    let mut ledger = paper.write().await;
+    let result = operation(&mut ledger);
+    save_ledger(&ledger)?;
+
+    Ok(result?)
+}
+
+/// Rejects features that only exist on the backend while paper trading.
+pub fn live_only(app_data: &AppData, feature: &'static str) -> Result<(), AppError> {
+    match app_data.paper {
+        Some(_) => Err(AppError::from(PaperError::Unsupported(feature))),
+        None => Ok(())
+    }
+}
+
+pub async fn buy(app_data: &AppData, project_id: i32, wallet: &WalletDTO, lamports: u64) -> Result<Signature, AppError> {
+    if let Some(paper) = &app_data.paper {
+        return with_ledger(paper, |ledger| ledger.buy(project_id, &wallet.public_key, lamports)).await
+    }
+
+    let rpc_client = app_data.rpc_client.read().await;
+    Ok(rpc_client.buy(project_id, wallet.id, lamports).await?)
+}
+
+pub async fn auto_buy(app_data: &AppData, project_id: i32, lamports: u64) -> Result<Vec<Signature>, AppError> {
+    if let Some(paper) = &app_data.paper {
+        return with_ledger(paper, |ledger| ledger.auto_buy(project_id, lamports)).await
+    }
+
+    let rpc_client = app_data.rpc_client.read().await;
+    Ok(rpc_client.auto_buy(project_id, lamports).await?)
+}
+
+pub async fn sell(app_data: &AppData, project_id: i32, wallet: &WalletDTO) -> Result<Signature, AppError> {
+    if let Some(paper) = &app_data.paper {
+        return with_ledger(paper, |ledger| ledger.sell(project_id, &wallet.public_key)).await
+    }
+
+    let rpc_client = app_data.rpc_client.read().await;
+    Ok(rpc_client.sell(project_id, wallet.id).await?)
+}
+
+pub async fn auto_sell(app_data: &AppData, project_id: i32) -> Result<Vec<Signature>, AppError> {
+    if let Some(paper) = &app_data.paper {
+        return with_ledger(paper, |ledger| ledger.auto_sell(project_id)).await
+    }
+
+    let rpc_client = app_data.rpc_client.read().await;
+    Ok(rpc_client.auto_sell(project_id).await?)
+}
+
+pub async fn transfer_sol_from_sniper(app_data: &AppData, wallet: &WalletDTO, receiver: Pubkey, lamports: u64) -> Result<Signature, AppError> {
+    if let Some(paper) = &app_data.paper {
+        return with_ledger(paper, |ledger| ledger.transfer_sol_from_sniper(&wallet.public_key, &receiver, lamports)).await
+    }
+
+    let rpc_client = app_data.rpc_client.read().await;
+    Ok(rpc_client.transfer_sol_from_sniper(wallet.id, receiver, lamports).await?)
+}
+
+pub async fn transfer_sol_from_main(app_data: &AppData, receiver: Pubkey, lamports: u64) -> Result<Signature, AppError> {
+    if let Some(paper) = &app_data.paper {
+        return with_ledger(paper, |ledger| ledger.transfer_sol_from_main(&receiver, lamports)).await
+    }
+
+    let rpc_client = app_data.rpc_client.read().await;
+    Ok(rpc_client.transfer_sol_from_main(receiver, lamports).await?)
+}
+
+pub async fn transfer_tokens_from_sniper(app_data: &AppData, wallet: &WalletDTO, mint_id: Pubkey, receiver: Pubkey, amount: u64) -> Result<Signature, AppError> {
+    if let Some(paper) = &app_data.paper {
+        return with_ledger(paper, |ledger| ledger.transfer_tokens_from_sniper(&wallet.public_key, &mint_id, &receiver, amount)).await
+    }
+
+    let rpc_client = app_data.rpc_client.read().await;
+    Ok(rpc_client.transfer_tokens_from_sniper(wallet.id, mint_id, receiver, amount).await?)
+}
+
+pub async fn user_projects(app_data: &AppData) -> Result<HashMap<i32, ProjectDTO>, AppError> {
+    if let Some(paper) = &app_data.paper {
+        return Ok(project_dtos(&*paper.read().await)?)
+    }
+
+    let rpc_client = app_data.rpc_client.read().await;
+    Ok(rpc_client.get_user_projects().await?)
+}
+
+/// Snipers holding tokens of `mint_id` with their raw balance, keyed like `UserDTO.wallets`.
+pub async fn token_balances(app_data: &AppData, mint_id: Pubkey) -> Result<Vec<(String, u64)>, AppError> {
+    if let Some(paper) = &app_data.paper {
+        return Ok(paper.read().await.token_balances(&mint_id))
+    }
+
+    let rpc_client = app_data.rpc_client.read().await;
+    let balances = rpc_client.get_user_balances(Some(mint_id)).await?;
+    drop(rpc_client);
+
+    Ok(balances.wallets.into_iter()
+        .filter_map(|(wallet, balance)| balance.token_balance.filter(|amount| *amount > 0).map(|amount| (wallet, amount)))
+        .collect())
+}
+
+/// SOL balance of an account, from the chain or from the ledger in paper mode.
+pub async fn sol_balance(app_data: &AppData, public_key: &Pubkey) -> Result<u64, AppError> {
+    if let Some(paper) = &app_data.paper {
+        return Ok(paper.read().await.sol_balance(public_key).unwrap_or(0))
+    }
+
+    Ok(app_data.solana_client.get_balance(public_key).await?)
+}
+
+/// Fee of a SOL transfer signed by `from`, quoted by the RPC or the flat fee of the ledger in paper mode.
+pub async fn transfer_fee(app_data: &AppData, from: &Pubkey) -> Result<u64, AppError> {
+    if app_data.paper.is_some() {
+        return Ok(NETWORK_FEE_LAMPORTS)
+    }
+
+    Ok(get_transfer_fee(&app_data.solana_client, from).await?)
+}
+
+/// The bonding curve of a mint. Paper projects trade on their simulated curve, any other mint is
+/// read from the chain.
+pub async fn bonding_curve(app_data: &AppData, mint_id: &Pubkey) -> Result<Option<BondingCurve>, ClientError> {
+    if let Some(paper) = &app_data.paper {
+        if let Some(project) = paper.read().await.find_project(mint_id) {
+            return Ok(Some(project.curve.clone()))
+        }
+    }
+
+    get_bonding_curve(&app_data.solana_client, mint_id).await
+}
+
+/// Imports a token. Paper projects start from a snapshot of `curve` and have no known deployer.
+pub async fn create_project(app_data: &AppData, mint_id: Pubkey, name: String, curve: &BondingCurve) -> Result<ProjectDTO, AppError> {
+    if let Some(paper) = &app_data.paper {
+        let project = with_ledger(paper, |ledger| Ok(ledger.add_project(name, mint_id, Pubkey::default(), curve.clone()).clone())).await?;
+        return Ok(project_dto(&project)?)
+    }
+
+    let rpc_client = app_data.rpc_client.read().await;
+    Ok(rpc_client.create_project(mint_id).await?)
+}
+
+pub async fn delete_project(app_data: &AppData, project_id: i32) -> Result<(), AppError> {
+    if let Some(paper) = &app_data.paper {
+        return with_ledger(paper, |ledger| ledger.remove_project(project_id)).await
+    }
+
+    let rpc_client = app_data.rpc_client.read().await;
+    rpc_client.delete_project(project_id).await?;
+
+    Ok(())
+}
+
+/// Arms a snipe and returns the future that resolves with the sniped project. A paper snipe fills
+/// once the sync loop launches its deployer's token, see `PaperLedger::launch_due`.
+pub async fn create_snipe(app_data: &Arc<AppData>, params: CreateSnipeParams, wallets: &[WalletDTO]) -> Result<PendingSnipe, AppError> {
+    let Some(paper) = &app_data.paper else {
+        let rpc_client = app_data.rpc_client.read().await;
+        let pending_snipe = rpc_client.create_snipe(params).await?;
+        drop(rpc_client);
+
+        return Ok(Box::pin(async move { pending_snipe.await.map_err(|err| err.to_string()) }))
+    };
+
+    let mut snipers: Vec<&WalletDTO> = match &params.wallet_ids {
+        Some(wallet_ids) => wallets.iter().filter(|wallet| wallet_ids.contains(&wallet.id)).collect(),
+        None => wallets.iter().collect()
+    };
+    snipers.sort_by_key(|wallet| wallet.public_key.to_string());
+    snipers.truncate(params.wallet_count);
+
+    let deployer = params.deployer;
+    let snipers = snipers.iter().map(|wallet| wallet.public_key).collect();
+    with_ledger(paper, |ledger| {
+        ledger.arm_snipe(deployer, snipers, params.max_lamports_per_wallet, params.max_total_lamports, unix_timestamp())
+    }).await?;
+
+    let limits = SnipeLimits { max_lamports_per_wallet: params.max_lamports_per_wallet, max_total_lamports: params.max_total_lamports, expiry: None };
+    let record = new_snipe_record(app_data, deployer, params.wallet_count, &limits).await;
+    let app_data = Arc::clone(app_data);
+
+    Ok(Box::pin(async move { wait_for_snipe(app_data, record).await.map_err(|err| err.to_string()) }))
+}
+
+pub async fn cancel_snipe(app_data: &AppData, deployer: &Pubkey) -> Result<(), AppError> {
+    if let Some(paper) = &app_data.paper {
+        return with_ledger(paper, |ledger| {
+            ledger.cancel_snipe(deployer);
+            Ok(())
+        }).await
+    }
+
+    let rpc_client = app_data.rpc_client.read().await;
+    rpc_client.cancel_snipe(deployer).await?;
+
+    Ok(())
+}
+
+/// Fills in the confirmation status of submitted transactions and the sniper behind each auto trade.
+/// Paper transactions settle when they are sent, so they are confirmed right away, sells get their
+/// proceeds from the ledger and buys what they actually spent.
+pub async fn settle(app_data: &AppData, records: &mut [TransactionRecord]) {
+    if let Some(paper) = &app_data.paper {
+        let ledger = paper.read().await;
+        for record in records.iter_mut() {
+            record.status = ConfirmationStatus::Confirmed;
+            if record.lamports.is_none() {
+                record.lamports = ledger.proceeds.get(&record.signature).copied();
+            }
+            if let Some(cost) = ledger.costs.get(&record.signature) {
+                record.lamports = Some(*cost);
+            }
+            if record.wallet.is_none() {
+                record.wallet = ledger.signers.get(&record.signature).cloned();
+            }
+        }
+
+        return
+    }
+
+    let signatures: Vec<Signature> = records.iter()
+        .filter_map(|record| Signature::from_str(&record.signature).ok())
+        .collect();
+    let statuses = track_signatures(&app_data.solana_client, &signatures).await;
+    for (record, status) in records.iter_mut().zip(statuses) {
+        record.status = status;
+    }
+
+    // Auto trades come back as bare signatures, the sniper that signed each one is read on chain
+    let wallets: Vec<Pubkey> = match &app_data.user.read().await.0 {
+        Some(user) => user.wallets.values().map(|wallet| wallet.public_key).collect(),
+        None => return
+    };
+    for record in records.iter_mut().filter(|record| record.wallet.is_none() && record.is_confirmed()) {
+        if !matches!(record.action.as_str(), "auto_buy" | "auto_sell") {
+            continue;
+        }
+        let Ok(signature) = Signature::from_str(&record.signature) else { continue };
+
+        if let Some(wallet) = get_signer(&app_data.solana_client, &signature, &wallets).await {
+            record.wallet = Some(wallet.to_string());
+        }
+    }
+}
+
+/// One pass of the sync loop in paper mode. Launches the snipes that are due, moves the market
+/// when `tick` is set and publishes the ledger to `AppData` the way the backend sync does.
+pub async fn sync_paper(app_data: &AppData, paper: &RwLock<PaperLedger>, tick: bool) -> Result<(), String> {
+    let mint_id = match app_data.active_project.read().await.0 {
+        Some(project_id) => app_data.projects.read().await.get(&project_id).map(|project| project.pumpfun.mint_id),
+        None => None
+    };
+
+    let mut ledger = paper.write().await;
+    let fills = ledger.launch_due(unix_timestamp());
+    if tick {
+        ledger.tick();
+    }
+    save_ledger(&ledger).map_err(|err| err.to_string())?;
+
+    let projects = project_dtos(&ledger).map_err(|err| err.to_string())?;
+    let user = user_dto(&ledger, mint_id.as_ref()).map_err(|err| err.to_string())?;
+    let curve = mint_id.and_then(|mint_id| ledger.find_project(&mint_id)).map(|project| (project.mint, project.curve.clone()));
+    drop(ledger);
+
+    let records: Vec<TransactionRecord> = fills.iter().flat_map(|fill| fill.buys.iter().map(|(wallet, signature, lamports)| {
+        let mut record = TransactionRecord::new("snipe", *signature)
+            .with_project(fill.project_id)
+            .with_wallet(*wallet)
+            .with_lamports(*lamports);
+        record.status = ConfirmationStatus::Confirmed;
+        record
+    })).collect();
+    if !records.is_empty() {
+        record_transactions(&records).map_err(|err| err.to_string())?;
+    }
+
+    *app_data.projects.write().await = projects;
+    if let Some(user_data) = &mut app_data.user.write().await.0 {
+        *user_data = user;
+    }
+    app_data.bonding_curve.write().await.0 = curve;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use moonbois_core::rpc::MoonboisClient;
+    use solana_sdk::native_token::LAMPORTS_PER_SOL;
+
+    use crate::chain::new_solana_client;
+    use crate::handlers::trade::confirm_token_safety;
+    use crate::jobs::ladder::SellLadders;
+    use crate::jobs::sell::SellingProjects;
+    use crate::jobs::snipe::SnipeJobs;
+    use crate::paper::PaperConfig;
+    use crate::storage;
+    use crate::storage::TestDataDir;
+    use crate::ActiveCurve;
+    use crate::ActiveProject;
+    use crate::ActiveUser;
+    use crate::BalanceVerification;
+    use crate::BumpStatus;
+    use crate::PanicOnLogin;
+
+    use super::*;
+
+    /// A logged in paper session with a still market, nothing here reaches the backend or the chain.
+    /// Its files live in a data dir of their own for as long as the returned guard is held.
+    fn paper_app_data(name: &str, config: PaperConfig) -> (Arc<AppData>, TestDataDir) {
+        let data_dir = TestDataDir::new(name);
+        storage::use_paper_data_dir();
+
+        let ledger = PaperLedger::new(&PaperConfig { market_lamports: 0, ..config });
+        let user = user_dto(&ledger, None).unwrap();
+
+        let app_data = Arc::new(AppData {
+            active_project: RwLock::new(ActiveProject(None)),
+            bump_status: RwLock::new(BumpStatus(None)),
+            balance_verification: RwLock::new(BalanceVerification(None)),
+            bonding_curve: RwLock::new(ActiveCurve(None)),
+            snipes: RwLock::new(SnipeJobs(HashMap::new())),
+            sell_ladders: RwLock::new(SellLadders(HashMap::new())),
+            selling: RwLock::new(SellingProjects(HashSet::new())),
+            panic_on_login: RwLock::new(PanicOnLogin(false)),
+            paper: Some(RwLock::new(ledger)),
+            notifications: RwLock::new(vec![]),
+            projects: RwLock::new(HashMap::new()),
+            rpc_client: RwLock::new(MoonboisClient::new()),
+            solana_client: new_solana_client(),
+            user: RwLock::new(ActiveUser(Some(user)))
+        });
+
+        (app_data, data_dir)
+    }
+
+    fn paper(app_data: &AppData) -> &RwLock<PaperLedger> {
+        app_data.paper.as_ref().unwrap()
+    }
+
+    #[tokio::test]
+    async fn trades_settle_against_the_ledger() {
+        let (app_data, _data_dir) = paper_app_data("trades-settle-against-the-ledger", PaperConfig::default());
+        let mint = Pubkey::new_unique();
+        let project = create_project(&app_data, mint, "test".to_string(), &BondingCurve::launch()).await.unwrap();
+
+        let buys = auto_buy(&app_data, project.id, LAMPORTS_PER_SOL).await.unwrap();
+        assert_eq!(buys.len(), 5);
+        assert_eq!(token_balances(&app_data, mint).await.unwrap().len(), 5);
+        assert!(user_projects(&app_data).await.unwrap().contains_key(&project.id));
+
+        let sells = auto_sell(&app_data, project.id).await.unwrap();
+        let mut records: Vec<TransactionRecord> = sells.into_iter()
+            .map(|signature| TransactionRecord::new("auto_sell", signature).with_project(project.id))
+            .collect();
+        settle(&app_data, &mut records).await;
+
+        let received: u64 = records.iter().map(|record| record.lamports.unwrap()).sum();
+        assert!(records.iter().all(|record| matches!(record.status, ConfirmationStatus::Confirmed)));
+        assert!(received > 0 && received < LAMPORTS_PER_SOL);
+        assert!(token_balances(&app_data, mint).await.unwrap().is_empty());
+        assert!(paper(&app_data).read().await.total_lamports() < 15 * LAMPORTS_PER_SOL);
+    }
+
+    #[tokio::test]
+    async fn live_only_features_are_rejected() {
+        let (app_data, _data_dir) = paper_app_data("live-only-features-are-rejected", PaperConfig::default());
+
+        assert!(matches!(
+            live_only(&app_data, "Export"),
+            Err(AppError::PaperError(PaperError::Unsupported("Export")))
+        ));
+    }
+
+    #[tokio::test]
+    async fn snipes_fill_when_the_deployer_launches() {
+        let (app_data, _data_dir) = paper_app_data("snipes-fill-when-the-deployer-launches", PaperConfig { launch_delay: 0, ..PaperConfig::default() });
+        let deployer = Pubkey::new_unique();
+        let wallets: Vec<WalletDTO> = app_data.user.read().await.0.as_ref().unwrap().wallets.values().cloned().collect();
+        let params = CreateSnipeParams {
+            deployer,
+            wallet_count: 2,
+            wallet_ids: None,
+            max_lamports_per_wallet: Some(LAMPORTS_PER_SOL / 10),
+            max_total_lamports: None
+        };
+
+        let pending_snipe = create_snipe(&app_data, params, &wallets).await.unwrap();
+        sync_paper(&app_data, paper(&app_data), false).await.unwrap();
+        let project = pending_snipe.await.unwrap();
+
+        assert_eq!(project.deployer, deployer);
+        assert_eq!(token_balances(&app_data, project.pumpfun.mint_id).await.unwrap().len(), 2);
+        assert!(paper(&app_data).read().await.snipes.is_empty());
+    }
+
+    #[tokio::test]
+    async fn paper_buys_skip_the_on_chain_token_check() {
+        let (app_data, _data_dir) = paper_app_data("paper-buys-skip-the-on-chain-token-check", PaperConfig::default());
+        let mint = Pubkey::new_unique();
+        let project = create_project(&app_data, mint, "test".to_string(), &BondingCurve::launch()).await.unwrap();
+        let wallet = app_data.user.read().await.0.as_ref().unwrap().wallets.values().next().cloned().unwrap();
+
+        // The mint only exists in the ledger, analyzing it on chain would fail
+        assert!(confirm_token_safety(&app_data, &mint).await.unwrap());
+        buy(&app_data, project.id, &wallet, LAMPORTS_PER_SOL / 10).await.unwrap();
+
+        assert_eq!(token_balances(&app_data, mint).await.unwrap().len(), 1);
+    }
+}
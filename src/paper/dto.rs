@@ -0,0 +1,50 @@
+use std::collections::HashMap;
+
+use moonbois_core::ProjectDTO;
+use moonbois_core::UserDTO;
+use serde_json::json;
+use solana_sdk::pubkey::Pubkey;
+
+use super::ledger::PaperLedger;
+use super::ledger::PaperProject;
+use super::PaperError;
+
+// The DTOs are built through their JSON form so paper mode yields exactly what the backend would
+// return, the rest of the client cannot tell the two apart.
+
+/// The paper fee payer and snipers as a user, token balances are filled for `mint` only like
+/// `get_user_balances` does.
+pub fn user_dto(ledger: &PaperLedger, mint: Option<&Pubkey>) -> Result<UserDTO, PaperError> {
+    let wallets: HashMap<String, serde_json::Value> = ledger.wallets.iter().map(|wallet| {
+        (wallet.public_key.to_string(), json!({
+            "id": wallet.id,
+            "public_key": serde_json::to_value(wallet.public_key).unwrap_or_default(),
+            "sol_balance": wallet.lamports,
+            "token_balance": mint.map(|mint| wallet.token_balance(mint))
+        }))
+    }).collect();
+
+    Ok(serde_json::from_value(json!({
+        "id": 0,
+        "public_key": serde_json::to_value(ledger.fee_payer)?,
+        "sol_balance": ledger.lamports,
+        "wallets": wallets
+    }))?)
+}
+
+pub fn project_dto(project: &PaperProject) -> Result<ProjectDTO, PaperError> {
+    Ok(serde_json::from_value(json!({
+        "id": project.id,
+        "name": project.name,
+        "deployer": serde_json::to_value(project.deployer)?,
+        "pumpfun": {
+            "mint_id": serde_json::to_value(project.mint)?
+        }
+    }))?)
+}
+
+pub fn project_dtos(ledger: &PaperLedger) -> Result<HashMap<i32, ProjectDTO>, PaperError> {
+    ledger.projects.iter()
+        .map(|project| project_dto(project).map(|dto| (project.id, dto)))
+        .collect()
+}
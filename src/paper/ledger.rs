@@ -0,0 +1,653 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+
+use crate::chain::pumpfun::BondingCurve;
+use crate::storage::StorageError;
+
+use super::PaperConfig;
+use super::PaperError;
+
+static PAPER_LEDGER_FILE: &str = "paper_ledger.json";
+/// Lamports every paper transaction costs its sender.
+pub static NETWORK_FEE_LAMPORTS: u64 = 5000;
+
+static FEE_PAYER_TAG: u8 = 1;
+static WALLET_TAG: u8 = 2;
+static MINT_TAG: u8 = 3;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PaperWallet {
+    pub id: i32,
+    #[serde(with = "crate::storage::pubkey_string")]
+    pub public_key: Pubkey,
+    pub lamports: u64,
+    /// Raw token balances keyed by mint.
+    pub tokens: HashMap<String, u64>
+}
+
+impl PaperWallet {
+    pub fn token_balance(&self, mint: &Pubkey) -> u64 {
+        self.tokens.get(&mint.to_string()).copied().unwrap_or(0)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PaperProject {
+    pub id: i32,
+    pub name: String,
+    #[serde(with = "crate::storage::pubkey_string")]
+    pub mint: Pubkey,
+    #[serde(with = "crate::storage::pubkey_string")]
+    pub deployer: Pubkey,
+    pub curve: BondingCurve,
+    /// Tokens the simulated market bought, the most it can sell back.
+    pub market_tokens: u64
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PaperSnipe {
+    #[serde(with = "crate::storage::pubkey_string")]
+    pub deployer: Pubkey,
+    pub wallets: Vec<String>,
+    pub max_lamports_per_wallet: Option<u64>,
+    pub max_total_lamports: Option<u64>,
+    pub launch_at: u64
+}
+
+/// A paper snipe whose deployer launched, with the buy of every sniper that had SOL to spend.
+pub struct SnipeFill {
+    pub project_id: i32,
+    pub deployer: Pubkey,
+    pub buys: Vec<(Pubkey, Signature, u64)>
+}
+
+/// Balances, projects and pending snipes of paper trading. Every operation settles immediately
+/// against the bonding curve of its project, and signatures and the simulated market come from
+/// counters and a seeded generator so the same calls always give the same ledger.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PaperLedger {
+    #[serde(with = "crate::storage::pubkey_string")]
+    pub fee_payer: Pubkey,
+    pub lamports: u64,
+    /// SOL across the fee payer and snipers when the ledger was created, the baseline of the P&L.
+    pub starting_lamports: u64,
+    pub wallets: Vec<PaperWallet>,
+    pub projects: Vec<PaperProject>,
+    pub snipes: Vec<PaperSnipe>,
+    /// Lamports each sell returned after fees, keyed by signature.
+    pub proceeds: HashMap<String, u64>,
+    /// Lamports each buy spent before network fees, keyed by signature. Lower than asked when the
+    /// buy ran into the end of the curve and was partly refunded.
+    pub costs: HashMap<String, u64>,
+    /// Sniper behind each buy and sell, keyed by signature, so auto trades are recorded per wallet.
+    pub signers: HashMap<String, String>,
+    pub market_lamports: u64,
+    pub launch_delay: u64,
+    nonce: u64,
+    rng: u64
+}
+
+impl PaperLedger {
+    pub fn new(config: &PaperConfig) -> Self {
+        let wallets = (0..config.wallet_count).map(|index| PaperWallet {
+            id: index as i32 + 1,
+            public_key: paper_pubkey(WALLET_TAG, config.seed, index as u64),
+            lamports: config.wallet_lamports,
+            tokens: HashMap::new()
+        }).collect();
+
+        Self {
+            fee_payer: paper_pubkey(FEE_PAYER_TAG, config.seed, 0),
+            lamports: config.fee_payer_lamports,
+            starting_lamports: config.fee_payer_lamports + config.wallet_lamports * config.wallet_count as u64,
+            wallets,
+            projects: vec![],
+            snipes: vec![],
+            proceeds: HashMap::new(),
+            costs: HashMap::new(),
+            signers: HashMap::new(),
+            market_lamports: config.market_lamports,
+            launch_delay: config.launch_delay,
+            nonce: 0,
+            rng: (config.seed ^ 0x9e37_79b9_7f4a_7c15).max(1)
+        }
+    }
+
+    /// SOL across the fee payer and every sniper.
+    pub fn total_lamports(&self) -> u64 {
+        self.lamports + self.wallets.iter().map(|wallet| wallet.lamports).sum::<u64>()
+    }
+
+    pub fn wallet(&self, public_key: &Pubkey) -> Option<&PaperWallet> {
+        self.wallets.iter().find(|wallet| &wallet.public_key == public_key)
+    }
+
+    fn wallet_mut(&mut self, public_key: &Pubkey) -> Result<&mut PaperWallet, PaperError> {
+        self.wallets.iter_mut()
+            .find(|wallet| &wallet.public_key == public_key)
+            .ok_or(PaperError::WalletNotFound(*public_key))
+    }
+
+    pub fn project(&self, project_id: i32) -> Option<&PaperProject> {
+        self.projects.iter().find(|project| project.id == project_id)
+    }
+
+    fn project_mut(&mut self, project_id: i32) -> Result<&mut PaperProject, PaperError> {
+        self.projects.iter_mut()
+            .find(|project| project.id == project_id)
+            .ok_or(PaperError::ProjectNotFound(project_id))
+    }
+
+    pub fn find_project(&self, mint: &Pubkey) -> Option<&PaperProject> {
+        self.projects.iter().find(|project| &project.mint == mint)
+    }
+
+    /// SOL balance of the fee payer or a sniper, `None` for accounts outside the ledger.
+    pub fn sol_balance(&self, public_key: &Pubkey) -> Option<u64> {
+        if public_key == &self.fee_payer {
+            return Some(self.lamports)
+        }
+
+        self.wallet(public_key).map(|wallet| wallet.lamports)
+    }
+
+    /// Snipers holding tokens of `mint` with their raw balance.
+    pub fn token_balances(&self, mint: &Pubkey) -> Vec<(String, u64)> {
+        self.wallets.iter()
+            .map(|wallet| (wallet.public_key.to_string(), wallet.token_balance(mint)))
+            .filter(|(_, amount)| *amount > 0)
+            .collect()
+    }
+
+    /// Lamports selling every sniper's tokens of the project at once would return.
+    pub fn position_value(&self, project: &PaperProject) -> u64 {
+        let tokens: u64 = self.wallets.iter().map(|wallet| wallet.token_balance(&project.mint)).sum();
+        if tokens == 0 || project.curve.complete {
+            return 0
+        }
+
+        project.curve.sell_quote(tokens)
+    }
+
+    /// Adds a project trading on `curve`, or returns the project already trading the mint.
+    pub fn add_project(&mut self, name: String, mint: Pubkey, deployer: Pubkey, curve: BondingCurve) -> &PaperProject {
+        if let Some(index) = self.projects.iter().position(|project| project.mint == mint) {
+            return &self.projects[index]
+        }
+
+        let id = self.projects.iter().map(|project| project.id).max().unwrap_or(0) + 1;
+        self.projects.push(PaperProject { id, name, mint, deployer, curve, market_tokens: 0 });
+        &self.projects[self.projects.len() - 1]
+    }
+
+    pub fn remove_project(&mut self, project_id: i32) -> Result<(), PaperError> {
+        let index = self.projects.iter().position(|project| project.id == project_id)
+            .ok_or(PaperError::ProjectNotFound(project_id))?;
+        self.projects.remove(index);
+
+        Ok(())
+    }
+
+    pub fn buy(&mut self, project_id: i32, wallet: &Pubkey, lamports: u64) -> Result<Signature, PaperError> {
+        let mint = self.tradable_mint(project_id)?;
+        let needed = lamports + NETWORK_FEE_LAMPORTS;
+        let available = self.wallet_mut(wallet)?.lamports;
+        if available < needed {
+            return Err(PaperError::InsufficientFunds { needed, available })
+        }
+
+        let (tokens, spent) = self.project_mut(project_id)?.curve.apply_buy(lamports);
+        let sniper = self.wallet_mut(wallet)?;
+        sniper.lamports -= spent + NETWORK_FEE_LAMPORTS;
+        *sniper.tokens.entry(mint.to_string()).or_default() += tokens;
+
+        let signature = self.next_signature();
+        self.costs.insert(signature.to_string(), spent);
+        self.signers.insert(signature.to_string(), wallet.to_string());
+
+        Ok(signature)
+    }
+
+    /// Buys for `lamports` with every sniper that can afford it.
+    pub fn auto_buy(&mut self, project_id: i32, lamports: u64) -> Result<Vec<Signature>, PaperError> {
+        self.tradable_mint(project_id)?;
+        let needed = lamports + NETWORK_FEE_LAMPORTS;
+        let buyers: Vec<Pubkey> = self.wallets.iter()
+            .filter(|wallet| wallet.lamports >= needed)
+            .map(|wallet| wallet.public_key)
+            .collect();
+
+        if buyers.is_empty() {
+            let available = self.wallets.iter().map(|wallet| wallet.lamports).max().unwrap_or(0);
+            return Err(PaperError::InsufficientFunds { needed, available })
+        }
+
+        self.each_wallet(&buyers, |ledger, wallet| ledger.buy(project_id, wallet, lamports))
+    }
+
+    /// Sells the whole balance of one sniper.
+    pub fn sell(&mut self, project_id: i32, wallet: &Pubkey) -> Result<Signature, PaperError> {
+        let mint = self.tradable_mint(project_id)?;
+        let tokens = self.wallet_mut(wallet)?.token_balance(&mint);
+        if tokens == 0 {
+            return Err(PaperError::NoTokens)
+        }
+
+        let lamports = self.project_mut(project_id)?.curve.apply_sell(tokens);
+        let seller = self.wallet_mut(wallet)?;
+        seller.tokens.remove(&mint.to_string());
+        seller.lamports = (seller.lamports + lamports).saturating_sub(NETWORK_FEE_LAMPORTS);
+
+        let signature = self.next_signature();
+        self.proceeds.insert(signature.to_string(), lamports.saturating_sub(NETWORK_FEE_LAMPORTS));
+        self.signers.insert(signature.to_string(), wallet.to_string());
+
+        Ok(signature)
+    }
+
+    /// Sells the whole balance of every sniper holding the project's tokens.
+    pub fn auto_sell(&mut self, project_id: i32) -> Result<Vec<Signature>, PaperError> {
+        let mint = self.tradable_mint(project_id)?;
+        let sellers: Vec<Pubkey> = self.wallets.iter()
+            .filter(|wallet| wallet.token_balance(&mint) > 0)
+            .map(|wallet| wallet.public_key)
+            .collect();
+
+        if sellers.is_empty() {
+            return Err(PaperError::NoTokens)
+        }
+
+        self.each_wallet(&sellers, |ledger, wallet| ledger.sell(project_id, wallet))
+    }
+
+    /// Runs one trade per wallet the way the backend sends one transaction per sniper. Trades that
+    /// went through stay applied, so their signatures are returned even when a later one fails
+    /// and the error only surfaces when nothing went through.
+    fn each_wallet(&mut self, wallets: &[Pubkey], trade: impl Fn(&mut Self, &Pubkey) -> Result<Signature, PaperError>) -> Result<Vec<Signature>, PaperError> {
+        let mut signatures = vec![];
+        let mut error = None;
+        for wallet in wallets {
+            match trade(self, wallet) {
+                Ok(signature) => signatures.push(signature),
+                Err(err) => error = error.or(Some(err))
+            }
+        }
+
+        match error {
+            Some(err) if signatures.is_empty() => Err(err),
+            _ => Ok(signatures)
+        }
+    }
+
+    pub fn transfer_sol_from_sniper(&mut self, wallet: &Pubkey, receiver: &Pubkey, lamports: u64) -> Result<Signature, PaperError> {
+        let needed = lamports + NETWORK_FEE_LAMPORTS;
+        let sender = self.wallet_mut(wallet)?;
+        if sender.lamports < needed {
+            return Err(PaperError::InsufficientFunds { needed, available: sender.lamports })
+        }
+        sender.lamports -= needed;
+        self.credit_sol(receiver, lamports);
+
+        Ok(self.next_signature())
+    }
+
+    pub fn transfer_sol_from_main(&mut self, receiver: &Pubkey, lamports: u64) -> Result<Signature, PaperError> {
+        let needed = lamports + NETWORK_FEE_LAMPORTS;
+        if self.lamports < needed {
+            return Err(PaperError::InsufficientFunds { needed, available: self.lamports })
+        }
+        self.lamports -= needed;
+        self.credit_sol(receiver, lamports);
+
+        Ok(self.next_signature())
+    }
+
+    /// Moves tokens between snipers, tokens sent outside the ledger leave the simulation.
+    pub fn transfer_tokens_from_sniper(&mut self, wallet: &Pubkey, mint: &Pubkey, receiver: &Pubkey, amount: u64) -> Result<Signature, PaperError> {
+        let sender = self.wallet_mut(wallet)?;
+        let available = sender.token_balance(mint);
+        if available < amount {
+            return Err(PaperError::InsufficientTokens { needed: amount, available })
+        }
+        if sender.lamports < NETWORK_FEE_LAMPORTS {
+            return Err(PaperError::InsufficientFunds { needed: NETWORK_FEE_LAMPORTS, available: sender.lamports })
+        }
+        sender.lamports -= NETWORK_FEE_LAMPORTS;
+        sender.tokens.insert(mint.to_string(), available - amount);
+
+        if let Ok(receiver) = self.wallet_mut(receiver) {
+            *receiver.tokens.entry(mint.to_string()).or_default() += amount;
+        }
+
+        Ok(self.next_signature())
+    }
+
+    /// Arms a snipe whose deployer launches `launch_delay` seconds after `now`.
+    pub fn arm_snipe(
+        &mut self,
+        deployer: Pubkey,
+        wallets: Vec<Pubkey>,
+        max_lamports_per_wallet: Option<u64>,
+        max_total_lamports: Option<u64>,
+        now: u64
+    ) -> Result<(), PaperError> {
+        if self.snipes.iter().any(|snipe| snipe.deployer == deployer) {
+            return Err(PaperError::SnipePending(deployer))
+        }
+
+        self.snipes.push(PaperSnipe {
+            deployer,
+            wallets: wallets.iter().map(|wallet| wallet.to_string()).collect(),
+            max_lamports_per_wallet,
+            max_total_lamports,
+            launch_at: now + self.launch_delay
+        });
+
+        Ok(())
+    }
+
+    pub fn cancel_snipe(&mut self, deployer: &Pubkey) -> bool {
+        let pending = self.snipes.len();
+        self.snipes.retain(|snipe| &snipe.deployer != deployer);
+        self.snipes.len() < pending
+    }
+
+    /// Launches a fresh token for every snipe due at `now` and buys it with the snipe's wallets.
+    /// Without a per-wallet limit a sniper spends its whole balance, the total cap is shared in
+    /// wallet order.
+    pub fn launch_due(&mut self, now: u64) -> Vec<SnipeFill> {
+        let (due, pending): (Vec<PaperSnipe>, Vec<PaperSnipe>) = std::mem::take(&mut self.snipes).into_iter()
+            .partition(|snipe| snipe.launch_at <= now);
+        self.snipes = pending;
+
+        let mut fills = vec![];
+        for snipe in due {
+            self.nonce += 1;
+            let mint = paper_pubkey(MINT_TAG, self.nonce, 0);
+            let name = format!("paper_{}", &snipe.deployer.to_string()[0..5]);
+            let project_id = self.add_project(name, mint, snipe.deployer, BondingCurve::launch()).id;
+
+            let mut budget = snipe.max_total_lamports.unwrap_or(u64::MAX);
+            let mut buys = vec![];
+            for wallet in snipe.wallets.iter().filter_map(|wallet| wallet.parse::<Pubkey>().ok()) {
+                let Some(balance) = self.sol_balance(&wallet) else { continue };
+                let lamports = balance.saturating_sub(NETWORK_FEE_LAMPORTS)
+                    .min(snipe.max_lamports_per_wallet.unwrap_or(u64::MAX))
+                    .min(budget);
+                if lamports == 0 {
+                    continue;
+                }
+
+                if let Ok(signature) = self.buy(project_id, &wallet, lamports) {
+                    let spent = self.costs.get(&signature.to_string()).copied().unwrap_or(lamports);
+                    budget -= spent;
+                    buys.push((wallet, signature, spent));
+                }
+            }
+
+            fills.push(SnipeFill { project_id, deployer: snipe.deployer, buys });
+        }
+
+        fills
+    }
+
+    /// Moves every open curve with one random trade of at most `market_lamports` from other
+    /// traders. The market only sells back tokens it bought, so prices wander around the level
+    /// the snipers' own trades set.
+    pub fn tick(&mut self) {
+        if self.market_lamports == 0 {
+            return
+        }
+
+        for index in 0..self.projects.len() {
+            if self.projects[index].curve.complete {
+                continue;
+            }
+
+            let lamports = self.next_random() % self.market_lamports + 1;
+            let sell = self.next_random() % 2 == 0;
+            let project = &mut self.projects[index];
+
+            let tokens = project.curve.buy_quote(lamports).min(project.market_tokens);
+            if sell && tokens > 0 {
+                project.curve.apply_sell(tokens);
+                project.market_tokens -= tokens;
+            } else {
+                project.market_tokens += project.curve.apply_buy(lamports).0;
+            }
+        }
+    }
+
+    fn tradable_mint(&self, project_id: i32) -> Result<Pubkey, PaperError> {
+        let project = self.project(project_id).ok_or(PaperError::ProjectNotFound(project_id))?;
+        if project.curve.complete {
+            return Err(PaperError::CurveComplete)
+        }
+
+        Ok(project.mint)
+    }
+
+    fn credit_sol(&mut self, receiver: &Pubkey, lamports: u64) {
+        if receiver == &self.fee_payer {
+            self.lamports += lamports;
+        } else if let Ok(wallet) = self.wallet_mut(receiver) {
+            wallet.lamports += lamports;
+        }
+    }
+
+    fn next_signature(&mut self) -> Signature {
+        self.nonce += 1;
+        let mut bytes = [0u8; 64];
+        bytes[0..5].copy_from_slice(b"paper");
+        bytes[56..64].copy_from_slice(&self.nonce.to_le_bytes());
+        Signature::from(bytes)
+    }
+
+    fn next_random(&mut self) -> u64 {
+        // xorshift64, small and stable across platforms
+        let mut value = self.rng;
+        value ^= value << 13;
+        value ^= value >> 7;
+        value ^= value << 17;
+        self.rng = value;
+        value
+    }
+}
+
+fn paper_pubkey(tag: u8, seed: u64, index: u64) -> Pubkey {
+    let mut bytes = [0u8; 32];
+    bytes[0] = tag;
+    bytes[16..24].copy_from_slice(&seed.to_le_bytes());
+    bytes[24..32].copy_from_slice(&index.to_le_bytes());
+    Pubkey::new_from_array(bytes)
+}
+
+pub fn load_ledger() -> Result<Option<PaperLedger>, StorageError> {
+    crate::storage::load_client_document(PAPER_LEDGER_FILE)
+}
+
+pub fn save_ledger(ledger: &PaperLedger) -> Result<(), StorageError> {
+    crate::storage::save_client_document(PAPER_LEDGER_FILE, ledger)
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_sdk::native_token::LAMPORTS_PER_SOL;
+
+    use super::*;
+
+    fn ledger() -> PaperLedger {
+        PaperLedger::new(&PaperConfig { market_lamports: 0, ..PaperConfig::default() })
+    }
+
+    fn launched(ledger: &mut PaperLedger) -> i32 {
+        let mint = paper_pubkey(MINT_TAG, 99, 0);
+        ledger.add_project("test".to_string(), mint, Pubkey::default(), BondingCurve::launch()).id
+    }
+
+    #[test]
+    fn starts_from_configured_balances() {
+        let ledger = ledger();
+
+        assert_eq!(ledger.wallets.len(), 5);
+        assert_eq!(ledger.lamports, 10 * LAMPORTS_PER_SOL);
+        assert_eq!(ledger.total_lamports(), 15 * LAMPORTS_PER_SOL);
+        assert_eq!(ledger.starting_lamports, ledger.total_lamports());
+        assert_eq!(ledger.sol_balance(&ledger.fee_payer), Some(10 * LAMPORTS_PER_SOL));
+    }
+
+    #[test]
+    fn buy_then_sell_records_proceeds() {
+        let mut ledger = ledger();
+        let project_id = launched(&mut ledger);
+        let wallet = ledger.wallets[0].public_key;
+
+        ledger.buy(project_id, &wallet, LAMPORTS_PER_SOL / 2).unwrap();
+        let mint = ledger.project(project_id).unwrap().mint;
+        assert!(ledger.wallet(&wallet).unwrap().token_balance(&mint) > 0);
+        assert_eq!(ledger.wallet(&wallet).unwrap().lamports, LAMPORTS_PER_SOL / 2 - NETWORK_FEE_LAMPORTS);
+
+        let signature = ledger.sell(project_id, &wallet).unwrap();
+        let proceeds = ledger.proceeds[&signature.to_string()];
+        assert!(proceeds > 0 && proceeds < LAMPORTS_PER_SOL / 2);
+        assert_eq!(ledger.wallet(&wallet).unwrap().lamports, LAMPORTS_PER_SOL / 2 - NETWORK_FEE_LAMPORTS + proceeds);
+        assert!(ledger.token_balances(&mint).is_empty());
+    }
+
+    #[test]
+    fn buy_rejects_overspending() {
+        let mut ledger = ledger();
+        let project_id = launched(&mut ledger);
+        let wallet = ledger.wallets[0].public_key;
+
+        let result = ledger.buy(project_id, &wallet, LAMPORTS_PER_SOL);
+        assert!(matches!(result, Err(PaperError::InsufficientFunds { .. })));
+        assert_eq!(ledger.project(project_id).unwrap().curve, BondingCurve::launch());
+    }
+
+    #[test]
+    fn auto_buy_skips_wallets_that_cannot_afford_it() {
+        let mut ledger = ledger();
+        let project_id = launched(&mut ledger);
+        let poor = ledger.wallets[1].public_key;
+        let fee_payer = ledger.fee_payer;
+        ledger.transfer_sol_from_sniper(&poor, &fee_payer, LAMPORTS_PER_SOL / 2).unwrap();
+
+        let signatures = ledger.auto_buy(project_id, LAMPORTS_PER_SOL * 3 / 4).unwrap();
+        let mint = ledger.project(project_id).unwrap().mint;
+        assert_eq!(signatures.len(), 4);
+        assert_eq!(ledger.wallet(&poor).unwrap().token_balance(&mint), 0);
+
+        assert_eq!(ledger.auto_sell(project_id).unwrap().len(), 4);
+        assert!(matches!(ledger.auto_sell(project_id), Err(PaperError::NoTokens)));
+    }
+
+    #[test]
+    fn auto_buy_keeps_the_buys_made_before_the_curve_completes() {
+        let mut ledger = PaperLedger::new(&PaperConfig { wallet_lamports: 100 * LAMPORTS_PER_SOL, market_lamports: 0, ..PaperConfig::default() });
+        let project_id = launched(&mut ledger);
+        let last_buyer = ledger.wallets[1].public_key;
+
+        let signatures = ledger.auto_buy(project_id, 60 * LAMPORTS_PER_SOL).unwrap();
+        assert_eq!(signatures.len(), 2);
+        assert!(ledger.project(project_id).unwrap().curve.complete);
+
+        // The second buy only paid for the tokens that were left
+        let spent = ledger.costs[&signatures[1].to_string()];
+        assert!(spent < 30 * LAMPORTS_PER_SOL);
+        assert_eq!(ledger.wallet(&last_buyer).unwrap().lamports, 100 * LAMPORTS_PER_SOL - spent - NETWORK_FEE_LAMPORTS);
+        assert_eq!(ledger.signers[&signatures[1].to_string()], last_buyer.to_string());
+
+        assert!(matches!(ledger.auto_buy(project_id, LAMPORTS_PER_SOL), Err(PaperError::CurveComplete)));
+    }
+
+    #[test]
+    fn transfers_move_balances_inside_the_ledger() {
+        let mut ledger = ledger();
+        let (first, second) = (ledger.wallets[0].public_key, ledger.wallets[1].public_key);
+
+        ledger.transfer_sol_from_main(&first, LAMPORTS_PER_SOL).unwrap();
+        assert_eq!(ledger.lamports, 9 * LAMPORTS_PER_SOL - NETWORK_FEE_LAMPORTS);
+        assert_eq!(ledger.wallet(&first).unwrap().lamports, 2 * LAMPORTS_PER_SOL);
+
+        let project_id = launched(&mut ledger);
+        let mint = ledger.project(project_id).unwrap().mint;
+        ledger.buy(project_id, &first, LAMPORTS_PER_SOL).unwrap();
+        let tokens = ledger.wallet(&first).unwrap().token_balance(&mint);
+        ledger.transfer_tokens_from_sniper(&first, &mint, &second, tokens / 2).unwrap();
+        assert_eq!(ledger.wallet(&second).unwrap().token_balance(&mint), tokens / 2);
+        assert_eq!(ledger.wallet(&first).unwrap().token_balance(&mint), tokens - tokens / 2);
+
+        let external = Pubkey::new_unique();
+        let before = ledger.total_lamports();
+        ledger.transfer_sol_from_sniper(&second, &external, LAMPORTS_PER_SOL / 2).unwrap();
+        assert_eq!(ledger.total_lamports(), before - LAMPORTS_PER_SOL / 2 - NETWORK_FEE_LAMPORTS);
+    }
+
+    #[test]
+    fn snipes_launch_after_the_delay_within_their_caps() {
+        let mut ledger = ledger();
+        let deployer = Pubkey::new_unique();
+        let wallets: Vec<Pubkey> = ledger.wallets.iter().take(3).map(|wallet| wallet.public_key).collect();
+
+        ledger.arm_snipe(deployer, wallets.clone(), Some(LAMPORTS_PER_SOL / 2), Some(LAMPORTS_PER_SOL), 100).unwrap();
+        assert!(matches!(ledger.arm_snipe(deployer, vec![], None, None, 100), Err(PaperError::SnipePending(_))));
+        assert!(ledger.launch_due(119).is_empty());
+
+        let fills = ledger.launch_due(120);
+        assert_eq!(fills.len(), 1);
+        assert_eq!(fills[0].deployer, deployer);
+        assert_eq!(fills[0].buys.iter().map(|buy| buy.2).collect::<Vec<_>>(), vec![LAMPORTS_PER_SOL / 2, LAMPORTS_PER_SOL / 2]);
+        assert!(ledger.snipes.is_empty());
+
+        let project = ledger.project(fills[0].project_id).unwrap();
+        assert_eq!(project.deployer, deployer);
+        assert_eq!(ledger.token_balances(&project.mint).len(), 2);
+    }
+
+    #[test]
+    fn cancelled_snipes_never_launch() {
+        let mut ledger = ledger();
+        let deployer = Pubkey::new_unique();
+        ledger.arm_snipe(deployer, vec![ledger.wallets[0].public_key], None, None, 0).unwrap();
+
+        assert!(ledger.cancel_snipe(&deployer));
+        assert!(ledger.launch_due(u64::MAX).is_empty());
+        assert!(ledger.projects.is_empty());
+    }
+
+    #[test]
+    fn market_ticks_are_deterministic() {
+        let config = PaperConfig { seed: 7, ..PaperConfig::default() };
+        let mut first = PaperLedger::new(&config);
+        let mut second = PaperLedger::new(&config);
+        launched(&mut first);
+        launched(&mut second);
+
+        for _ in 0..50 {
+            first.tick();
+            second.tick();
+        }
+
+        assert_eq!(first.projects[0].curve, second.projects[0].curve);
+        assert_ne!(first.projects[0].curve, BondingCurve::launch());
+
+        // Whatever the market holds it bought from the curve
+        let sold = crate::chain::pumpfun::INITIAL_REAL_TOKEN_RESERVES - first.projects[0].curve.real_token_reserves;
+        assert_eq!(first.projects[0].market_tokens, sold);
+    }
+
+    #[test]
+    fn signatures_are_unique() {
+        let mut ledger = ledger();
+        let project_id = launched(&mut ledger);
+        let signatures = ledger.auto_buy(project_id, LAMPORTS_PER_SOL / 10).unwrap();
+
+        let unique: std::collections::HashSet<String> = signatures.iter().map(|signature| signature.to_string()).collect();
+        assert_eq!(unique.len(), signatures.len());
+    }
+}
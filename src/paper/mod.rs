@@ -0,0 +1,85 @@
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::pubkey::Pubkey;
+
+pub mod dispatch;
+pub mod dto;
+pub mod ledger;
+
+/// Starting balances and market behaviour of a fresh paper ledger, read from the environment.
+#[derive(Clone, Debug)]
+pub struct PaperConfig {
+    pub fee_payer_lamports: u64,
+    pub wallet_count: usize,
+    pub wallet_lamports: u64,
+    /// Largest simulated trade other traders make on each market tick, zero keeps prices still.
+    pub market_lamports: u64,
+    /// Seconds between arming a paper snipe and its deployer launching.
+    pub launch_delay: u64,
+    pub seed: u64
+}
+
+impl Default for PaperConfig {
+    fn default() -> Self {
+        Self {
+            fee_payer_lamports: 10 * LAMPORTS_PER_SOL,
+            wallet_count: 5,
+            wallet_lamports: LAMPORTS_PER_SOL,
+            market_lamports: LAMPORTS_PER_SOL / 2,
+            launch_delay: 20,
+            seed: 0
+        }
+    }
+}
+
+impl PaperConfig {
+    /// Overrides the defaults with `MOONBOIS_PAPER_FEE_PAYER_SOL`, `MOONBOIS_PAPER_WALLETS`,
+    /// `MOONBOIS_PAPER_WALLET_SOL`, `MOONBOIS_PAPER_MARKET_SOL`, `MOONBOIS_PAPER_LAUNCH_SECS` and
+    /// `MOONBOIS_PAPER_SEED`. Values that do not parse are ignored, and so is a wallet count of zero
+    /// since the client needs at least one sniper.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let sol = |name: &str, default: u64| std::env::var(name).ok()
+            .and_then(|value| value.parse::<f64>().ok())
+            .filter(|sol| *sol >= 0f64)
+            .map(|sol| (sol * LAMPORTS_PER_SOL as f64) as u64)
+            .unwrap_or(default);
+        let number = |name: &str| std::env::var(name).ok().and_then(|value| value.parse::<u64>().ok());
+
+        Self {
+            fee_payer_lamports: sol("MOONBOIS_PAPER_FEE_PAYER_SOL", defaults.fee_payer_lamports),
+            wallet_count: number("MOONBOIS_PAPER_WALLETS").filter(|count| *count > 0).map(|count| count as usize).unwrap_or(defaults.wallet_count),
+            wallet_lamports: sol("MOONBOIS_PAPER_WALLET_SOL", defaults.wallet_lamports),
+            market_lamports: sol("MOONBOIS_PAPER_MARKET_SOL", defaults.market_lamports),
+            launch_delay: number("MOONBOIS_PAPER_LAUNCH_SECS").unwrap_or(defaults.launch_delay),
+            seed: number("MOONBOIS_PAPER_SEED").unwrap_or(defaults.seed)
+        }
+    }
+}
+
+/// Whether this session trades on paper, set with `--paper` or `MOONBOIS_PAPER=1`.
+pub fn paper_mode_requested(arg: &str) -> bool {
+    std::env::args().skip(1).any(|value| value == arg)
+        || std::env::var("MOONBOIS_PAPER").is_ok_and(|value| value == "1" || value == "true")
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum PaperError {
+    #[error("Wallet {0} is not part of the paper ledger")]
+    WalletNotFound(Pubkey),
+    #[error("Project {0} is not part of the paper ledger")]
+    ProjectNotFound(i32),
+    #[error("Insufficient SOL, {needed} lamports needed and {available} available")]
+    InsufficientFunds { needed: u64, available: u64 },
+    #[error("Insufficient tokens, {needed} needed and {available} available")]
+    InsufficientTokens { needed: u64, available: u64 },
+    #[error("No wallet holds tokens of this project")]
+    NoTokens,
+    #[error("The bonding curve is complete")]
+    CurveComplete,
+    #[error("A paper snipe on {0} is already pending")]
+    SnipePending(Pubkey),
+    #[error("{0} is not available in paper mode")]
+    Unsupported(&'static str),
+    #[error("Unable to build the paper account - {0}")]
+    DtoError(#[from] serde_json::Error)
+}
@@ -0,0 +1,48 @@
+use serde::Deserialize;
+use serde::Serialize;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::pubkey::Pubkey;
+
+use super::StorageError;
+
+static ADDRESS_BOOK_FILE: &str = "address_book.json";
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AddressEntry {
+    pub label: String,
+    #[serde(with = "super::pubkey_string")]
+    pub address: Pubkey
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AddressBook {
+    pub entries: Vec<AddressEntry>,
+    /// Outgoing transfers can only go to saved entries when set.
+    pub allowlist_only: bool,
+    /// Sends at or above this amount need a second confirmation.
+    pub confirm_threshold_lamports: u64
+}
+
+impl Default for AddressBook {
+    fn default() -> Self {
+        Self {
+            entries: vec![],
+            allowlist_only: false,
+            confirm_threshold_lamports: LAMPORTS_PER_SOL
+        }
+    }
+}
+
+impl AddressBook {
+    pub fn label(&self, address: &Pubkey) -> Option<&str> {
+        self.entries.iter().find(|entry| &entry.address == address).map(|entry| entry.label.as_str())
+    }
+}
+
+pub fn load_address_book() -> Result<AddressBook, StorageError> {
+    super::load(ADDRESS_BOOK_FILE)
+}
+
+pub fn save_address_book(address_book: &AddressBook) -> Result<(), StorageError> {
+    super::save(ADDRESS_BOOK_FILE, address_book)
+}
@@ -0,0 +1,25 @@
+use super::StorageError;
+
+pub(super) static ARCHIVE_FILE: &str = "archived_projects.json";
+
+/// Projects hidden from the project browser. They stay on the backend so their trades and
+/// transaction history are kept and the project can be restored.
+pub fn load_archived() -> Result<Vec<i32>, StorageError> {
+    super::load(ARCHIVE_FILE)
+}
+
+pub fn archive_project(project_id: i32) -> Result<(), StorageError> {
+    let mut archived = load_archived()?;
+    if !archived.contains(&project_id) {
+        archived.push(project_id);
+    }
+
+    super::save(ARCHIVE_FILE, &archived)
+}
+
+pub fn restore_project(project_id: i32) -> Result<(), StorageError> {
+    let mut archived = load_archived()?;
+    archived.retain(|archived_id| *archived_id != project_id);
+
+    super::save(ARCHIVE_FILE, &archived)
+}
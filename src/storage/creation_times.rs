@@ -0,0 +1,15 @@
+use std::collections::HashMap;
+
+use super::StorageError;
+
+static CREATION_TIMES_FILE: &str = "creation_times.json";
+
+/// Block time each mint was created at, keyed by mint. It never changes, so each mint only has to
+/// be looked up once.
+pub fn load_creation_times() -> Result<HashMap<String, i64>, StorageError> {
+    super::load(CREATION_TIMES_FILE)
+}
+
+pub fn save_creation_times(creation_times: &HashMap<String, i64>) -> Result<(), StorageError> {
+    super::save(CREATION_TIMES_FILE, creation_times)
+}
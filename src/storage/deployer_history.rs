@@ -0,0 +1,40 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::chain::deployer::DeployedToken;
+
+use super::StorageError;
+
+static DEPLOYER_HISTORY_FILE: &str = "deployer_history.json";
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct DeployerHistory {
+    pub tokens: Vec<DeployedToken>,
+    pub newest_signature: Option<String>,
+    pub oldest_signature: Option<String>,
+    /// Stretches between two scans that were left out because a refresh hit the scan limit.
+    pub gaps: Vec<HistoryGap>,
+    pub updated_at: u64
+}
+
+/// Signatures older than `before` and newer than `until` that were never scanned.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct HistoryGap {
+    pub before: String,
+    pub until: String
+}
+
+pub fn load_deployer_history(deployer: &Pubkey) -> Result<Option<DeployerHistory>, StorageError> {
+    let mut histories: HashMap<String, DeployerHistory> = super::load(DEPLOYER_HISTORY_FILE)?;
+    Ok(histories.remove(&deployer.to_string()))
+}
+
+pub fn save_deployer_history(deployer: &Pubkey, history: &DeployerHistory) -> Result<(), StorageError> {
+    let mut histories: HashMap<String, DeployerHistory> = super::load(DEPLOYER_HISTORY_FILE)?;
+    histories.insert(deployer.to_string(), history.clone());
+
+    super::save(DEPLOYER_HISTORY_FILE, &histories)
+}
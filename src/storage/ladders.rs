@@ -0,0 +1,205 @@
+use serde::Deserialize;
+use serde::Serialize;
+
+use super::StorageError;
+
+pub(super) static SELL_LADDERS_FILE: &str = "sell_ladders.json";
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum TrancheTrigger {
+    /// Runs `secs` after the previous tranche, or after the ladder started for the first one.
+    Delay { secs: u64 },
+    /// Runs once the curve price of one whole token reaches `price` SOL.
+    Price { price: f64 }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum TrancheOutcome {
+    Sold { signatures: Vec<String> },
+    Failed { error: String, signatures: Vec<String> }
+}
+
+impl ToString for TrancheOutcome {
+    fn to_string(&self) -> String {
+        match self {
+            Self::Sold { signatures } => format!("sold - {} transactions", signatures.len()),
+            Self::Failed { error, .. } => format!("failed - {}", error)
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Tranche {
+    /// Share of the position when the ladder started, in percent.
+    pub percent: f64,
+    pub trigger: TrancheTrigger,
+    pub executed_at: Option<u64>,
+    pub outcome: Option<TrancheOutcome>
+}
+
+impl Tranche {
+    pub fn new(percent: f64, trigger: TrancheTrigger) -> Self {
+        Self { percent, trigger, executed_at: None, outcome: None }
+    }
+
+    pub fn is_sold(&self) -> bool {
+        matches!(self.outcome, Some(TrancheOutcome::Sold { .. }))
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum LadderStatus {
+    Running,
+    Paused,
+    Completed,
+    Cancelled
+}
+
+impl ToString for LadderStatus {
+    fn to_string(&self) -> String {
+        match self {
+            Self::Running => "running".to_string(),
+            Self::Paused => "paused".to_string(),
+            Self::Completed => "completed".to_string(),
+            Self::Cancelled => "cancelled".to_string()
+        }
+    }
+}
+
+/// An exit split into tranches, one ladder per project.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SellLadder {
+    pub project_id: i32,
+    pub mint: String,
+    pub created_at: u64,
+    pub status: LadderStatus,
+    pub tranches: Vec<Tranche>
+}
+
+impl SellLadder {
+    pub fn is_finished(&self) -> bool {
+        matches!(self.status, LadderStatus::Completed | LadderStatus::Cancelled)
+    }
+
+    /// The first tranche not sold yet, a failed tranche is retried.
+    pub fn next_tranche(&self) -> Option<usize> {
+        self.tranches.iter().position(|tranche| !tranche.is_sold())
+    }
+
+    pub fn sold_count(&self) -> usize {
+        self.tranches.iter().filter(|tranche| tranche.is_sold()).count()
+    }
+
+    pub fn is_due(&self, index: usize, now: u64, price: Option<f64>) -> bool {
+        match self.tranches[index].trigger {
+            TrancheTrigger::Delay { secs } => {
+                let previous = self.tranches[..index].iter().rev()
+                    .find_map(|tranche| tranche.executed_at)
+                    .unwrap_or(self.created_at);
+                now >= previous + secs
+            },
+            TrancheTrigger::Price { price: level } => price.is_some_and(|price| price >= level)
+        }
+    }
+
+    /// Share of the current position the tranche at `index` sells, its percent of what the
+    /// tranches left to sell add up to.
+    pub fn sell_share(&self, index: usize) -> f64 {
+        let remaining: f64 = self.tranches.iter()
+            .filter(|tranche| !tranche.is_sold())
+            .map(|tranche| tranche.percent)
+            .sum();
+
+        if remaining <= 0.0 {
+            return 1.0
+        }
+
+        (self.tranches[index].percent / remaining).min(1.0)
+    }
+}
+
+pub fn load_ladders() -> Result<Vec<SellLadder>, StorageError> {
+    super::load(SELL_LADDERS_FILE)
+}
+
+pub fn save_ladder(ladder: &SellLadder) -> Result<(), StorageError> {
+    let mut ladders = load_ladders()?;
+    ladders.retain(|saved| saved.project_id != ladder.project_id);
+    ladders.push(ladder.clone());
+
+    super::save(SELL_LADDERS_FILE, &ladders)
+}
+
+pub fn remove_ladder(project_id: i32) -> Result<(), StorageError> {
+    let mut ladders = load_ladders()?;
+    ladders.retain(|saved| saved.project_id != project_id);
+
+    super::save(SELL_LADDERS_FILE, &ladders)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ladder(tranches: Vec<Tranche>) -> SellLadder {
+        SellLadder { project_id: 1, mint: "mint".to_string(), created_at: 100, status: LadderStatus::Running, tranches }
+    }
+
+    fn sold(mut tranche: Tranche, executed_at: u64) -> Tranche {
+        tranche.executed_at = Some(executed_at);
+        tranche.outcome = Some(TrancheOutcome::Sold { signatures: vec![] });
+        tranche
+    }
+
+    fn failed(mut tranche: Tranche, executed_at: u64) -> Tranche {
+        tranche.executed_at = Some(executed_at);
+        tranche.outcome = Some(TrancheOutcome::Failed { error: "error".to_string(), signatures: vec![] });
+        tranche
+    }
+
+    fn delay(percent: f64, secs: u64) -> Tranche {
+        Tranche::new(percent, TrancheTrigger::Delay { secs })
+    }
+
+    #[test]
+    fn next_tranche_retries_failed_tranches() {
+        let ladder = ladder(vec![sold(delay(25.0, 10), 110), failed(delay(25.0, 10), 120), delay(50.0, 10)]);
+        assert_eq!(ladder.next_tranche(), Some(1));
+        assert_eq!(ladder.sold_count(), 1);
+
+        let ladder = SellLadder { tranches: ladder.tranches.iter().cloned().map(|tranche| sold(tranche, 130)).collect(), ..ladder };
+        assert_eq!(ladder.next_tranche(), None);
+    }
+
+    #[test]
+    fn delays_count_from_the_last_executed_tranche() {
+        let fresh = ladder(vec![delay(50.0, 30), delay(50.0, 60)]);
+        assert!(!fresh.is_due(0, 129, None));
+        assert!(fresh.is_due(0, 130, None));
+
+        let started = ladder(vec![sold(delay(50.0, 30), 140), delay(50.0, 60)]);
+        assert!(!started.is_due(1, 199, None));
+        assert!(started.is_due(1, 200, None));
+    }
+
+    #[test]
+    fn price_tranches_need_a_price_at_the_level() {
+        let ladder = ladder(vec![Tranche::new(100.0, TrancheTrigger::Price { price: 0.002 })]);
+
+        assert!(!ladder.is_due(0, 1_000, None));
+        assert!(!ladder.is_due(0, 1_000, Some(0.0019)));
+        assert!(ladder.is_due(0, 1_000, Some(0.002)));
+    }
+
+    #[test]
+    fn sell_share_is_relative_to_what_is_left() {
+        let fresh = ladder(vec![delay(25.0, 0), delay(25.0, 0), delay(50.0, 0)]);
+        assert_eq!(fresh.sell_share(0), 0.25);
+
+        let started = ladder(vec![sold(delay(25.0, 0), 100), delay(25.0, 0), delay(50.0, 0)]);
+        assert!((started.sell_share(1) - 1.0 / 3.0).abs() < 1e-9);
+
+        let last = ladder(vec![sold(delay(25.0, 0), 100), sold(delay(25.0, 0), 100), delay(50.0, 0)]);
+        assert_eq!(last.sell_share(2), 1.0);
+    }
+}
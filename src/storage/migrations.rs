@@ -0,0 +1,24 @@
+use solana_sdk::pubkey::Pubkey;
+
+use super::StorageError;
+
+pub(super) static MIGRATIONS_FILE: &str = "migrations.json";
+
+/// Mints whose bonding curve completed and migrated off pump.fun.
+pub fn is_migrated(mint_id: &Pubkey) -> Result<bool, StorageError> {
+    let migrated: Vec<String> = super::load(MIGRATIONS_FILE)?;
+    Ok(migrated.contains(&mint_id.to_string()))
+}
+
+pub fn load_migrated() -> Result<Vec<String>, StorageError> {
+    super::load(MIGRATIONS_FILE)
+}
+
+pub fn mark_migrated(mint_id: &Pubkey) -> Result<(), StorageError> {
+    let mut migrated: Vec<String> = super::load(MIGRATIONS_FILE)?;
+    if !migrated.contains(&mint_id.to_string()) {
+        migrated.push(mint_id.to_string());
+    }
+
+    super::save(MIGRATIONS_FILE, &migrated)
+}
@@ -0,0 +1,222 @@
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::RwLock;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+pub mod address_book;
+pub mod archive;
+pub mod creation_times;
+pub mod deployer_history;
+pub mod ladders;
+pub mod migrations;
+pub mod presets;
+pub mod risk;
+pub mod snipes;
+pub mod tickers;
+pub mod token_checks;
+pub mod trade_rules;
+pub mod transactions;
+
+static PAPER_DATA_DIR: AtomicBool = AtomicBool::new(false);
+static USER_DATA_DIR: RwLock<Option<i32>> = RwLock::new(None);
+
+/// Moves every local file into the `paper` folder of the data dir, so paper trading keeps its own
+/// transactions, snipes, rules and ladders. Set once at startup before anything is loaded.
+pub fn use_paper_data_dir() {
+    PAPER_DATA_DIR.store(true, Ordering::Relaxed);
+}
+
+/// Moves the documents of an account into `users/<user_id>` of the data dir, so accounts sharing a
+/// machine never see each other's snipes, projects or history. Set on every login.
+pub fn use_user_data_dir(user_id: i32) {
+    *USER_DATA_DIR.write().unwrap_or_else(|err| err.into_inner()) = Some(user_id);
+}
+
+/// Directory holding the logged in account's local state. Defaults to `%APPDATA%\moonbois` on
+/// windows and `~/.moonbois` elsewhere, and can be overridden with `MOONBOIS_DATA_DIR`.
+pub fn data_dir() -> PathBuf {
+    match *USER_DATA_DIR.read().unwrap_or_else(|err| err.into_inner()) {
+        Some(user_id) => client_data_dir().join("users").join(user_id.to_string()),
+        None => client_data_dir()
+    }
+}
+
+/// Directory of the documents that belong to the client rather than an account, like the paper
+/// ledger that is loaded before anyone logs in.
+fn client_data_dir() -> PathBuf {
+    if PAPER_DATA_DIR.load(Ordering::Relaxed) {
+        return base_data_dir().join("paper");
+    }
+
+    base_data_dir()
+}
+
+fn base_data_dir() -> PathBuf {
+    #[cfg(test)]
+    if let Some(data_dir) = TEST_DATA_DIR.with(|data_dir| data_dir.borrow().clone()) {
+        return data_dir;
+    }
+
+    if let Ok(data_dir) = std::env::var("MOONBOIS_DATA_DIR") {
+        return PathBuf::from(data_dir);
+    }
+
+    #[cfg(windows)]
+    let base = std::env::var("APPDATA").map(PathBuf::from).unwrap_or_else(|_| PathBuf::from("."));
+
+    #[cfg(not(windows))]
+    let base = std::env::var("HOME").map(PathBuf::from).unwrap_or_else(|_| PathBuf::from("."));
+
+    #[cfg(windows)]
+    return base.join("moonbois");
+
+    #[cfg(not(windows))]
+    return base.join(".moonbois");
+}
+
+#[cfg(test)]
+thread_local! {
+    static TEST_DATA_DIR: std::cell::RefCell<Option<PathBuf>> = const { std::cell::RefCell::new(None) };
+}
+
+/// Points the data dir of the current thread at a fresh temporary folder until dropped. Tests run
+/// on their own threads, so they can touch storage in parallel without sharing files.
+#[cfg(test)]
+pub struct TestDataDir(PathBuf);
+
+#[cfg(test)]
+impl TestDataDir {
+    pub fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("moonbois-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        TEST_DATA_DIR.with(|data_dir| *data_dir.borrow_mut() = Some(path.clone()));
+
+        Self(path)
+    }
+}
+
+#[cfg(test)]
+impl Drop for TestDataDir {
+    fn drop(&mut self) {
+        TEST_DATA_DIR.with(|data_dir| *data_dir.borrow_mut() = None);
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Loads a JSON document from the data dir, falling back to the default when it does not exist yet.
+pub fn load<T: DeserializeOwned + Default>(name: &str) -> Result<T, StorageError> {
+    load_from(data_dir(), name)
+}
+
+pub fn save<T: Serialize>(name: &str, value: &T) -> Result<(), StorageError> {
+    save_to(data_dir(), name, value)
+}
+
+/// `load` for documents of the client, see `client_data_dir`.
+pub fn load_client_document<T: DeserializeOwned + Default>(name: &str) -> Result<T, StorageError> {
+    load_from(client_data_dir(), name)
+}
+
+pub fn save_client_document<T: Serialize>(name: &str, value: &T) -> Result<(), StorageError> {
+    save_to(client_data_dir(), name, value)
+}
+
+fn load_from<T: DeserializeOwned + Default>(data_dir: PathBuf, name: &str) -> Result<T, StorageError> {
+    let path = data_dir.join(name);
+    if !path.exists() {
+        return Ok(T::default());
+    }
+
+    let contents = fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&contents)?)
+}
+
+fn save_to<T: Serialize>(data_dir: PathBuf, name: &str, value: &T) -> Result<(), StorageError> {
+    fs::create_dir_all(&data_dir)?;
+
+    let path = data_dir.join(name);
+    let tmp_path = data_dir.join(format!("{}.tmp", name));
+    fs::write(&tmp_path, serde_json::to_string_pretty(value)?)?;
+    fs::rename(tmp_path, path)?;
+
+    Ok(())
+}
+
+/// Deletes a document from the data dir, documents that do not exist are skipped.
+pub fn remove(name: &str) -> Result<(), StorageError> {
+    match fs::remove_file(data_dir().join(name)) {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(err.into()),
+        _ => Ok(())
+    }
+}
+
+/// Documents that follow the trading of an account rather than its settings, the risk policy,
+/// presets, deployers and address book are kept when these are cleared.
+pub fn trading_state_files() -> [&'static str; 7] {
+    [
+        transactions::TRANSACTIONS_FILE,
+        snipes::SNIPES_FILE,
+        ladders::SELL_LADDERS_FILE,
+        trade_rules::TRADE_RULES_FILE,
+        trade_rules::TRADE_RULE_LOG_FILE,
+        archive::ARCHIVE_FILE,
+        migrations::MIGRATIONS_FILE
+    ]
+}
+
+/// Writes rows as CSV into the `exports` folder of the data dir and returns the file path.
+pub fn export_csv(name: &str, header: &[&str], rows: &[Vec<String>]) -> Result<PathBuf, StorageError> {
+    let export_dir = data_dir().join("exports");
+    fs::create_dir_all(&export_dir)?;
+
+    let escape = |field: &str| -> String {
+        if field.contains([',', '"', '\n']) {
+            format!("\"{}\"", field.replace('"', "\"\""))
+        } else {
+            field.to_string()
+        }
+    };
+
+    let mut contents = header.iter().map(|field| escape(field)).collect::<Vec<_>>().join(",");
+    contents.push('\n');
+    for row in rows {
+        contents.push_str(&row.iter().map(|field| escape(field)).collect::<Vec<_>>().join(","));
+        contents.push('\n');
+    }
+
+    let path = export_dir.join(name);
+    fs::write(&path, contents)?;
+
+    Ok(path)
+}
+
+/// Serializes a `Pubkey` as its base58 string so local files stay readable.
+pub mod pubkey_string {
+    use std::str::FromStr;
+
+    use serde::Deserialize;
+    use serde::Deserializer;
+    use serde::Serializer;
+    use solana_sdk::pubkey::Pubkey;
+
+    pub fn serialize<S: Serializer>(pubkey: &Pubkey, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&pubkey.to_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Pubkey, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        Pubkey::from_str(&value).map_err(serde::de::Error::custom)
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum StorageError {
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    JsonError(#[from] serde_json::Error)
+}
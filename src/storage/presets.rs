@@ -0,0 +1,70 @@
+use serde::Deserialize;
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+
+use super::snipes::SnipeLimits;
+use super::StorageError;
+
+static PRESETS_FILE: &str = "snipe_presets.json";
+static DEPLOYERS_FILE: &str = "deployers.json";
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub enum WalletSelection {
+    Count(usize),
+    /// Base58 public keys of the wallets to snipe with.
+    Wallets(Vec<String>)
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SnipePreset {
+    pub name: String,
+    pub wallets: WalletSelection,
+    pub limits: SnipeLimits
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DeployerEntry {
+    pub label: String,
+    #[serde(with = "super::pubkey_string")]
+    pub address: Pubkey
+}
+
+pub fn load_presets() -> Result<Vec<SnipePreset>, StorageError> {
+    super::load(PRESETS_FILE)
+}
+
+pub fn save_preset(preset: &SnipePreset) -> Result<(), StorageError> {
+    let mut presets = load_presets()?;
+    presets.retain(|existing| existing.name != preset.name);
+    presets.push(preset.clone());
+    presets.sort_by(|a, b| a.name.cmp(&b.name));
+
+    super::save(PRESETS_FILE, &presets)
+}
+
+pub fn remove_preset(name: &str) -> Result<(), StorageError> {
+    let mut presets = load_presets()?;
+    presets.retain(|preset| preset.name != name);
+
+    super::save(PRESETS_FILE, &presets)
+}
+
+pub fn load_deployers() -> Result<Vec<DeployerEntry>, StorageError> {
+    super::load(DEPLOYERS_FILE)
+}
+
+pub fn save_deployer(entry: &DeployerEntry) -> Result<(), StorageError> {
+    let mut deployers = load_deployers()?;
+    deployers.retain(|existing| existing.address != entry.address);
+    deployers.push(entry.clone());
+    deployers.sort_by(|a, b| a.label.cmp(&b.label));
+
+    super::save(DEPLOYERS_FILE, &deployers)
+}
+
+pub fn remove_deployer(address: &Pubkey) -> Result<(), StorageError> {
+    let mut deployers = load_deployers()?;
+    deployers.retain(|entry| &entry.address != address);
+
+    super::save(DEPLOYERS_FILE, &deployers)
+}
@@ -0,0 +1,251 @@
+use serde::Deserialize;
+use serde::Serialize;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::pubkey::Pubkey;
+
+use super::snipes::SnipeLimits;
+use super::transactions::TransactionRecord;
+use super::StorageError;
+
+static RISK_POLICY_FILE: &str = "risk_policy.json";
+static ROLLING_WINDOW_SECS: u64 = 60 * 60 * 24;
+/// Recorded actions that move SOL out of our control and count towards the rolling limits.
+static OUTFLOW_ACTIONS: [&str; 4] = ["buy", "auto_buy", "snipe", "send"];
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum SpendKind {
+    Trade,
+    AutoTrade,
+    Snipe,
+    Send,
+    Deposit,
+    Withdraw
+}
+
+/// A state-changing action about to be sent to the backend.
+pub struct Spend {
+    pub kind: SpendKind,
+    /// Total lamports across every wallet involved.
+    pub lamports: u64,
+    /// Lamports coming out of each sniper involved, for the per-wallet limit.
+    pub wallets: Vec<(Pubkey, u64)>
+}
+
+/// Local spending limits, every limit is off while `None`.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct RiskPolicy {
+    /// Max SOL for a single buy, send, deposit or withdraw.
+    pub max_trade_lamports: Option<u64>,
+    /// Max SOL for an auto trade or snipe across all wallets.
+    pub max_auto_trade_lamports: Option<u64>,
+    /// Max SOL a single wallet may spend in a rolling 24h.
+    pub max_wallet_lamports: Option<u64>,
+    /// Max SOL spent across all wallets in a rolling 24h.
+    pub max_daily_lamports: Option<u64>,
+    /// Max share of the fee payer balance a single action may use, between 0 and 1.
+    pub max_fee_payer_share: Option<f64>
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum RiskViolation {
+    #[error("{} SOL exceeds the max trade limit of {} SOL", sol(*.0), sol(*.1))]
+    MaxTrade(u64, u64),
+    #[error("{} SOL across all wallets exceeds the max auto trade limit of {} SOL", sol(*.0), sol(*.1))]
+    MaxAutoTrade(u64, u64),
+    #[error("snipes need a total or per wallet spend cap while the auto trade or daily limit is set")]
+    UncappedSnipe,
+    #[error("wallet {} would spend {} SOL in 24h, over the per wallet limit of {} SOL", &.0.to_string()[0..5], sol(*.1), sol(*.2))]
+    MaxWallet(Pubkey, u64, u64),
+    #[error("{} SOL spent in 24h would exceed the daily limit of {} SOL", sol(*.0), sol(*.1))]
+    MaxDaily(u64, u64),
+    #[error("{} SOL is more than {}% of the fee payer balance of {} SOL", sol(*.0), .1 * 100.0, sol(*.2))]
+    FeePayerShare(u64, f64, u64)
+}
+
+fn sol(lamports: u64) -> f64 {
+    lamports as f64 / LAMPORTS_PER_SOL as f64
+}
+
+impl RiskPolicy {
+    /// Checks `spend` against every limit. `records` is the local transaction history used for
+    /// the rolling limits.
+    pub fn check(&self, spend: &Spend, records: &[TransactionRecord], fee_payer_balance: u64, now: u64) -> Result<(), RiskViolation> {
+        match spend.kind {
+            SpendKind::Trade | SpendKind::Send | SpendKind::Deposit | SpendKind::Withdraw => {
+                if let Some(max) = self.max_trade_lamports.filter(|max| spend.lamports > *max) {
+                    return Err(RiskViolation::MaxTrade(spend.lamports, max))
+                }
+            },
+            SpendKind::AutoTrade | SpendKind::Snipe => {
+                if let Some(max) = self.max_auto_trade_lamports.filter(|max| spend.lamports > *max) {
+                    return Err(RiskViolation::MaxAutoTrade(spend.lamports, max))
+                }
+            }
+        }
+
+        if matches!(spend.kind, SpendKind::Deposit | SpendKind::Withdraw) {
+            return self.check_fee_payer_share(spend, fee_payer_balance)
+        }
+
+        let window: Vec<&TransactionRecord> = records.iter()
+            .filter(|record| now.saturating_sub(record.timestamp) < ROLLING_WINDOW_SECS)
+            .filter(|record| record.succeeded() && OUTFLOW_ACTIONS.contains(&record.action.as_str()))
+            .collect();
+
+        if let Some(max) = self.max_wallet_lamports {
+            for (wallet, lamports) in spend.wallets.iter() {
+                let wallet_key = wallet.to_string();
+                let spent: u64 = window.iter()
+                    .filter(|record| record.wallet.as_deref() == Some(wallet_key.as_str()))
+                    .filter_map(|record| record.lamports)
+                    .sum();
+                if spent.saturating_add(*lamports) > max {
+                    return Err(RiskViolation::MaxWallet(*wallet, spent.saturating_add(*lamports), max))
+                }
+            }
+        }
+
+        if let Some(max) = self.max_daily_lamports {
+            let spent: u64 = window.iter().filter_map(|record| record.lamports).sum();
+            if spent.saturating_add(spend.lamports) > max {
+                return Err(RiskViolation::MaxDaily(spent.saturating_add(spend.lamports), max))
+            }
+        }
+
+        self.check_fee_payer_share(spend, fee_payer_balance)
+    }
+
+    /// Snipes spend whatever their caps allow, so the worst case is checked. `snipers` are the
+    /// wallets the snipe may buy with, each is checked as if it spent its full cap.
+    pub fn check_snipe(&self, wallet_count: usize, snipers: &[Pubkey], limits: &SnipeLimits, records: &[TransactionRecord], fee_payer_balance: u64, now: u64) -> Result<(), RiskViolation> {
+        let per_wallet_total = limits.max_lamports_per_wallet.map(|lamports| lamports.saturating_mul(wallet_count as u64));
+        let lamports = match (limits.max_total_lamports, per_wallet_total) {
+            (Some(total), Some(per_wallet_total)) => total.min(per_wallet_total),
+            (Some(total), None) => total,
+            (None, Some(per_wallet_total)) => per_wallet_total,
+            (None, None) if self.max_auto_trade_lamports.is_some() || self.max_daily_lamports.is_some() => {
+                return Err(RiskViolation::UncappedSnipe)
+            },
+            (None, None) => return Ok(())
+        };
+
+        let per_wallet = limits.max_lamports_per_wallet.map_or(lamports, |max| max.min(lamports));
+        let wallets = snipers.iter().map(|wallet| (*wallet, per_wallet)).collect();
+
+        self.check(&Spend { kind: SpendKind::Snipe, lamports, wallets }, records, fee_payer_balance, now)
+    }
+
+    fn check_fee_payer_share(&self, spend: &Spend, fee_payer_balance: u64) -> Result<(), RiskViolation> {
+        if spend.kind == SpendKind::Withdraw {
+            return Ok(())
+        }
+
+        match self.max_fee_payer_share {
+            Some(share) if spend.lamports as f64 > fee_payer_balance as f64 * share => {
+                Err(RiskViolation::FeePayerShare(spend.lamports, share, fee_payer_balance))
+            },
+            _ => Ok(())
+        }
+    }
+}
+
+pub fn load_risk_policy() -> Result<RiskPolicy, StorageError> {
+    super::load(RISK_POLICY_FILE)
+}
+
+pub fn save_risk_policy(policy: &RiskPolicy) -> Result<(), StorageError> {
+    super::save(RISK_POLICY_FILE, policy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain::confirm::ConfirmationStatus;
+
+    static NOW: u64 = 1_000_000;
+
+    fn record(action: &str, wallet: Pubkey, lamports: u64, age: u64) -> TransactionRecord {
+        TransactionRecord {
+            signature: String::new(),
+            action: action.to_string(),
+            timestamp: NOW - age,
+            status: ConfirmationStatus::Confirmed,
+            project_id: Some(1),
+            wallet: Some(wallet.to_string()),
+            lamports: Some(lamports)
+        }
+    }
+
+    fn trade(wallet: Pubkey, lamports: u64) -> Spend {
+        Spend { kind: SpendKind::Trade, lamports, wallets: vec![(wallet, lamports)] }
+    }
+
+    #[test]
+    fn daily_limit_only_counts_outflows_inside_the_window() {
+        let policy = RiskPolicy { max_daily_lamports: Some(LAMPORTS_PER_SOL), ..RiskPolicy::default() };
+        let wallet = Pubkey::new_unique();
+        let mut failed = record("buy", wallet, LAMPORTS_PER_SOL, 10);
+        failed.status = ConfirmationStatus::Failed { error: "error".to_string(), logs: vec![] };
+        let records = vec![
+            record("buy", wallet, LAMPORTS_PER_SOL / 2, ROLLING_WINDOW_SECS + 1),
+            record("sell", wallet, LAMPORTS_PER_SOL, 10),
+            failed,
+            record("snipe", wallet, LAMPORTS_PER_SOL / 4, 10)
+        ];
+
+        assert!(policy.check(&trade(wallet, LAMPORTS_PER_SOL * 3 / 4), &records, u64::MAX, NOW).is_ok());
+        assert!(matches!(
+            policy.check(&trade(wallet, LAMPORTS_PER_SOL * 3 / 4 + 1), &records, u64::MAX, NOW),
+            Err(RiskViolation::MaxDaily(_, _))
+        ));
+    }
+
+    #[test]
+    fn wallet_limit_checks_every_wallet_of_an_auto_trade() {
+        let policy = RiskPolicy { max_wallet_lamports: Some(LAMPORTS_PER_SOL), ..RiskPolicy::default() };
+        let (fresh, busy) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let records = vec![record("auto_buy", busy, LAMPORTS_PER_SOL * 3 / 4, 10)];
+        let spend = |lamports| Spend {
+            kind: SpendKind::AutoTrade,
+            lamports: lamports * 2,
+            wallets: vec![(fresh, lamports), (busy, lamports)]
+        };
+
+        assert!(policy.check(&spend(LAMPORTS_PER_SOL / 4), &records, u64::MAX, NOW).is_ok());
+        assert!(matches!(
+            policy.check(&spend(LAMPORTS_PER_SOL / 2), &records, u64::MAX, NOW),
+            Err(RiskViolation::MaxWallet(wallet, _, _)) if wallet == busy
+        ));
+    }
+
+    #[test]
+    fn fee_payer_share_skips_withdrawals() {
+        let policy = RiskPolicy { max_fee_payer_share: Some(0.5), ..RiskPolicy::default() };
+        let withdraw = Spend { kind: SpendKind::Withdraw, lamports: LAMPORTS_PER_SOL, wallets: vec![] };
+        let deposit = Spend { kind: SpendKind::Deposit, lamports: LAMPORTS_PER_SOL, wallets: vec![] };
+
+        assert!(policy.check(&withdraw, &[], LAMPORTS_PER_SOL, NOW).is_ok());
+        assert!(policy.check(&deposit, &[], LAMPORTS_PER_SOL * 2, NOW).is_ok());
+        assert!(matches!(
+            policy.check(&deposit, &[], LAMPORTS_PER_SOL * 2 - 1, NOW),
+            Err(RiskViolation::FeePayerShare(_, _, _))
+        ));
+    }
+
+    #[test]
+    fn snipes_check_the_worst_case_of_their_caps() {
+        let policy = RiskPolicy { max_auto_trade_lamports: Some(LAMPORTS_PER_SOL), ..RiskPolicy::default() };
+        let limits = |per_wallet, total| SnipeLimits { max_lamports_per_wallet: per_wallet, max_total_lamports: total, expiry: None };
+
+        assert!(policy.check_snipe(4, &[], &limits(Some(LAMPORTS_PER_SOL / 4), None), &[], u64::MAX, NOW).is_ok());
+        assert!(policy.check_snipe(5, &[], &limits(Some(LAMPORTS_PER_SOL / 4), Some(LAMPORTS_PER_SOL)), &[], u64::MAX, NOW).is_ok());
+        assert!(matches!(
+            policy.check_snipe(usize::MAX, &[], &limits(Some(u64::MAX), None), &[], u64::MAX, NOW),
+            Err(RiskViolation::MaxAutoTrade(u64::MAX, _))
+        ));
+        assert!(matches!(
+            policy.check_snipe(1, &[], &limits(None, None), &[], u64::MAX, NOW),
+            Err(RiskViolation::UncappedSnipe)
+        ));
+    }
+}
@@ -0,0 +1,115 @@
+use serde::Deserialize;
+use serde::Serialize;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::pubkey::Pubkey;
+
+use super::StorageError;
+
+pub(super) static SNIPES_FILE: &str = "snipes.json";
+/// What a snipe buy may spend on top of its cap, the sniper's token account rent and transaction fees.
+static BUY_OVERHEAD_LAMPORTS: u64 = 2_100_000;
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct SnipeLimits {
+    pub max_lamports_per_wallet: Option<u64>,
+    pub max_total_lamports: Option<u64>,
+    /// Seconds after arming before the client cancels the snipe.
+    pub expiry: Option<u64>
+}
+
+/// A snipe armed by this client. Kept until the snipe fills, fails or is cancelled so it can be
+/// reconciled with the backend after a restart.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SnipeRecord {
+    #[serde(with = "super::pubkey_string")]
+    pub deployer: Pubkey,
+    pub wallet_count: usize,
+    pub created_at: u64,
+    /// Unix timestamp after which the client cancels the snipe.
+    pub expires_at: Option<u64>,
+    /// Caps passed to the backend, checked again against the buys once the snipe fills.
+    pub max_lamports_per_wallet: Option<u64>,
+    pub max_total_lamports: Option<u64>,
+    /// Projects from this deployer that already existed when the snipe was armed.
+    pub known_projects: Vec<i32>
+}
+
+impl SnipeRecord {
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.expires_at.map(|expires_at| now >= expires_at).unwrap_or(false)
+    }
+
+    /// Describes how the buys of a filled snipe, as wallet and lamports spent, went over its caps.
+    /// Each buy may also pay the 1% pump.fun fee and `BUY_OVERHEAD_LAMPORTS`.
+    pub fn overspend(&self, buys: &[(Pubkey, u64)]) -> Option<String> {
+        let allowed = |cap: u64, count: u64| cap + cap / 100 + BUY_OVERHEAD_LAMPORTS * count;
+        let mut reasons = vec![];
+
+        if let Some(cap) = self.max_lamports_per_wallet {
+            for (wallet, lamports) in buys.iter().filter(|(_, lamports)| *lamports > allowed(cap, 1)) {
+                reasons.push(format!("{} spent {} SOL over a cap of {} SOL", wallet, *lamports as f64 / LAMPORTS_PER_SOL as f64, cap as f64 / LAMPORTS_PER_SOL as f64));
+            }
+        }
+
+        let total: u64 = buys.iter().map(|(_, lamports)| lamports).sum();
+        if let Some(cap) = self.max_total_lamports.filter(|cap| total > allowed(*cap, buys.len() as u64)) {
+            reasons.push(format!("the snipe spent {} SOL over a total cap of {} SOL", total as f64 / LAMPORTS_PER_SOL as f64, cap as f64 / LAMPORTS_PER_SOL as f64));
+        }
+
+        (!reasons.is_empty()).then(|| reasons.join(", "))
+    }
+}
+
+pub fn load_snipes() -> Result<Vec<SnipeRecord>, StorageError> {
+    super::load(SNIPES_FILE)
+}
+
+pub fn save_snipe(record: &SnipeRecord) -> Result<(), StorageError> {
+    let mut snipes = load_snipes()?;
+    snipes.retain(|snipe| snipe.deployer != record.deployer);
+    snipes.push(record.clone());
+
+    super::save(SNIPES_FILE, &snipes)
+}
+
+pub fn remove_snipe(deployer: &Pubkey) -> Result<(), StorageError> {
+    let mut snipes = load_snipes()?;
+    snipes.retain(|snipe| &snipe.deployer != deployer);
+
+    super::save(SNIPES_FILE, &snipes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(max_lamports_per_wallet: Option<u64>, max_total_lamports: Option<u64>) -> SnipeRecord {
+        SnipeRecord {
+            deployer: Pubkey::new_unique(),
+            wallet_count: 2,
+            created_at: 0,
+            expires_at: None,
+            max_lamports_per_wallet,
+            max_total_lamports,
+            known_projects: vec![]
+        }
+    }
+
+    #[test]
+    fn allows_fees_and_rent_on_top_of_the_caps() {
+        let buys = vec![(Pubkey::new_unique(), 1_000_000_000 + 10_000_000 + BUY_OVERHEAD_LAMPORTS); 2];
+
+        assert!(record(Some(1_000_000_000), Some(2_000_000_000)).overspend(&buys).is_none());
+        assert!(record(None, None).overspend(&buys).is_none());
+    }
+
+    #[test]
+    fn flags_wallets_and_totals_over_their_cap() {
+        let buys = vec![(Pubkey::new_unique(), 500_000_000), (Pubkey::new_unique(), 1_500_000_000)];
+
+        let per_wallet = record(Some(1_000_000_000), None).overspend(&buys).unwrap();
+        assert!(per_wallet.contains(&buys[1].0.to_string()));
+        assert!(!per_wallet.contains(&buys[0].0.to_string()));
+        assert!(record(None, Some(1_500_000_000)).overspend(&buys).unwrap().contains("total cap"));
+    }
+}
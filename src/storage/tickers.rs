@@ -0,0 +1,15 @@
+use std::collections::HashMap;
+
+use super::StorageError;
+
+static TICKERS_FILE: &str = "tickers.json";
+
+/// Token symbols from the metadata, keyed by mint. A symbol never changes, so each mint only has
+/// to be analyzed once.
+pub fn load_tickers() -> Result<HashMap<String, String>, StorageError> {
+    super::load(TICKERS_FILE)
+}
+
+pub fn save_tickers(tickers: &HashMap<String, String>) -> Result<(), StorageError> {
+    super::save(TICKERS_FILE, tickers)
+}
@@ -0,0 +1,20 @@
+use solana_sdk::pubkey::Pubkey;
+
+use super::StorageError;
+
+static TOKEN_CHECKS_FILE: &str = "token_checks.json";
+
+/// Mints whose safety report was shown and accepted before the first buy.
+pub fn is_acknowledged(mint_id: &Pubkey) -> Result<bool, StorageError> {
+    let acknowledged: Vec<String> = super::load(TOKEN_CHECKS_FILE)?;
+    Ok(acknowledged.contains(&mint_id.to_string()))
+}
+
+pub fn acknowledge(mint_id: &Pubkey) -> Result<(), StorageError> {
+    let mut acknowledged: Vec<String> = super::load(TOKEN_CHECKS_FILE)?;
+    if !acknowledged.contains(&mint_id.to_string()) {
+        acknowledged.push(mint_id.to_string());
+    }
+
+    super::save(TOKEN_CHECKS_FILE, &acknowledged)
+}
@@ -0,0 +1,406 @@
+use serde::Deserialize;
+use serde::Serialize;
+
+use super::transactions::unix_timestamp;
+use super::StorageError;
+
+pub(super) static TRADE_RULES_FILE: &str = "trade_rules.json";
+pub(super) static TRADE_RULE_LOG_FILE: &str = "trade_rule_log.json";
+static TRADE_RULE_LOG_LENGTH: usize = 500;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum RuleTrigger {
+    /// Fires once the price reaches `multiple` times the entry price.
+    TakeProfit { multiple: f64 },
+    /// Fires once the price falls `drawdown` below the entry price, between 0 and 1.
+    StopLoss { drawdown: f64 }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TradeRule {
+    /// Unique within the project, so a rule can be found again after others were removed.
+    pub id: u32,
+    pub trigger: RuleTrigger,
+    /// Share of the position held when the rule fires to sell, between 0 and 1.
+    pub sell_share: f64,
+    /// Rules fire once and stay fired until re-armed.
+    pub fired: bool
+}
+
+impl TradeRule {
+    pub fn new(id: u32, trigger: RuleTrigger, sell_share: f64) -> Self {
+        Self { id, trigger, sell_share, fired: false }
+    }
+
+    pub fn is_triggered(&self, entry_price: f64, price: f64) -> bool {
+        match self.trigger {
+            RuleTrigger::TakeProfit { multiple } => price >= entry_price * multiple,
+            RuleTrigger::StopLoss { drawdown } => price <= entry_price * (1.0 - drawdown)
+        }
+    }
+}
+
+impl ToString for TradeRule {
+    fn to_string(&self) -> String {
+        let sell = if self.sell_share >= 1.0 {
+            "sell all".to_string()
+        } else {
+            format!("sell {}%", self.sell_share * 100.0)
+        };
+
+        match self.trigger {
+            RuleTrigger::TakeProfit { multiple } => format!("{} at {}x entry", sell, multiple),
+            RuleTrigger::StopLoss { drawdown } => format!("{} at -{}% from entry", sell, drawdown * 100.0)
+        }
+    }
+}
+
+/// Rules that fired together on a single price update.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RuleFiring {
+    /// Ids of the rules that fired.
+    pub rules: Vec<u32>,
+    /// Combined share of the position to sell, each rule sells its share of what is left.
+    pub sell_share: f64
+}
+
+/// The take profit and stop loss rules of a single project.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ProjectRules {
+    pub project_id: i32,
+    pub mint: String,
+    /// Price of one whole token in SOL the triggers are measured against.
+    pub entry_price: f64,
+    pub enabled: bool,
+    pub rules: Vec<TradeRule>
+}
+
+impl ProjectRules {
+    pub fn new(project_id: i32, mint: String, entry_price: f64) -> Self {
+        Self { project_id, mint, entry_price, enabled: true, rules: vec![] }
+    }
+
+    pub fn add_rule(&mut self, trigger: RuleTrigger, sell_share: f64) {
+        let id = self.rules.iter().map(|rule| rule.id).max().unwrap_or(0) + 1;
+        self.rules.push(TradeRule::new(id, trigger, sell_share));
+    }
+
+    pub fn find_rule(&self, id: u32) -> Option<&TradeRule> {
+        self.rules.iter().find(|rule| rule.id == id)
+    }
+
+    /// Enabled with at least one rule left to fire.
+    pub fn is_armed(&self) -> bool {
+        self.enabled && self.entry_price > 0.0 && self.rules.iter().any(|rule| !rule.fired)
+    }
+
+    /// Marks every armed rule triggered at `price` as fired and returns them, or `None` when
+    /// nothing fired.
+    pub fn evaluate(&mut self, price: f64) -> Option<RuleFiring> {
+        if !self.is_armed() {
+            return None
+        }
+
+        let entry_price = self.entry_price;
+        let mut firing = RuleFiring { rules: vec![], sell_share: 0.0 };
+        let mut kept = 1.0;
+        for rule in self.rules.iter_mut() {
+            if rule.fired || !rule.is_triggered(entry_price, price) {
+                continue;
+            }
+
+            rule.fired = true;
+            kept *= 1.0 - rule.sell_share.clamp(0.0, 1.0);
+            firing.rules.push(rule.id);
+        }
+
+        if firing.rules.is_empty() {
+            return None
+        }
+
+        firing.sell_share = 1.0 - kept;
+        Some(firing)
+    }
+}
+
+/// Tokens moved from one sniper to another ahead of a partial sell.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TokenMove {
+    pub from: String,
+    pub to: String,
+    pub amount: u64
+}
+
+/// How to sell part of a position when the backend only sells whole wallets. The moves run first
+/// so the wallets in `sells` hold exactly the share once they are sold.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SellPlan {
+    pub moves: Vec<TokenMove>,
+    pub sells: Vec<String>
+}
+
+/// Plans the sell of `share` of the position held in `wallets`. Wallets that fit in the share are
+/// sold whole, largest first, and the rest of the share is moved into one of them. Without such a
+/// wallet the rest is split off into `empty`, a funded sniper holding none of the tokens, or the
+/// surplus of a wallet is parked in another holder. `None` when the position sits in a single
+/// wallet and no sniper is left to split it with.
+pub fn plan_wallet_sells(wallets: &[(String, u64)], empty: &[String], share: f64) -> Option<SellPlan> {
+    let mut wallets: Vec<&(String, u64)> = wallets.iter().filter(|(_, amount)| *amount > 0).collect();
+    wallets.sort_by(|a, b| b.1.cmp(&a.1));
+
+    let total: u64 = wallets.iter().map(|(_, amount)| amount).sum();
+    let target = ((total as f64 * share.clamp(0.0, 1.0)).round() as u64).min(total);
+
+    let mut plan = SellPlan::default();
+    let mut remaining = target;
+    let mut kept = vec![];
+    for (wallet, amount) in wallets {
+        if *amount <= remaining {
+            remaining -= amount;
+            plan.sells.push(wallet.clone());
+        } else {
+            kept.push((wallet, *amount));
+        }
+    }
+
+    if remaining == 0 {
+        return Some(plan)
+    }
+
+    // Every kept wallet holds more than the rest of the share, the smallest one gives it up
+    let (source, amount) = kept.pop()?;
+    if let Some(sold) = plan.sells.first() {
+        plan.moves.push(TokenMove { from: source.clone(), to: sold.clone(), amount: remaining });
+    } else if let Some(split) = empty.first() {
+        plan.moves.push(TokenMove { from: source.clone(), to: split.clone(), amount: remaining });
+        plan.sells.push(split.clone());
+    } else if let Some((holder, _)) = kept.first() {
+        plan.moves.push(TokenMove { from: source.clone(), to: (*holder).clone(), amount: amount - remaining });
+        plan.sells.push(source.clone());
+    } else {
+        return None
+    }
+
+    Some(plan)
+}
+
+/// A fired rule and what came of it.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RuleEvent {
+    pub timestamp: u64,
+    pub project_id: i32,
+    pub rules: Vec<String>,
+    pub price: f64,
+    pub signatures: Vec<String>,
+    pub error: Option<String>
+}
+
+impl RuleEvent {
+    pub fn new(project_id: i32, rules: Vec<String>, price: f64) -> Self {
+        Self { timestamp: unix_timestamp(), project_id, rules, price, signatures: vec![], error: None }
+    }
+}
+
+pub fn load_trade_rules() -> Result<Vec<ProjectRules>, StorageError> {
+    super::load(TRADE_RULES_FILE)
+}
+
+pub fn find_project_rules(project_id: i32) -> Result<Option<ProjectRules>, StorageError> {
+    Ok(load_trade_rules()?.into_iter().find(|rules| rules.project_id == project_id))
+}
+
+pub fn save_project_rules(project_rules: &ProjectRules) -> Result<(), StorageError> {
+    let mut rules = load_trade_rules()?;
+    rules.retain(|saved| saved.project_id != project_rules.project_id);
+    rules.push(project_rules.clone());
+
+    super::save(TRADE_RULES_FILE, &rules)
+}
+
+/// Marks rules as fired on the stored copy, so edits made in the meantime are kept. Rules removed
+/// in the meantime are skipped.
+pub fn mark_rules_fired(project_id: i32, ids: &[u32]) -> Result<(), StorageError> {
+    let mut rules = load_trade_rules()?;
+    if let Some(project_rules) = rules.iter_mut().find(|rules| rules.project_id == project_id) {
+        for rule in project_rules.rules.iter_mut().filter(|rule| ids.contains(&rule.id)) {
+            rule.fired = true;
+        }
+    }
+
+    super::save(TRADE_RULES_FILE, &rules)
+}
+
+pub fn load_rule_log() -> Result<Vec<RuleEvent>, StorageError> {
+    super::load(TRADE_RULE_LOG_FILE)
+}
+
+pub fn log_rule_event(event: RuleEvent) -> Result<(), StorageError> {
+    let mut log = load_rule_log()?;
+    log.push(event);
+    if log.len() > TRADE_RULE_LOG_LENGTH {
+        log.drain(0..log.len() - TRADE_RULE_LOG_LENGTH);
+    }
+
+    super::save(TRADE_RULE_LOG_FILE, &log)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(rules: Vec<(RuleTrigger, f64)>) -> ProjectRules {
+        let mut project_rules = ProjectRules::new(1, "mint".to_string(), 1.0);
+        for (trigger, sell_share) in rules {
+            project_rules.add_rule(trigger, sell_share);
+        }
+
+        project_rules
+    }
+
+    fn take_profit_and_stop_loss() -> ProjectRules {
+        rules(vec![
+            (RuleTrigger::TakeProfit { multiple: 2.0 }, 0.5),
+            (RuleTrigger::StopLoss { drawdown: 0.4 }, 1.0)
+        ])
+    }
+
+    /// Feeds a price series through the rules and returns the step and firing of every trigger.
+    fn simulate(project_rules: &mut ProjectRules, prices: &[f64]) -> Vec<(usize, RuleFiring)> {
+        prices.iter()
+            .enumerate()
+            .filter_map(|(step, price)| project_rules.evaluate(*price).map(|firing| (step, firing)))
+            .collect()
+    }
+
+    #[test]
+    fn takes_profit_once_then_stops_out() {
+        let mut project_rules = take_profit_and_stop_loss();
+        let firings = simulate(&mut project_rules, &[1.0, 1.5, 2.1, 2.5, 1.8, 1.0, 0.7, 0.59, 0.3]);
+
+        assert_eq!(firings, vec![
+            (2, RuleFiring { rules: vec![1], sell_share: 0.5 }),
+            (7, RuleFiring { rules: vec![2], sell_share: 1.0 })
+        ]);
+        assert!(!project_rules.is_armed());
+    }
+
+    #[test]
+    fn stop_loss_fires_without_take_profit() {
+        let mut project_rules = take_profit_and_stop_loss();
+        let firings = simulate(&mut project_rules, &[1.0, 0.9, 0.55, 0.2, 3.0]);
+
+        assert_eq!(firings, vec![
+            (2, RuleFiring { rules: vec![2], sell_share: 1.0 }),
+            (4, RuleFiring { rules: vec![1], sell_share: 0.5 })
+        ]);
+    }
+
+    #[test]
+    fn combines_rules_crossed_by_one_jump() {
+        let mut project_rules = rules(vec![
+            (RuleTrigger::TakeProfit { multiple: 2.0 }, 0.5),
+            (RuleTrigger::TakeProfit { multiple: 3.0 }, 0.5)
+        ]);
+        let firings = simulate(&mut project_rules, &[1.0, 3.5]);
+
+        assert_eq!(firings, vec![(1, RuleFiring { rules: vec![1, 2], sell_share: 0.75 })]);
+    }
+
+    #[test]
+    fn ignores_disabled_and_unpriced_rules() {
+        let mut disabled = ProjectRules { enabled: false, ..take_profit_and_stop_loss() };
+        assert!(simulate(&mut disabled, &[1.0, 5.0, 0.1]).is_empty());
+
+        let mut unpriced = ProjectRules { entry_price: 0.0, ..take_profit_and_stop_loss() };
+        assert!(simulate(&mut unpriced, &[1.0, 5.0, 0.1]).is_empty());
+    }
+
+    #[test]
+    fn rearmed_rule_fires_again() {
+        let mut project_rules = take_profit_and_stop_loss();
+        assert!(project_rules.evaluate(2.0).is_some());
+        assert!(project_rules.evaluate(2.0).is_none());
+
+        project_rules.rules[0].fired = false;
+        assert_eq!(project_rules.evaluate(2.0), Some(RuleFiring { rules: vec![1], sell_share: 0.5 }));
+    }
+
+    #[test]
+    fn rule_ids_survive_removals() {
+        let mut project_rules = take_profit_and_stop_loss();
+        project_rules.rules.remove(0);
+        project_rules.add_rule(RuleTrigger::TakeProfit { multiple: 3.0 }, 1.0);
+
+        let ids: Vec<u32> = project_rules.rules.iter().map(|rule| rule.id).collect();
+        assert_eq!(ids, vec![2, 3]);
+        assert_eq!(project_rules.evaluate(0.5), Some(RuleFiring { rules: vec![2], sell_share: 1.0 }));
+    }
+
+    fn wallets(amounts: &[(&str, u64)]) -> Vec<(String, u64)> {
+        amounts.iter().map(|(wallet, amount)| (wallet.to_string(), *amount)).collect()
+    }
+
+    fn token_move(from: &str, to: &str, amount: u64) -> TokenMove {
+        TokenMove { from: from.to_string(), to: to.to_string(), amount }
+    }
+
+    fn sells(wallets: &[&str]) -> Vec<String> {
+        wallets.iter().map(|wallet| wallet.to_string()).collect()
+    }
+
+    /// Balances of every wallet after the moves of `plan` ran and its sells went through.
+    fn sold_amount(wallets: &[(String, u64)], plan: &SellPlan) -> u64 {
+        let mut balances: std::collections::HashMap<String, u64> = wallets.iter().cloned().collect();
+        for token_move in plan.moves.iter() {
+            *balances.get_mut(&token_move.from).unwrap() -= token_move.amount;
+            *balances.entry(token_move.to.clone()).or_default() += token_move.amount;
+        }
+
+        plan.sells.iter().map(|wallet| balances[wallet]).sum()
+    }
+
+    #[test]
+    fn sells_whole_wallets_that_fit() {
+        let wallets = wallets(&[("a", 20), ("b", 50), ("c", 0), ("d", 30)]);
+
+        assert_eq!(plan_wallet_sells(&wallets, &[], 0.5), Some(SellPlan { moves: vec![], sells: sells(&["b"]) }));
+        assert_eq!(plan_wallet_sells(&wallets, &[], 0.8), Some(SellPlan { moves: vec![], sells: sells(&["b", "d"]) }));
+        assert_eq!(plan_wallet_sells(&wallets, &[], 1.0), Some(SellPlan { moves: vec![], sells: sells(&["b", "d", "a"]) }));
+        assert_eq!(plan_wallet_sells(&wallets, &[], 0.0), Some(SellPlan::default()));
+    }
+
+    #[test]
+    fn moves_the_rest_into_a_sold_wallet() {
+        let wallets = wallets(&[("a", 20), ("b", 50), ("d", 30)]);
+        let plan = plan_wallet_sells(&wallets, &[], 0.6).unwrap();
+
+        assert_eq!(plan, SellPlan { moves: vec![token_move("a", "b", 10)], sells: sells(&["b"]) });
+        assert_eq!(sold_amount(&wallets, &plan), 60);
+    }
+
+    #[test]
+    fn splits_a_single_wallet_into_an_empty_sniper() {
+        let wallets = wallets(&[("a", 100)]);
+        let plan = plan_wallet_sells(&wallets, &["e".to_string()], 0.5).unwrap();
+
+        assert_eq!(plan, SellPlan { moves: vec![token_move("a", "e", 50)], sells: sells(&["e"]) });
+        assert_eq!(sold_amount(&wallets, &plan), 50);
+    }
+
+    #[test]
+    fn parks_the_surplus_in_another_holder() {
+        let wallets = wallets(&[("a", 60), ("b", 40)]);
+        let plan = plan_wallet_sells(&wallets, &[], 0.25).unwrap();
+
+        assert_eq!(plan, SellPlan { moves: vec![token_move("b", "a", 15)], sells: sells(&["b"]) });
+        assert_eq!(sold_amount(&wallets, &plan), 25);
+    }
+
+    #[test]
+    fn refuses_to_split_a_lone_wallet() {
+        let wallets = wallets(&[("a", 100)]);
+
+        assert_eq!(plan_wallet_sells(&wallets, &[], 0.5), None);
+        assert_eq!(plan_wallet_sells(&wallets, &[], 1.0), Some(SellPlan { moves: vec![], sells: sells(&["a"]) }));
+    }
+}
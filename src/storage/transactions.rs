@@ -0,0 +1,102 @@
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use serde::Deserialize;
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+
+use crate::chain::confirm::ConfirmationStatus;
+
+use super::StorageError;
+
+pub(super) static TRANSACTIONS_FILE: &str = "transactions.json";
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TransactionRecord {
+    pub signature: String,
+    pub action: String,
+    pub timestamp: u64,
+    pub status: ConfirmationStatus,
+    pub project_id: Option<i32>,
+    pub wallet: Option<String>,
+    pub lamports: Option<u64>
+}
+
+impl TransactionRecord {
+    pub fn new(action: &str, signature: Signature) -> Self {
+        Self {
+            signature: signature.to_string(),
+            action: action.to_string(),
+            timestamp: unix_timestamp(),
+            status: ConfirmationStatus::Pending,
+            project_id: None,
+            wallet: None,
+            lamports: None
+        }
+    }
+    pub fn with_project(mut self, project_id: i32) -> Self {
+        self.project_id = Some(project_id);
+
+        self
+    }
+    pub fn with_wallet(mut self, wallet: Pubkey) -> Self {
+        self.wallet = Some(wallet.to_string());
+
+        self
+    }
+    pub fn with_lamports(mut self, lamports: u64) -> Self {
+        self.lamports = Some(lamports);
+
+        self
+    }
+    /// Anything not known to have failed, unconfirmed transactions may still have landed.
+    pub fn succeeded(&self) -> bool {
+        !matches!(self.status, ConfirmationStatus::Failed { .. })
+    }
+    pub fn is_confirmed(&self) -> bool {
+        matches!(self.status, ConfirmationStatus::Confirmed | ConfirmationStatus::Finalized)
+    }
+}
+
+pub fn load_transactions() -> Result<Vec<TransactionRecord>, StorageError> {
+    super::load(TRANSACTIONS_FILE)
+}
+
+pub fn record_transactions(records: &[TransactionRecord]) -> Result<(), StorageError> {
+    let mut transactions = load_transactions()?;
+    transactions.extend_from_slice(records);
+
+    super::save(TRANSACTIONS_FILE, &transactions)
+}
+
+pub fn save_transactions(records: &[TransactionRecord]) -> Result<(), StorageError> {
+    super::save(TRANSACTIONS_FILE, &records)
+}
+
+/// SOL that went into and came out of a project according to the recorded buys and sells.
+pub struct ProjectFlows {
+    pub spent: u64,
+    pub received: u64,
+    /// Sells whose proceeds are unknown, the flows are incomplete while this is non-zero.
+    pub unpriced_sells: usize
+}
+
+pub fn project_flows(records: &[TransactionRecord], project_id: i32) -> ProjectFlows {
+    let mut flows = ProjectFlows { spent: 0, received: 0, unpriced_sells: 0 };
+
+    for record in records.iter().filter(|record| record.project_id == Some(project_id) && record.succeeded()) {
+        match (record.action.as_str(), record.lamports) {
+            ("buy" | "auto_buy" | "snipe", Some(lamports)) => flows.spent += lamports,
+            ("sell" | "auto_sell", Some(lamports)) => flows.received += lamports,
+            ("sell" | "auto_sell", None) => flows.unpriced_sells += 1,
+            _ => {}
+        }
+    }
+
+    flows
+}
+
+pub fn unix_timestamp() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|duration| duration.as_secs()).unwrap_or(0)
+}